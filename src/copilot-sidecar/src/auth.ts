@@ -0,0 +1,34 @@
+import { timingSafeEqual } from 'crypto';
+import type { Request, Response, NextFunction } from 'express';
+
+// Per-launch token generated by the desktop app (NAIDE_SIDECAR_TOKEN).
+// Without it, e.g. when the sidecar is started by hand with `npm run dev`, requests are not checked.
+const SIDECAR_TOKEN = process.env.NAIDE_SIDECAR_TOKEN || '';
+
+export function isAuthEnabled(): boolean {
+  return SIDECAR_TOKEN.length > 0;
+}
+
+// Constant-time comparison against the launch token
+export function isValidToken(token: string | null | undefined): boolean {
+  if (!isAuthEnabled()) {
+    return true;
+  }
+  if (!token) {
+    return false;
+  }
+  const expected = Buffer.from(SIDECAR_TOKEN);
+  const provided = Buffer.from(token);
+  return provided.length === expected.length && timingSafeEqual(provided, expected);
+}
+
+// Express middleware: every request needs "Authorization: Bearer <token>"
+export function requireToken(req: Request, res: Response, next: NextFunction) {
+  const header = req.headers.authorization || '';
+  const token = header.startsWith('Bearer ') ? header.slice('Bearer '.length) : null;
+  if (!isValidToken(token)) {
+    console.warn(`[Sidecar] Rejected unauthenticated request: ${req.method} ${req.path}`);
+    return res.status(401).json({ error: 'Unauthorized' });
+  }
+  next();
+}
@@ -9,6 +9,7 @@ import { z } from 'zod';
 import { initializeLogger } from './logger.js';
 import { StatusEventEmitter, createStatusWebSocketServer } from './statusEvents.js';
 import { proxyServer } from './proxy.js';
+import { isAuthEnabled, requireToken } from './auth.js';
 
 const __filename = fileURLToPath(import.meta.url);
 const __dirname = dirname(__filename);
@@ -63,8 +64,13 @@ app.use(cors({
 
 app.use(express.json());
 
-// Port for the sidecar API
-const PORT = 3001;
+// Every API call must carry the per-launch token (CORS preflights are answered above)
+app.use(requireToken);
+
+// Port for the sidecar API, chosen by the desktop app; 3001 when started by hand
+const PORT = Number(process.env.NAIDE_SIDECAR_PORT) || 3001;
+// Loopback only - the API is for the desktop app on this machine
+const HOST = '127.0.0.1';
 
 // Track Copilot client
 let copilotClient: CopilotClient | null = null;
@@ -117,6 +123,28 @@ async function initializeCopilot(): Promise<{ success: boolean; error?: string }
   }
 }
 
+// Size limits for system prompt files, shared with the desktop app's prompt validation
+// (src-tauri/src/prompt_docs.rs reads the same file). Files over a limit are not sent to Copilot.
+interface PromptLimits {
+  maxSystemPromptBytes: number;         // Any single *.system.md file
+  maxCombinedSystemPromptBytes: number; // base.system.md plus one mode prompt
+}
+
+const PROMPT_LIMITS: PromptLimits = JSON.parse(
+  readFileSync(join(__dirname, '..', 'prompt-limits.json'), 'utf-8')
+);
+
+// Read a prompt file if it is within the per-file limit
+function readPromptFile(path: string): string | null {
+  const content = readFileSync(path, 'utf-8');
+  const bytes = Buffer.byteLength(content, 'utf-8');
+  if (bytes > PROMPT_LIMITS.maxSystemPromptBytes) {
+    console.error(`[Sidecar] Skipping ${path}: ${bytes} bytes, over the ${PROMPT_LIMITS.maxSystemPromptBytes} byte limit`);
+    return null;
+  }
+  return content;
+}
+
 // Load system prompts from the naide app repository (not user's project)
 function loadSystemPrompts(mode: string): string {
   // System prompts are in the naide app repository, not the user's project
@@ -129,17 +157,28 @@ function loadSystemPrompts(mode: string): string {
     const modePath = join(promptsDir, `${mode.toLowerCase()}.system.md`);
     
     let systemPrompt = '';
+    let baseBytes = 0;
     
     if (existsSync(basePath)) {
-      systemPrompt += readFileSync(basePath, 'utf-8') + '\n\n';
-      console.log(`[Sidecar] Loaded base system prompt from: ${basePath}`);
+      const base = readPromptFile(basePath);
+      if (base !== null) {
+        baseBytes = Buffer.byteLength(base, 'utf-8');
+        systemPrompt += base + '\n\n';
+        console.log(`[Sidecar] Loaded base system prompt from: ${basePath}`);
+      }
     } else {
       console.warn(`[Sidecar] Base system prompt not found at: ${basePath}`);
     }
     
     if (existsSync(modePath)) {
-      systemPrompt += readFileSync(modePath, 'utf-8') + '\n\n';
-      console.log(`[Sidecar] Loaded ${mode} system prompt from: ${modePath}`);
+      const modePrompt = readPromptFile(modePath);
+      const combinedBytes = baseBytes + Buffer.byteLength(modePrompt ?? '', 'utf-8');
+      if (modePrompt !== null && combinedBytes > PROMPT_LIMITS.maxCombinedSystemPromptBytes) {
+        console.error(`[Sidecar] Skipping ${modePath}: base and mode prompts are ${combinedBytes} bytes, over the ${PROMPT_LIMITS.maxCombinedSystemPromptBytes} byte limit`);
+      } else if (modePrompt !== null) {
+        systemPrompt += modePrompt + '\n\n';
+        console.log(`[Sidecar] Loaded ${mode} system prompt from: ${modePath}`);
+      }
     } else {
       console.warn(`[Sidecar] Mode system prompt not found at: ${modePath}`);
     }
@@ -1482,6 +1521,102 @@ app.post('/api/copilot/chat', async (req, res) => {
   });
 });
 
+// =============================================================================
+// Chat metadata - title, summary and tags for an archived chat session
+// =============================================================================
+
+const METADATA_TIMEOUT_MS = 120000;
+
+const METADATA_SYSTEM_PROMPT = `You describe chat sessions between a developer and the Naide assistant.
+Reply with a single JSON object and nothing else:
+{"title": string, "summary": string, "tags": string[]}
+- title: at most 8 words, no quotes or trailing punctuation
+- summary: 1-3 sentences on what was discussed, decided or built
+- tags: 2-6 short lowercase topics (technologies, features, areas of the app)`;
+
+// Pull the JSON object out of a reply that may be wrapped in a code fence or prose
+function parseMetadataReply(reply: string): { title?: unknown; summary?: unknown; tags?: unknown } {
+  const start = reply.indexOf('{');
+  const end = reply.lastIndexOf('}');
+  if (start === -1 || end <= start) {
+    throw new Error('Reply did not contain a JSON object');
+  }
+  return JSON.parse(reply.slice(start, end + 1));
+}
+
+app.post('/api/chat/metadata', async (req, res) => {
+  const { messages, truncated, mode, model } = req.body;
+  
+  if (!Array.isArray(messages) || messages.length === 0) {
+    return res.status(400).json({ error: 'messages is required' });
+  }
+  
+  console.log(`[Sidecar] Chat metadata request - ${messages.length} messages, model: ${model || '(default)'}`);
+  
+  if (!copilotReady || !copilotClient) {
+    const initResult = await initializeCopilot();
+    if (!initResult.success) {
+      return res.status(400).json({ error: initResult.error });
+    }
+  }
+  
+  const transcript = messages
+    .map((m: { role: string; content: string }) => `${m.role === 'user' ? 'User' : 'Assistant'}: ${m.content}`)
+    .join('\n\n');
+  const prompt = `Describe this ${mode || 'Planning'} mode chat session${truncated ? ' (long messages were shortened)' : ''}:\n\n${transcript}`;
+  
+  try {
+    const sessionConfig: any = {
+      systemMessage: { content: METADATA_SYSTEM_PROMPT },
+    };
+    if (model) {
+      sessionConfig.model = model;
+    }
+    const session = await copilotClient!.createSession(sessionConfig);
+    
+    const replyPromise = new Promise<string>((resolve, reject) => {
+      const chunks: string[] = [];
+      const unsubscribeFns: Array<() => void> = [];
+      const finish = () => {
+        clearTimeout(timeoutHandle);
+        unsubscribeFns.forEach(fn => fn());
+        session.destroy().catch(err => console.error('[Sidecar] Error destroying session:', err));
+      };
+      const timeoutHandle = setTimeout(() => {
+        finish();
+        reject(new Error('Metadata generation timed out'));
+      }, METADATA_TIMEOUT_MS);
+      
+      unsubscribeFns.push(session.on('assistant.message', (event) => {
+        if (event.data.content) {
+          chunks.push(event.data.content);
+        }
+      }));
+      unsubscribeFns.push(session.on('session.idle', () => {
+        finish();
+        resolve(chunks.join(''));
+      }));
+      unsubscribeFns.push(session.on('session.error', (event) => {
+        finish();
+        reject(new Error(event.data.message || 'Copilot session error'));
+      }));
+    });
+    
+    await session.send({ prompt });
+    const parsed = parseMetadataReply(await replyPromise);
+    
+    return res.json({
+      title: typeof parsed.title === 'string' ? parsed.title : '',
+      summary: typeof parsed.summary === 'string' ? parsed.summary : '',
+      tags: Array.isArray(parsed.tags) ? parsed.tags.filter((t): t is string => typeof t === 'string') : [],
+    });
+  } catch (error) {
+    console.error('[Sidecar] Failed to generate chat metadata:', error);
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    return res.status(500).json({ error: `Failed to generate chat metadata: ${errorMessage}` });
+  }
+});
+
 // Health check endpoint
 app.get('/health', (req, res) => {
   res.json({ status: 'ok', copilotReady });
@@ -1541,9 +1676,12 @@ const server = createServer(app);
 // Create WebSocket server for status events
 createStatusWebSocketServer(server, statusEmitter);
 
-server.listen(PORT, async () => {
-  console.log(`[Sidecar] Copilot sidecar running on http://localhost:${PORT}`);
-  console.log(`[Sidecar] WebSocket server ready at ws://localhost:${PORT}/api/status`);
+server.listen(PORT, HOST, async () => {
+  console.log(`[Sidecar] Copilot sidecar running on http://${HOST}:${PORT}`);
+  console.log(`[Sidecar] WebSocket server ready at ws://${HOST}:${PORT}/api/status`);
+  if (!isAuthEnabled()) {
+    console.warn('[Sidecar] NAIDE_SIDECAR_TOKEN is not set - requests are not authenticated');
+  }
   
   // Initialize Copilot on startup
   const initResult = await initializeCopilot();
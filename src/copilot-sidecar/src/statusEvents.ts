@@ -1,5 +1,6 @@
 import { EventEmitter } from 'events';
 import { WebSocket, WebSocketServer } from 'ws';
+import { isValidToken } from './auth.js';
 
 export type StatusEventType = 'file_read' | 'file_write' | 'analysis' | 'build' | 'test' | 'api_call' | 'session_complete';
 export type StatusEventStatus = 'in_progress' | 'complete' | 'error';
@@ -73,9 +74,17 @@ export function createStatusWebSocketServer(
 
   // Handle WebSocket upgrade requests
   server.on('upgrade', (request: any, socket: any, head: any) => {
-    const pathname = new URL(request.url, `http://${request.headers.host}`).pathname;
+    const url = new URL(request.url, `http://${request.headers.host}`);
     
-    if (pathname === '/api/status') {
+    // Browsers cannot set headers on WebSocket connections, so the token comes in the query string
+    if (!isValidToken(url.searchParams.get('token'))) {
+      console.warn('[StatusWS] Rejected unauthenticated connection');
+      socket.write('HTTP/1.1 401 Unauthorized\r\n\r\n');
+      socket.destroy();
+      return;
+    }
+    
+    if (url.pathname === '/api/status') {
       wss.handleUpgrade(request, socket, head, (ws: WebSocket) => {
         wss.emit('connection', ws, request);
       });
@@ -9,6 +9,8 @@ use regex::Regex;
 use tauri::Emitter;
 use serde_json;
 
+use crate::ignore_rules::ProjectIgnore;
+
 /// Strip ANSI escape codes from a string
 /// These codes are used for terminal colorization (e.g., Vite colorizes URLs)
 fn strip_ansi_codes(s: &str) -> String {
@@ -231,75 +233,44 @@ pub fn detect_all_runnable_apps(project_path: &str) -> Result<Vec<AppInfo>, Stri
 
 /// Find all files with a given extension recursively
 fn find_files_with_extension(dir: &Path, extension: &str) -> Result<Vec<PathBuf>, String> {
-    let mut files = Vec::new();
-    
-    if !dir.is_dir() {
-        return Ok(files);
-    }
-    
-    fn walk_dir(dir: &Path, extension: &str, files: &mut Vec<PathBuf>) -> Result<(), String> {
-        for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read dir: {}", e))? {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let path = entry.path();
-            
-            // Skip node_modules, bin, obj folders
-            if let Some(name) = path.file_name() {
-                let name_str = name.to_string_lossy();
-                if name_str == "node_modules" || name_str == "bin" || name_str == "obj" {
-                    continue;
-                }
-            }
-            
-            if path.is_dir() {
-                walk_dir(&path, extension, files)?;
-            } else if let Some(ext) = path.extension() {
-                if ext == extension {
-                    files.push(path);
-                }
-            }
-        }
-        Ok(())
-    }
-    
-    walk_dir(dir, extension, &mut files)?;
-    Ok(files)
+    find_project_files(dir, &|path| path.extension().map(|ext| ext == extension).unwrap_or(false))
 }
 
-/// Find all files with a given name recursively (skipping common irrelevant directories)
+/// Find all files with a given name recursively
 fn find_files_by_name(dir: &Path, filename: &str) -> Result<Vec<PathBuf>, String> {
+    find_project_files(dir, &|path| path.file_name().map(|name| name == filename).unwrap_or(false))
+}
+
+/// Walk a project and collect matching files, skipping anything the project ignores
+/// (.gitignore, .git/info/exclude, .naideignore and the built-in defaults)
+fn find_project_files(dir: &Path, matches: &dyn Fn(&Path) -> bool) -> Result<Vec<PathBuf>, String> {
     let mut files = Vec::new();
     
     if !dir.is_dir() {
         return Ok(files);
     }
     
-    fn walk_dir(dir: &Path, filename: &str, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    fn walk_dir(dir: &Path, rules: &ProjectIgnore, matches: &dyn Fn(&Path) -> bool, files: &mut Vec<PathBuf>) -> Result<(), String> {
         for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read dir: {}", e))? {
             let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
             let path = entry.path();
+            let is_dir = path.is_dir();
             
-            // Skip irrelevant directories
-            if let Some(name) = path.file_name() {
-                let name_str = name.to_string_lossy();
-                if name_str == "node_modules" || name_str == ".git" || name_str == "bin" 
-                    || name_str == "obj" || name_str == "dist" || name_str == "build" 
-                    || name_str == "out" || name_str == ".naide" {
-                    continue;
-                }
+            if rules.is_ignored(&path, is_dir) {
+                continue;
             }
             
-            if path.is_dir() {
-                walk_dir(&path, filename, files)?;
-            } else if let Some(name) = path.file_name() {
-                if name == filename {
-                    files.push(path);
-                }
+            if is_dir {
+                walk_dir(&path, rules, matches, files)?;
+            } else if matches(&path) {
+                files.push(path);
             }
         }
         Ok(())
     }
     
-    walk_dir(dir, filename, &mut files)?;
+    let rules = ProjectIgnore::load(dir);
+    walk_dir(dir, &rules, matches, &mut files)?;
     Ok(files)
 }
 
@@ -0,0 +1,249 @@
+use std::time::Duration;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::chat_index;
+use crate::chat_sessions::{
+    chat_sessions_dir, load_session, now_timestamp, validate_session_filename, write_session,
+    ChatDigest, ChatRole, ChatSession, TokenCounts, ACTIVE_SESSION_FILE,
+};
+use crate::settings::read_settings;
+use crate::sidecar_client;
+
+// Rough average for English text and code
+const CHARS_PER_TOKEN: u64 = 4;
+// What is sent to the sidecar: long pastes are cut, and very long sessions keep their start
+const MAX_MESSAGE_CHARS: usize = 2000;
+const MAX_TRANSCRIPT_CHARS: usize = 24000;
+const MAX_GENERATED_TITLE_CHARS: usize = 80;
+const MAX_SUMMARY_CHARS: usize = 1000;
+const MAX_TAGS: usize = 8;
+const MAX_TAG_CHARS: usize = 32;
+const SIDECAR_TIMEOUT: Duration = Duration::from_secs(180);
+
+// Reply of the sidecar's /api/chat/metadata endpoint
+#[derive(Debug, Deserialize)]
+struct GeneratedMetadata {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Estimated tokens per direction; command output never reaches the model and is not counted.
+/// Deliberately not per model: there is no tokenizer for the Copilot models on this side,
+/// so every session gets the same character-based estimate whichever model it used.
+pub fn count_tokens(session: &ChatSession) -> TokenCounts {
+    let mut counts = TokenCounts::default();
+    for message in &session.messages {
+        match message.role {
+            ChatRole::User | ChatRole::System => counts.input += estimate_tokens(&message.content),
+            ChatRole::Assistant | ChatRole::Tool => {
+                counts.output += estimate_tokens(&message.content);
+                for call in &message.tool_calls {
+                    counts.output += estimate_tokens(&call.arguments.to_string());
+                }
+            }
+            ChatRole::Command => {}
+        }
+    }
+    counts.total = counts.input + counts.output;
+    counts
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// User and assistant messages to summarize, within the transcript budget.
+/// Returns the messages and whether any were cut or left out.
+fn transcript(session: &ChatSession) -> (Vec<Value>, bool) {
+    let mut messages = Vec::new();
+    let mut used = 0;
+    let mut truncated = false;
+
+    for message in &session.messages {
+        if !matches!(message.role, ChatRole::User | ChatRole::Assistant) || message.content.trim().is_empty() {
+            continue;
+        }
+        let room = MAX_TRANSCRIPT_CHARS.saturating_sub(used).min(MAX_MESSAGE_CHARS);
+        if room == 0 {
+            truncated = true;
+            break;
+        }
+        let content = truncate_chars(message.content.trim(), room);
+        truncated |= content.ends_with('…') && !message.content.trim().ends_with('…');
+        used += content.chars().count();
+        let role = if message.role == ChatRole::User { "user" } else { "assistant" };
+        messages.push(json!({ "role": role, "content": content }));
+    }
+    (messages, truncated)
+}
+
+fn clean_title(title: &str) -> Option<String> {
+    let line = title.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line.trim_start_matches('#').trim().trim_matches(|c| c == '"' || c == '\'' || c == '`').trim();
+    if line.is_empty() {
+        return None;
+    }
+    Some(truncate_chars(line, MAX_GENERATED_TITLE_CHARS))
+}
+
+/// Lowercase, hyphenated, unique tags
+fn clean_tags(tags: &[String]) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        let tag: String = tag.split_whitespace().collect::<Vec<_>>().join("-").chars().take(MAX_TAG_CHARS).collect();
+        if !tag.is_empty() && !cleaned.contains(&tag) {
+            cleaned.push(tag);
+        }
+        if cleaned.len() == MAX_TAGS {
+            break;
+        }
+    }
+    cleaned
+}
+
+/// Store generated metadata on a session. A title the user already set is kept.
+fn apply_metadata(session: &mut ChatSession, generated: GeneratedMetadata, model: Option<String>) -> Result<(), String> {
+    let summary = generated.summary.trim();
+    if summary.is_empty() {
+        return Err("The sidecar returned an empty summary".to_string());
+    }
+
+    if session.title.is_none() {
+        session.title = clean_title(&generated.title);
+    }
+    session.digest = Some(ChatDigest {
+        summary: truncate_chars(summary, MAX_SUMMARY_CHARS),
+        tags: clean_tags(&generated.tags),
+        token_counts: count_tokens(session),
+        model,
+        generated_at: now_timestamp(),
+    });
+    Ok(())
+}
+
+// Tauri command: Generate a title, summary and tags for an archived chat session
+// The sidecar writes them with the model selected in settings
+#[tauri::command]
+pub async fn summarize_chat_session(
+    app: tauri::AppHandle,
+    project_path: String,
+    filename: String,
+) -> Result<ChatSession, String> {
+    validate_session_filename(&filename)?;
+    if filename == ACTIVE_SESSION_FILE {
+        return Err("Only archived chat sessions can be summarized".to_string());
+    }
+    let dir = chat_sessions_dir(&project_path);
+    if !dir.join(&filename).is_file() {
+        return Err(format!("Chat session file not found: {}", filename));
+    }
+
+    let session = load_session(&dir, &filename).map_err(|e| e.message())?;
+    let (messages, truncated) = transcript(&session);
+    if messages.is_empty() {
+        return Err("Chat session has no messages to summarize".to_string());
+    }
+
+    let model = read_settings(&app).ok().and_then(|settings| settings.selected_model);
+    let request = json!({
+        "messages": messages,
+        "truncated": truncated,
+        "mode": session.mode,
+        "model": model,
+    });
+    // The model can take minutes; keep the blocking request off the async runtime
+    let reply = tauri::async_runtime::spawn_blocking(move || {
+        sidecar_client::post_json("/api/chat/metadata", &request, SIDECAR_TIMEOUT)
+    })
+    .await
+    .map_err(|e| format!("Failed to request chat metadata: {}", e))??;
+    let generated: GeneratedMetadata = serde_json::from_value(reply)
+        .map_err(|e| format!("Failed to parse generated chat metadata: {}", e))?;
+
+    // Renames and saves made while waiting are kept: the metadata goes onto the session as it is now
+    if !dir.join(&filename).is_file() {
+        return Err(format!("Chat session was removed while summarizing: {}", filename));
+    }
+    let mut session = load_session(&dir, &filename).map_err(|e| e.message())?;
+    apply_metadata(&mut session, generated, model)?;
+    write_session(&dir.join(&filename), &session)?;
+    chat_index::update_index_entry(&project_path, &filename, &session);
+
+    log::info!("Summarized chat session {} ({} estimated tokens)", filename,
+        session.digest.as_ref().map(|d| d.token_counts.total).unwrap_or(0));
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat_sessions::migrate_session;
+
+    fn sample(title: Option<&str>) -> ChatSession {
+        let raw = json!({
+            "id": "s1",
+            "mode": "Planning",
+            "title": title,
+            "messages": [
+                { "id": "u1", "role": "user", "content": "x".repeat(3000), "timestamp": "2025-06-01T10:00:00Z" },
+                { "id": "c1", "role": "command", "content": "y".repeat(400), "command": "ls", "timestamp": "2025-06-01T10:00:01Z" },
+                { "id": "a1", "role": "assistant", "content": "Sure.", "timestamp": "2025-06-01T10:00:02Z",
+                  "toolCalls": [{ "id": "t1", "name": "view", "arguments": { "path": "a" } }] }
+            ]
+        });
+        migrate_session(raw, "s1.json", "2025-06-01T11:00:00Z").unwrap().0
+    }
+
+    #[test]
+    fn test_token_counts_and_transcript() {
+        let session = sample(None);
+        let counts = count_tokens(&session);
+        assert_eq!(counts.input, 750);
+        assert_eq!(counts.output, 2 + 3); // "Sure." and {"path":"a"}
+        assert_eq!(counts.total, counts.input + counts.output);
+
+        let (messages, truncated) = transcript(&session);
+        assert!(truncated);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["content"].as_str().unwrap().chars().count(), MAX_MESSAGE_CHARS + 1);
+        assert_eq!(messages[1]["role"], json!("assistant"));
+    }
+
+    #[test]
+    fn test_apply_metadata_cleans_reply() {
+        let generated = || GeneratedMetadata {
+            title: "\n\"Login page plan\"\nextra".to_string(),
+            summary: "  Planned a login page.  ".to_string(),
+            tags: vec!["Auth".into(), "#auth".into(), "React Router".into(), " ".into()],
+        };
+
+        let mut session = sample(None);
+        apply_metadata(&mut session, generated(), Some("gpt-4.1".to_string())).unwrap();
+        assert_eq!(session.title.as_deref(), Some("Login page plan"));
+        let digest = session.digest.unwrap();
+        assert_eq!(digest.summary, "Planned a login page.");
+        assert_eq!(digest.tags, vec!["auth", "react-router"]);
+        assert_eq!(digest.model.as_deref(), Some("gpt-4.1"));
+
+        // A title chosen by the user wins over the generated one
+        let mut session = sample(Some("My title"));
+        apply_metadata(&mut session, generated(), None).unwrap();
+        assert_eq!(session.title.as_deref(), Some("My title"));
+
+        let empty = GeneratedMetadata { title: String::new(), summary: " ".to_string(), tags: Vec::new() };
+        assert!(apply_metadata(&mut sample(None), empty, None).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+use std::path::PathBuf;
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::chat_sessions::{
+    chat_sessions_dir, load_session, now_timestamp, validate_session_filename, ChatMessage, ChatRole, ChatSession,
+};
+use crate::file_io;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatExportResult {
+    pub path: String,
+    pub format: ExportFormat,
+    pub bytes: usize,
+    pub message_count: usize,
+}
+
+fn role_label(role: ChatRole) -> &'static str {
+    match role {
+        ChatRole::User => "User",
+        ChatRole::Assistant => "Assistant",
+        ChatRole::Command => "Command",
+        ChatRole::System => "System",
+        ChatRole::Tool => "Tool",
+    }
+}
+
+/// Title used for the export heading
+pub fn session_title(session: &ChatSession) -> String {
+    if let Some(title) = &session.title {
+        return title.clone();
+    }
+    let first_line = session.first_user_message().lines().next().unwrap_or("").trim();
+    if first_line.is_empty() {
+        return "Chat session".to_string();
+    }
+    let mut title: String = first_line.chars().take(80).collect();
+    if first_line.chars().count() > 80 {
+        title.push('…');
+    }
+    title
+}
+
+/// Close a code fence left open by a message, so it cannot swallow the rest of the export
+fn close_open_fences(content: &str) -> String {
+    let fences = content.lines()
+        .filter(|line| line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~"))
+        .count();
+    if fences % 2 == 1 {
+        format!("{}\n```", content.trim_end())
+    } else {
+        content.trim_end().to_string()
+    }
+}
+
+/// Markdown of a message without its heading
+fn message_body(message: &ChatMessage) -> String {
+    let mut out = String::new();
+
+    if message.role == ChatRole::Command {
+        if let Some(command) = &message.command {
+            out.push_str(&format!("```sh\n$ {}\n```\n\n", command));
+        }
+        if let Some(status) = &message.command_status {
+            out.push_str(&format!("Status: {}\n\n", status));
+        }
+        return out;
+    }
+
+    out.push_str(&close_open_fences(&message.content));
+    out.push_str("\n\n");
+
+    for call in &message.tool_calls {
+        out.push_str(&format!("**Tool call:** `{}`", call.name));
+        if let Some(status) = &call.status {
+            out.push_str(&format!(" ({})", status));
+        }
+        out.push('\n');
+        if !call.arguments.is_null() {
+            let arguments = serde_json::to_string_pretty(&call.arguments).unwrap_or_default();
+            out.push_str(&format!("\n```json\n{}\n```\n", arguments));
+        }
+        out.push('\n');
+    }
+
+    if !message.attachments.is_empty() {
+        out.push_str("**Attachments:**\n\n");
+        for attachment in &message.attachments {
+            out.push_str(&format!("- {}", attachment.name));
+            if let Some(path) = &attachment.path {
+                out.push_str(&format!(" (`{}`)", path));
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render a session as Markdown; message content is Markdown already and kept as is
+pub fn render_markdown(session: &ChatSession) -> String {
+    let mut out = format!("# {}\n\n", session_title(session));
+    out.push_str(&format!("- Mode: {}\n- Created: {}\n- Updated: {}\n- Messages: {}\n", session.mode, session.created_at, session.updated_at, session.messages.len()));
+    if !session.project_name.is_empty() {
+        out.push_str(&format!("- Project: {}\n", session.project_name));
+    }
+    out.push_str(&format!("- Exported: {}\n", now_timestamp()));
+
+    for message in &session.messages {
+        out.push_str("\n---\n\n");
+        out.push_str(&format!("## {}\n\n_{}_\n\n", role_label(message.role), message.timestamp));
+        out.push_str(&message_body(message));
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Whether a link or image URL is relative or uses http(s) or mailto.
+/// Whitespace and control characters are ignored, as browsers do ("java\tscript:").
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_control()).collect();
+    match url.find([':', '/', '?', '#']) {
+        Some(index) if url[index..].starts_with(':') => {
+            matches!(url[..index].to_ascii_lowercase().as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+/// Markdown to HTML; raw HTML in messages is shown as text, not rendered,
+/// and links or images with other URL schemes (e.g. javascript:) keep only their text
+fn markdown_to_html(markdown: &str) -> String {
+    // Whether each open link or image was dropped, so its end is dropped too
+    let mut dropped = Vec::new();
+    let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS)
+        .filter_map(move |event| match event {
+            Event::Html(raw) | Event::InlineHtml(raw) => Some(Event::Text(raw)),
+            Event::Start(Tag::Link { ref dest_url, .. }) | Event::Start(Tag::Image { ref dest_url, .. }) => {
+                let safe = is_safe_url(dest_url);
+                dropped.push(!safe);
+                safe.then_some(event)
+            }
+            Event::End(TagEnd::Link) | Event::End(TagEnd::Image) => {
+                (!dropped.pop().unwrap_or(false)).then_some(event)
+            }
+            other => Some(other),
+        });
+    let mut out = String::new();
+    html::push_html(&mut out, parser);
+    out
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:860px;margin:2rem auto;padding:0 1rem;color:#1f2328;line-height:1.55}\
+header{border-bottom:1px solid #d0d7de;margin-bottom:1.5rem}\
+header dl{display:grid;grid-template-columns:max-content 1fr;gap:.25rem 1rem;color:#57606a;font-size:.9rem}\
+.message{border:1px solid #d0d7de;border-radius:8px;padding:.75rem 1rem;margin:1rem 0}\
+.message.user{background:#f6f8fa}.message.command{background:#fff8c5}\
+.meta{display:flex;justify-content:space-between;font-size:.8rem;color:#57606a;margin-bottom:.5rem}\
+.role{font-weight:600;text-transform:uppercase;letter-spacing:.04em}\
+pre{background:#0d1117;color:#e6edf3;padding:.75rem;border-radius:6px;overflow-x:auto}\
+code{font-family:ui-monospace,SFMono-Regular,Menlo,monospace;font-size:.9em}\
+table{border-collapse:collapse}td,th{border:1px solid #d0d7de;padding:.25rem .5rem}";
+
+/// Render a session as a standalone HTML page (inline styles, no scripts)
+pub fn render_html(session: &ChatSession) -> String {
+    let title = escape_html(&session_title(session));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        title, HTML_STYLE
+    );
+    out.push_str(&format!(
+        "<header>\n<h1>{}</h1>\n<dl><dt>Mode</dt><dd>{}</dd><dt>Created</dt><dd>{}</dd><dt>Messages</dt><dd>{}</dd></dl>\n</header>\n",
+        title, escape_html(&session.mode), escape_html(&session.created_at), session.messages.len()
+    ));
+
+    for message in &session.messages {
+        let role = role_label(message.role);
+        out.push_str(&format!(
+            "<section class=\"message {}\">\n<div class=\"meta\"><span class=\"role\">{}</span><time>{}</time></div>\n",
+            role.to_lowercase(), role, escape_html(&message.timestamp)
+        ));
+        out.push_str(&markdown_to_html(&message_body(message)));
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Render a session as one JSONL line in the chat fine-tuning format
+/// ({"messages": [{"role", "content"}, ...]}). Command output is not part of the conversation and is left out;
+/// so are tool messages, which the format only accepts with the tool call id they answer, and sessions do not store it.
+pub fn render_jsonl(session: &ChatSession) -> String {
+    let messages: Vec<serde_json::Value> = session.messages.iter()
+        .filter(|m| !m.content.trim().is_empty())
+        .filter_map(|m| {
+            let role = match m.role {
+                ChatRole::User => "user",
+                ChatRole::Assistant => "assistant",
+                ChatRole::System => "system",
+                ChatRole::Command | ChatRole::Tool => return None,
+            };
+            Some(json!({ "role": role, "content": m.content }))
+        })
+        .collect();
+    format!("{}\n", json!({ "messages": messages }))
+}
+
+pub fn render(session: &ChatSession, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => render_markdown(session),
+        ExportFormat::Html => render_html(session),
+        ExportFormat::Jsonl => render_jsonl(session),
+    }
+}
+
+// Tauri command: Export a chat session to Markdown, HTML or JSONL
+// target_path is chosen by the user with the save dialog; the extension is added if missing
+#[tauri::command]
+pub async fn export_chat_session(
+    project_path: String,
+    filename: String,
+    format: ExportFormat,
+    target_path: String,
+) -> Result<ChatExportResult, String> {
+    validate_session_filename(&filename)?;
+    let dir = chat_sessions_dir(&project_path);
+    if !dir.join(&filename).is_file() {
+        return Err(format!("Chat session file not found: {}", filename));
+    }
+
+    let mut target = PathBuf::from(&target_path);
+    if !target.is_absolute() {
+        return Err("Export path must be absolute".to_string());
+    }
+    if target.extension().is_none() {
+        target.set_extension(format.extension());
+    }
+    match target.parent() {
+        Some(parent) if parent.is_dir() => {}
+        _ => return Err("Export folder does not exist".to_string()),
+    }
+    if target.is_dir() {
+        return Err("Export path is a folder".to_string());
+    }
+
+    let session = load_session(&dir, &filename).map_err(|e| e.message())?;
+    let content = render(&session, format);
+    file_io::write_atomic(&target, content.as_bytes())?;
+
+    log::info!("Exported chat session {} as {:?} to {:?}", filename, format, target);
+    Ok(ChatExportResult {
+        path: target.to_string_lossy().to_string(),
+        format,
+        bytes: content.len(),
+        message_count: session.messages.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat_sessions::migrate_session;
+
+    fn sample() -> ChatSession {
+        let raw = json!({
+            "id": "s1",
+            "mode": "Building",
+            "createdAt": "2025-05-01T08:00:00.000Z",
+            "messages": [
+                { "id": "u1", "role": "user", "content": "Add a <b>login</b> page\nwith tests", "timestamp": "2025-05-01T08:00:00.000Z" },
+                { "id": "a1", "role": "assistant", "content": "Here:\n```ts\nexport const x = 1;", "timestamp": "2025-05-01T08:00:10.000Z",
+                  "toolCalls": [{ "id": "t1", "name": "write_file", "arguments": { "path": "src/login.tsx" } }] },
+                { "id": "c1", "role": "command", "content": "(command executed)", "command": "npm test", "commandStatus": "success", "timestamp": "2025-05-01T08:01:00.000Z" },
+                { "id": "t1", "role": "tool", "content": "Wrote src/login.tsx", "timestamp": "2025-05-01T08:01:05.000Z" }
+            ]
+        });
+        migrate_session(raw, "s1.json", "2025-05-01T09:00:00.000Z").unwrap().0
+    }
+
+    #[test]
+    fn test_markdown_keeps_code_blocks() {
+        let markdown = render_markdown(&sample());
+        assert!(markdown.starts_with("# Add a <b>login</b> page\n"));
+        assert!(markdown.contains("```ts\nexport const x = 1;\n```"));
+        assert!(markdown.contains("**Tool call:** `write_file`"));
+        assert!(markdown.contains("```sh\n$ npm test\n```"));
+    }
+
+    #[test]
+    fn test_html_and_jsonl() {
+        let page = render_html(&sample());
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>Add a &lt;b&gt;login&lt;/b&gt; page</title>"));
+        assert!(page.contains("&lt;b&gt;login&lt;/b&gt;"));
+        assert!(!page.contains("<b>login</b>"));
+        assert!(page.contains("<code class=\"language-ts\">"));
+
+        let links = markdown_to_html("[docs](https://example.com) [x](javascript:alert(1)) [y](<JavaScript :x>) ![i](data:text/html,x) [rel](./a.md)");
+        assert!(links.contains("<a href=\"https://example.com\">docs</a>"));
+        assert!(links.contains("<a href=\"./a.md\">rel</a>"));
+        assert!(!links.to_lowercase().contains("script:"));
+        assert!(!links.contains("data:"));
+        assert!(links.contains(" x ") && links.contains(" y "));
+        assert!(!is_safe_url("java\tscript:alert(1)"));
+        assert!(is_safe_url("mailto:a@example.com") && is_safe_url("notes/a.md#top"));
+
+        let jsonl = render_jsonl(&sample());
+        assert_eq!(jsonl.lines().count(), 1);
+        let line: serde_json::Value = serde_json::from_str(jsonl.trim()).unwrap();
+        let messages = line["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["role"], json!("assistant"));
+        assert!(messages.iter().all(|m| m["role"] != json!("tool")));
+    }
+}
@@ -0,0 +1,411 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::chat_index;
+use crate::chat_sessions::{chat_sessions_dir, migrate_session, now_timestamp, session_id_for, write_session, ChatSession};
+
+// Exports larger than this are rejected rather than read into memory
+const MAX_IMPORT_BYTES: u64 = 50 * 1024 * 1024;
+// Chat participant id of the Naide VS Code extension
+const NAIDE_PARTICIPANT_ID: &str = "naide.chat";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportSource {
+    VscodeCopilot, // "Chat: Export Chat..." from VS Code
+    NaideVscode,   // The same export, from conversations with @naide
+    Openai,        // {"messages": [...]}, a bare message list, or JSONL of either
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedSession {
+    pub filename: String,
+    pub title: Option<String>,
+    pub message_count: usize,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatImportResult {
+    pub source: ImportSource,
+    pub imported: Vec<ImportedSession>,
+    pub skipped: Vec<String>, // Reasons for conversations that could not be imported
+}
+
+fn millis_to_timestamp(millis: i64) -> Option<String> {
+    Utc.timestamp_millis_opt(millis).single()
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Timestamp from an RFC 3339 string, or a number of seconds or milliseconds since the epoch
+fn parse_timestamp(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => DateTime::parse_from_rfc3339(text).ok()
+            .map(|t| t.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Millis, true)),
+        Value::Number(number) => {
+            let number = number.as_f64()?;
+            // Values below 10^11 are seconds (until the year 5138)
+            let millis = if number < 1e11 { number * 1000.0 } else { number };
+            millis_to_timestamp(millis as i64)
+        }
+        _ => None,
+    }
+}
+
+/// Work out the source of an export from its shape
+pub fn detect_source(values: &[Value]) -> Option<ImportSource> {
+    let first = values.first()?;
+    if let Some(requests) = first.get("requests").and_then(|r| r.as_array()) {
+        let naide = requests.iter().any(|request| {
+            request.pointer("/agent/id").and_then(|id| id.as_str()) == Some(NAIDE_PARTICIPANT_ID)
+                || request.pointer("/message/text").and_then(|t| t.as_str())
+                    .map(|t| t.trim_start().starts_with("@naide"))
+                    .unwrap_or(false)
+        });
+        return Some(if naide { ImportSource::NaideVscode } else { ImportSource::VscodeCopilot });
+    }
+    if first.get("messages").map(|m| m.is_array()).unwrap_or(false) || first.is_array() {
+        return Some(ImportSource::Openai);
+    }
+    None
+}
+
+/// Markdown of a VS Code chat response: its markdown parts joined in order
+fn vscode_response_text(response: &Value) -> (String, Vec<Value>) {
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for part in response.as_array().into_iter().flatten() {
+        let value = part.get("value").and_then(|v| v.as_str())
+            .or_else(|| part.pointer("/content/value").and_then(|v| v.as_str()));
+        match (part.get("kind").and_then(|k| k.as_str()), value) {
+            (Some("toolInvocationSerialized"), _) => {
+                let name = part.get("toolId").and_then(|t| t.as_str()).unwrap_or("tool");
+                let id = part.get("toolCallId").and_then(|t| t.as_str()).unwrap_or(name);
+                tool_calls.push(json!({ "id": id, "name": name, "arguments": null, "status": "success" }));
+            }
+            (_, Some(value)) => text.push_str(value),
+            _ => {}
+        }
+    }
+    (text.trim().to_string(), tool_calls)
+}
+
+/// Strip "@naide" and a leading slash command from a prompt; returns the mode it selects
+fn strip_naide_prefix(text: &str) -> (String, Option<&'static str>) {
+    let mut rest = text.trim_start();
+    if let Some(stripped) = rest.strip_prefix("@naide") {
+        rest = stripped.trim_start();
+    }
+    for (command, mode) in [("/plan", "Planning"), ("/build", "Building")] {
+        if let Some(stripped) = rest.strip_prefix(command) {
+            if stripped.is_empty() || stripped.starts_with(char::is_whitespace) {
+                return (stripped.trim_start().to_string(), Some(mode));
+            }
+        }
+    }
+    (rest.to_string(), None)
+}
+
+/// One session from a VS Code chat export (Copilot or @naide)
+fn convert_vscode(export: &Value, source: ImportSource, fallback_time: &str) -> Result<Value, String> {
+    let requests = export.get("requests").and_then(|r| r.as_array())
+        .ok_or_else(|| "missing \"requests\"".to_string())?;
+
+    let mut messages = Vec::new();
+    let mut mode = None;
+    for (index, request) in requests.iter().enumerate() {
+        let prompt = request.pointer("/message/text").and_then(|t| t.as_str()).unwrap_or("");
+        let timestamp = request.get("timestamp").and_then(parse_timestamp)
+            .unwrap_or_else(|| fallback_time.to_string());
+
+        let (prompt, command_mode) = if source == ImportSource::NaideVscode {
+            strip_naide_prefix(prompt)
+        } else {
+            (prompt.to_string(), None)
+        };
+        let request_mode = request.pointer("/slashCommand/name").and_then(|c| c.as_str())
+            .and_then(|c| match c { "plan" => Some("Planning"), "build" => Some("Building"), _ => None })
+            .or(command_mode);
+        if mode.is_none() {
+            mode = request_mode;
+        }
+
+        messages.push(json!({ "id": format!("import-{}-user", index), "role": "user", "content": prompt, "timestamp": timestamp }));
+
+        let (answer, tool_calls) = vscode_response_text(request.get("response").unwrap_or(&Value::Null));
+        if !answer.is_empty() || !tool_calls.is_empty() {
+            // The response ends when the request's timings say it did
+            let answered = request.pointer("/result/timings/totalElapsed").and_then(|e| e.as_i64())
+                .zip(request.get("timestamp").and_then(|t| t.as_i64()))
+                .and_then(|(elapsed, start)| millis_to_timestamp(start + elapsed))
+                .unwrap_or_else(|| timestamp.clone());
+            messages.push(json!({ "id": format!("import-{}-assistant", index), "role": "assistant", "content": answer, "timestamp": answered, "toolCalls": tool_calls }));
+        }
+    }
+
+    Ok(json!({
+        "mode": mode.unwrap_or("Planning"),
+        "title": export.get("title").cloned().unwrap_or(Value::Null),
+        "messages": messages,
+    }))
+}
+
+/// One session from OpenAI-style messages. Timestamps are taken from
+/// "timestamp", "created_at" or "created" where the export has them.
+fn convert_openai(conversation: &Value, fallback_time: &str) -> Result<Value, String> {
+    let list = conversation.get("messages").unwrap_or(conversation).as_array()
+        .ok_or_else(|| "missing \"messages\"".to_string())?;
+    let message_time = |message: &Value| ["timestamp", "created_at", "created"].iter()
+        .find_map(|key| message.get(*key).and_then(parse_timestamp));
+    let session_time = ["created_at", "created", "create_time", "timestamp"].iter()
+        .find_map(|key| conversation.get(*key).and_then(parse_timestamp));
+    // Messages without a time of their own take the previous message's,
+    // or the session's (or first known message's) time at the start
+    let mut current_time = session_time.clone()
+        .or_else(|| list.iter().find_map(message_time))
+        .unwrap_or_else(|| fallback_time.to_string());
+
+    let mut messages = Vec::new();
+    for (index, message) in list.iter().enumerate() {
+        let role = match message.get("role").and_then(|r| r.as_str()) {
+            // Newer system prompts, and tool results from the older function calling API
+            Some("developer") => "system",
+            Some("function") => "tool",
+            Some(role) => role,
+            None => return Err(format!("message {} has no role", index)),
+        };
+        if let Some(time) = message_time(message) {
+            current_time = time;
+        }
+        let timestamp = current_time.clone();
+
+        let tool_calls: Vec<Value> = message.get("tool_calls").and_then(|t| t.as_array()).into_iter().flatten()
+            .map(|call| {
+                let arguments = call.pointer("/function/arguments").and_then(|a| a.as_str())
+                    .map(|a| serde_json::from_str(a).unwrap_or_else(|_| Value::String(a.to_string())))
+                    .unwrap_or(Value::Null);
+                json!({
+                    "id": call.get("id").cloned().unwrap_or_else(|| json!(format!("call-{}", index))),
+                    "name": call.pointer("/function/name").cloned().unwrap_or_else(|| json!("tool")),
+                    "arguments": arguments,
+                })
+            })
+            .collect();
+
+        let mut converted = json!({
+            "id": format!("import-{}", index),
+            "role": role,
+            "content": message.get("content").cloned().unwrap_or(Value::Null),
+            "timestamp": timestamp,
+        });
+        if !tool_calls.is_empty() {
+            converted["toolCalls"] = Value::Array(tool_calls);
+        }
+        messages.push(converted);
+    }
+
+    let mut session = json!({
+        "mode": conversation.get("mode").cloned().unwrap_or_else(|| json!("Planning")),
+        "title": conversation.get("title").cloned().unwrap_or(Value::Null),
+        "messages": messages,
+    });
+    if let Some(created) = session_time {
+        session["createdAt"] = json!(created);
+    }
+    Ok(session)
+}
+
+/// Parse an export into conversations: one JSON document, or JSONL with one per line
+fn parse_export(content: &str) -> Result<Vec<Value>, String> {
+    if let Ok(value) = serde_json::from_str::<Value>(content) {
+        // A list of conversations rather than a bare message list
+        if let Some(items) = value.as_array() {
+            if items.iter().all(|item| item.get("messages").is_some() || item.get("requests").is_some()) && !items.is_empty() {
+                return Ok(items.clone());
+            }
+        }
+        return Ok(vec![value]);
+    }
+    content.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("Invalid JSON on line {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Convert, validate and type one conversation
+pub fn convert_conversation(conversation: &Value, source: ImportSource, origin: &str, fallback_time: &str) -> Result<ChatSession, String> {
+    let mut raw = match source {
+        ImportSource::VscodeCopilot | ImportSource::NaideVscode => convert_vscode(conversation, source, fallback_time)?,
+        ImportSource::Openai => convert_openai(conversation, fallback_time)?,
+    };
+    let messages = raw["messages"].as_array().cloned().unwrap_or_default();
+    if messages.is_empty() {
+        return Err("conversation has no messages".to_string());
+    }
+
+    // Original timestamps: created at the first message, updated at the last
+    let first = messages.first().and_then(|m| m["timestamp"].as_str()).unwrap_or(fallback_time).to_string();
+    let last = messages.last().and_then(|m| m["timestamp"].as_str()).unwrap_or(fallback_time).to_string();
+    if !raw["createdAt"].is_string() {
+        raw["createdAt"] = json!(first);
+    }
+    raw["updatedAt"] = json!(last);
+    if raw["title"].is_null() {
+        if let Some(object) = raw.as_object_mut() {
+            object.remove("title");
+        }
+    }
+    raw["importedFrom"] = json!({ "source": source, "file": origin, "importedAt": now_timestamp() });
+
+    migrate_session(raw, origin, fallback_time)
+        .map(|(session, _)| session)
+        .map_err(|e| e.message())
+}
+
+/// Give the session file the conversation's last activity time, so lists sort it by its history
+fn set_file_time(path: &Path, timestamp: &str) {
+    if let Ok(time) = DateTime::parse_from_rfc3339(timestamp) {
+        let time: SystemTime = time.with_timezone(&Utc).into();
+        if let Err(e) = File::options().write(true).open(path).and_then(|file| file.set_modified(time)) {
+            log::warn!("Failed to set modification time of {:?}: {}", path, e);
+        }
+    }
+}
+
+// Tauri command: Import conversations from another tool into .naide/chatsessions
+// source_path is chosen with the open dialog; source is detected when not given
+#[tauri::command]
+pub async fn import_chat_sessions(
+    project_path: String,
+    source_path: String,
+    source: Option<ImportSource>,
+) -> Result<ChatImportResult, String> {
+    let source_file = PathBuf::from(&source_path);
+    let metadata = fs::metadata(&source_file)
+        .map_err(|e| format!("Failed to read import file: {}", e))?;
+    if !metadata.is_file() {
+        return Err("Import path is not a file".to_string());
+    }
+    if metadata.len() > MAX_IMPORT_BYTES {
+        return Err(format!("Import file is too large ({} bytes, limit {})", metadata.len(), MAX_IMPORT_BYTES));
+    }
+
+    let content = fs::read_to_string(&source_file)
+        .map_err(|e| format!("Failed to read import file: {}", e))?;
+    let conversations = parse_export(&content)?;
+    let source = match source {
+        Some(source) => source,
+        None => detect_source(&conversations)
+            .ok_or_else(|| "Unrecognized chat export format".to_string())?,
+    };
+
+    let origin = source_file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let fallback_time = metadata.modified().ok()
+        .map(|t| DateTime::<Utc>::from(t).to_rfc3339_opts(SecondsFormat::Millis, true))
+        .unwrap_or_else(now_timestamp);
+
+    let dir = chat_sessions_dir(&project_path);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create chat sessions directory: {}", e))?;
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, conversation) in conversations.iter().enumerate() {
+        let mut session = match convert_conversation(conversation, source, &origin, &fallback_time) {
+            Ok(session) => session,
+            Err(e) => {
+                skipped.push(format!("Conversation {}: {}", index + 1, e));
+                continue;
+            }
+        };
+
+        let date = session.created_at.get(..10).unwrap_or("import").to_string();
+        session.id = session_id_for(&date, "import");
+        let filename = format!("{}.json", session.id);
+        let path = dir.join(&filename);
+        write_session(&path, &session)?;
+        set_file_time(&path, &session.updated_at);
+        chat_index::update_index_entry(&project_path, &filename, &session);
+
+        imported.push(ImportedSession {
+            filename,
+            title: session.title.clone(),
+            message_count: session.messages.len(),
+            created_at: session.created_at.clone(),
+        });
+    }
+
+    log::info!("Imported {} chat sessions from {} ({:?}), skipped {}", imported.len(), origin, source, skipped.len());
+    Ok(ChatImportResult { source, imported, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::chat_sessions::ChatRole;
+
+    #[test]
+    fn test_vscode_exports() {
+        let export = json!({
+            "requesterUsername": "dev",
+            "responderUsername": "GitHub Copilot",
+            "requests": [{
+                "message": { "text": "@naide /build add a login page" },
+                "agent": { "id": "naide.chat" },
+                "timestamp": 1735812000000i64,
+                "result": { "timings": { "totalElapsed": 4000 } },
+                "response": [
+                    { "value": "Creating " },
+                    { "kind": "toolInvocationSerialized", "toolId": "copilot_createFile", "toolCallId": "t1" },
+                    { "kind": "markdownContent", "content": { "value": "the page." } }
+                ]
+            }]
+        });
+        let conversations = vec![export];
+        assert_eq!(detect_source(&conversations), Some(ImportSource::NaideVscode));
+
+        let session = convert_conversation(&conversations[0], ImportSource::NaideVscode, "chat.json", "2025-01-01T00:00:00.000Z").unwrap();
+        assert_eq!(session.mode, "Building");
+        assert_eq!(session.messages[0].content, "add a login page");
+        assert_eq!(session.messages[0].timestamp, "2025-01-02T10:00:00.000Z");
+        assert_eq!(session.messages[1].content, "Creating the page.");
+        assert_eq!(session.messages[1].tool_calls[0].name, "copilot_createFile");
+        assert_eq!(session.created_at, "2025-01-02T10:00:00.000Z");
+        assert_eq!(session.updated_at, "2025-01-02T10:00:04.000Z");
+        assert_eq!(session.extra["importedFrom"]["source"], json!("naide-vscode"));
+    }
+
+    #[test]
+    fn test_openai_jsonl_import() {
+        let root = TempDir::new("chat-import");
+        let export = root.join("dataset.jsonl");
+        fs::write(&export, concat!(
+            r#"{"messages":[{"role":"developer","content":"Be brief"},{"role":"user","content":"Hi","created":1735812000},{"role":"assistant","content":[{"type":"text","text":"Hello"}],"tool_calls":[{"id":"c1","type":"function","function":{"name":"lookup","arguments":"{\"q\":1}"}}]},{"role":"function","name":"lookup","content":"42"}]}"#, "\n",
+            r#"{"messages":[]}"#, "\n",
+        )).unwrap();
+
+        let project = root.to_string_lossy().to_string();
+        let result = tauri::async_runtime::block_on(import_chat_sessions(project.clone(), export.to_string_lossy().to_string(), None)).unwrap();
+        assert_eq!(result.source, ImportSource::Openai);
+        assert_eq!(result.imported.len(), 1);
+        assert_eq!(result.skipped.len(), 1);
+
+        let sessions = chat_index::refresh_index(&project).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].filename.starts_with("2025-01-02-import-"));
+        assert_eq!(sessions[0].created_at, "2025-01-02T10:00:00.000Z");
+        let session = crate::chat_sessions::load_session(&chat_sessions_dir(&project), &sessions[0].filename).unwrap();
+        assert_eq!(session.messages[0].role, ChatRole::System);
+        assert_eq!(session.messages[0].timestamp, "2025-01-02T10:00:00.000Z");
+        assert_eq!(session.messages[1].timestamp, "2025-01-02T10:00:00.000Z");
+        assert_eq!(session.messages[2].content, "Hello");
+        assert_eq!(session.messages[2].tool_calls[0].arguments, json!({ "q": 1 }));
+        assert_eq!(session.messages[3].role, ChatRole::Tool);
+    }
+}
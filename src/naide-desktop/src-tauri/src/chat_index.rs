@@ -0,0 +1,296 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::chat_sessions::{
+    chat_sessions_dir, load_session, ChatSession, ChatSessionMetadata, ACTIVE_SESSION_FILE,
+};
+use crate::file_io;
+
+// Session metadata cache, validated against file size and modification time
+pub const INDEX_FILE: &str = "index.json";
+const INDEX_VERSION: u32 = 2;
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+// Serializes index updates from concurrent commands
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct IndexEntry {
+    metadata: ChatSessionMetadata,
+    modified_ms: u64,
+    size: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ChatIndex {
+    version: u32,
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatSessionPage {
+    pub sessions: Vec<ChatSessionMetadata>,
+    pub total: usize, // Sessions matching the filter, across all pages
+    pub offset: usize,
+}
+
+/// Whether a file in the chat sessions folder is an archived session
+pub fn is_session_file(filename: &str) -> bool {
+    filename.ends_with(".json") && filename != ACTIVE_SESSION_FILE && filename != INDEX_FILE
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified_ms = metadata.modified().ok()?
+        .duration_since(std::time::UNIX_EPOCH).ok()?
+        .as_millis() as u64;
+    Some((modified_ms, metadata.len()))
+}
+
+fn load_index(dir: &Path) -> ChatIndex {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ChatIndex>(&content).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .unwrap_or_else(|| ChatIndex { version: INDEX_VERSION, entries: BTreeMap::new() })
+}
+
+fn save_index(dir: &Path, index: &ChatIndex) -> Result<(), String> {
+    let content = serde_json::to_string(index)
+        .map_err(|e| format!("Failed to serialize chat index: {}", e))?;
+    file_io::write_atomic(&dir.join(INDEX_FILE), content.as_bytes())
+}
+
+/// Index entry for a session that was just loaded or written.
+/// The file is stat'ed afterwards, since loading may migrate (rewrite) it.
+fn entry_for(dir: &Path, filename: &str, session: &ChatSession) -> Option<IndexEntry> {
+    let (modified_ms, size) = file_stamp(&dir.join(filename))?;
+    Some(IndexEntry {
+        metadata: ChatSessionMetadata {
+            filename: filename.to_string(),
+            last_modified: modified_ms / 1000,
+            message_count: session.messages.len(),
+            mode: session.mode.clone(),
+            first_user_message: session.first_user_message().to_string(),
+            title: session.title.clone(),
+            created_at: session.created_at.clone(),
+            summary: session.digest.as_ref().map(|d| d.summary.clone()),
+            tags: session.digest.as_ref().map(|d| d.tags.clone()).unwrap_or_default(),
+            token_count: session.digest.as_ref().map(|d| d.token_counts.total),
+            model: session.digest.as_ref().and_then(|d| d.model.clone()),
+        },
+        modified_ms,
+        size,
+    })
+}
+
+/// Bring the index in line with the folder: new and changed files are parsed,
+/// removed files dropped. Returns the metadata of every readable session.
+pub fn refresh_index(project_path: &str) -> Result<Vec<ChatSessionMetadata>, String> {
+    let dir = chat_sessions_dir(project_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    if !dir.is_dir() {
+        return Err("Chat sessions path is not a directory".to_string());
+    }
+
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = load_index(&dir);
+    let mut changed = false;
+
+    let entries = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read chat sessions directory: {}", e))?;
+    let mut present = BTreeMap::new();
+
+    for entry in entries.flatten() {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        if !is_session_file(&filename) || !entry.path().is_file() {
+            continue;
+        }
+
+        let stamp = file_stamp(&entry.path());
+        let cached = index.entries.get(&filename)
+            .filter(|cached| Some((cached.modified_ms, cached.size)) == stamp)
+            .cloned();
+
+        let indexed = match cached {
+            Some(cached) => Some(cached),
+            None => {
+                changed = true;
+                match load_session(&dir, &filename) {
+                    Ok(session) => entry_for(&dir, &filename, &session),
+                    Err(e) => {
+                        log::warn!("Skipping chat session file {}: {}", filename, e.message());
+                        None
+                    }
+                }
+            }
+        };
+        if let Some(indexed) = indexed {
+            present.insert(filename, indexed);
+        }
+    }
+
+    if changed || present.len() != index.entries.len() {
+        index.entries = present;
+        save_index(&dir, &index)?;
+    }
+
+    Ok(index.entries.into_values().map(|entry| entry.metadata).collect())
+}
+
+/// Record a session that was just written
+pub fn update_index_entry(project_path: &str, filename: &str, session: &ChatSession) {
+    if !is_session_file(filename) {
+        return;
+    }
+    let dir = chat_sessions_dir(project_path);
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = load_index(&dir);
+    match entry_for(&dir, filename, session) {
+        Some(entry) => index.entries.insert(filename.to_string(), entry),
+        None => index.entries.remove(filename),
+    };
+    if let Err(e) = save_index(&dir, &index) {
+        log::warn!("Failed to update chat index: {}", e);
+    }
+}
+
+/// Drop a session that was moved or deleted
+pub fn remove_index_entry(project_path: &str, filename: &str) {
+    let dir = chat_sessions_dir(project_path);
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = load_index(&dir);
+    if index.entries.remove(filename).is_some() {
+        if let Err(e) = save_index(&dir, &index) {
+            log::warn!("Failed to update chat index: {}", e);
+        }
+    }
+}
+
+/// Sort sessions in place: by "date" (last modified, default), "created" or "mode".
+/// Sorting by mode groups sessions alphabetically, newest first within a mode.
+fn sort_sessions(sessions: &mut [ChatSessionMetadata], sort_by: &str, descending: bool) {
+    match sort_by {
+        "created" => sessions.sort_by(|a, b| a.created_at.cmp(&b.created_at)),
+        "mode" => sessions.sort_by(|a, b| a.mode.cmp(&b.mode).then(b.last_modified.cmp(&a.last_modified))),
+        _ => sessions.sort_by(|a, b| a.last_modified.cmp(&b.last_modified)),
+    }
+    if descending && sort_by != "mode" {
+        sessions.reverse();
+    }
+}
+
+// Tauri command: List chat sessions (excluding active session)
+#[tauri::command]
+pub async fn list_chat_sessions(project_path: String) -> Result<Vec<ChatSessionMetadata>, String> {
+    let mut sessions = refresh_index(&project_path)?;
+
+    // Sort by last_modified descending (most recent first)
+    sort_sessions(&mut sessions, "date", true);
+
+    Ok(sessions)
+}
+
+// Tauri command: List one page of chat sessions from the index
+// sort_by is "date" (default), "created" or "mode"; mode filters to one chat mode
+#[tauri::command]
+pub async fn list_chat_sessions_page(
+    project_path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort_by: Option<String>,
+    descending: Option<bool>,
+    mode: Option<String>,
+) -> Result<ChatSessionPage, String> {
+    let mut sessions = refresh_index(&project_path)?;
+    if let Some(mode) = mode {
+        sessions.retain(|s| s.mode.eq_ignore_ascii_case(&mode));
+    }
+    sort_sessions(&mut sessions, sort_by.as_deref().unwrap_or("date"), descending.unwrap_or(true));
+
+    let total = sessions.len();
+    let offset = offset.unwrap_or(0).min(total);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let sessions = sessions.into_iter().skip(offset).take(limit).collect();
+
+    Ok(ChatSessionPage { sessions, total, offset })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn run<T>(future: impl std::future::Future<Output = T>) -> T {
+        tauri::async_runtime::block_on(future)
+    }
+
+    #[test]
+    fn test_index_tracks_changes_and_pages() {
+        let root = TempDir::new("chat-index");
+        let project = root.to_string_lossy().to_string();
+        let dir = chat_sessions_dir(&project);
+        fs::create_dir_all(&dir).unwrap();
+
+        let write = |name: &str, mode: &str, text: &str| {
+            let content = format!(r#"{{"id":"x","mode":"{}","messages":[{{"role":"user","content":"{}"}}]}}"#, mode, text);
+            fs::write(dir.join(name), content).unwrap();
+        };
+        write("a.json", "Planning", "first");
+        write("b.json", "Building", "second");
+        write(ACTIVE_SESSION_FILE, "Planning", "active");
+
+        let sessions = run(list_chat_sessions(project.clone())).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(dir.join(INDEX_FILE).is_file());
+
+        // Changed files are re-read, removed ones dropped
+        write("a.json", "Planning", "edited, and longer than before");
+        fs::remove_file(dir.join("b.json")).unwrap();
+        let sessions = run(list_chat_sessions(project.clone())).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].first_user_message, "edited, and longer than before");
+
+        write("b.json", "Building", "second");
+        write("c.json", "Planning", "third");
+        let page = run(list_chat_sessions_page(project.clone(), Some(0), Some(2), Some("mode".to_string()), None, None)).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.sessions.len(), 2);
+        assert_eq!(page.sessions[0].mode, "Building");
+
+        let page = run(list_chat_sessions_page(project.clone(), None, None, None, None, Some("planning".to_string()))).unwrap();
+        assert_eq!(page.total, 2);
+
+        remove_index_entry(&project, "c.json");
+        assert!(!load_index(&dir).entries.contains_key("c.json"));
+    }
+
+    #[test]
+    fn test_migration_keeps_history_order() {
+        let root = TempDir::new("chat-index-order");
+        let project = root.to_string_lossy().to_string();
+        let dir = chat_sessions_dir(&project);
+        fs::create_dir_all(&dir).unwrap();
+
+        // Two legacy sessions (no schema version); the older one is migrated last
+        let day = std::time::Duration::from_secs(24 * 60 * 60);
+        for (name, age) in [("newer.json", 1), ("older.json", 2)] {
+            let path = dir.join(name);
+            fs::write(&path, r#"{"messages":[{"role":"user","content":"hi"}]}"#).unwrap();
+            let time = std::time::SystemTime::now() - day * age;
+            fs::File::options().write(true).open(&path).unwrap().set_modified(time).unwrap();
+        }
+        let before = file_stamp(&dir.join("older.json")).unwrap().0;
+
+        let sessions = run(list_chat_sessions(project.clone())).unwrap();
+        assert_eq!(sessions.iter().map(|s| s.filename.as_str()).collect::<Vec<_>>(), vec!["newer.json", "older.json"]);
+        assert!(fs::read_to_string(dir.join("older.json")).unwrap().contains("schemaVersion"));
+        assert_eq!(file_stamp(&dir.join("older.json")).unwrap().0, before);
+    }
+}
@@ -0,0 +1,275 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::chat_index;
+use crate::chat_sessions::{
+    chat_sessions_dir, load_session, validate_session_filename, ChatRole, ChatSession, ACTIVE_SESSION_FILE,
+};
+
+const DEFAULT_RESULT_LIMIT: usize = 200;
+const MAX_RESULT_LIMIT: usize = 1000;
+// Characters of context kept on each side of the first hit
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChatSearchOptions {
+    pub roles: Option<Vec<ChatRole>>,  // Only messages with these roles
+    pub mode: Option<String>,          // Only sessions in this chat mode
+    pub from: Option<String>,          // RFC 3339 or YYYY-MM-DD (inclusive)
+    pub to: Option<String>,            // RFC 3339 or YYYY-MM-DD (inclusive, whole day)
+    pub filename: Option<String>,      // Search within one session only
+    pub case_sensitive: Option<bool>,
+    pub include_active: Option<bool>,  // Include default-chat.json (default true)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatSearchResult {
+    pub filename: String,
+    pub session_title: Option<String>,
+    pub mode: String,
+    pub message_index: usize,
+    pub message_id: String,
+    pub role: ChatRole,
+    pub timestamp: String,
+    pub snippet: String,
+    // [start, end) of each hit in the snippet, in UTF-16 code units (JavaScript string indices)
+    pub highlights: Vec<(usize, usize)>,
+    pub match_count: usize, // Hits in the whole message
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatSearchResponse {
+    pub results: Vec<ChatSearchResult>,
+    pub total_messages: usize, // Matching messages, including those past the limit
+    pub truncated: bool,
+}
+
+/// Parse a range bound; a bare date means the start (or, for `end_of_day`, the end) of that day in UTC
+fn parse_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {} (expected YYYY-MM-DD or an RFC 3339 timestamp)", value))?;
+    let time = if end_of_day { date.and_hms_milli_opt(23, 59, 59, 999) } else { date.and_hms_opt(0, 0, 0) };
+    time.map(|t| t.and_utc())
+        .ok_or_else(|| format!("Invalid date: {}", value))
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Cut a snippet around the first hit and locate every hit inside it
+fn build_snippet(content: &str, hits: &[(usize, usize)]) -> (String, Vec<(usize, usize)>) {
+    let (first_start, first_end) = hits[0];
+
+    let mut start = first_start;
+    for _ in 0..SNIPPET_CONTEXT_CHARS {
+        match content[..start].char_indices().next_back() {
+            Some((i, _)) => start = i,
+            None => break,
+        }
+    }
+    let mut end = first_end;
+    for _ in 0..SNIPPET_CONTEXT_CHARS {
+        match content[end..].chars().next() {
+            Some(c) => end += c.len_utf8(),
+            None => break,
+        }
+    }
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < content.len() { "…" } else { "" };
+    let body = &content[start..end];
+    // Snippets stay on one line
+    let snippet = format!("{}{}{}", prefix, body, suffix).replace(['\n', '\r'], " ");
+
+    let offset = utf16_len(prefix);
+    let highlights = hits.iter()
+        .filter(|(s, e)| *s >= start && *e <= end)
+        .map(|(s, e)| (offset + utf16_len(&content[start..*s]), offset + utf16_len(&content[start..*e])))
+        .collect();
+    (snippet, highlights)
+}
+
+/// Matches in one session, in message order
+pub fn search_session(
+    filename: &str,
+    session: &ChatSession,
+    pattern: &Regex,
+    options: &ChatSearchOptions,
+    range: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+) -> Vec<ChatSearchResult> {
+    let mut results = Vec::new();
+
+    for (index, message) in session.messages.iter().enumerate() {
+        if let Some(roles) = &options.roles {
+            if !roles.contains(&message.role) {
+                continue;
+            }
+        }
+        if range.0.is_some() || range.1.is_some() {
+            let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) else {
+                continue;
+            };
+            let timestamp = timestamp.with_timezone(&Utc);
+            if range.0.map(|from| timestamp < from).unwrap_or(false) || range.1.map(|to| timestamp > to).unwrap_or(false) {
+                continue;
+            }
+        }
+
+        let hits: Vec<(usize, usize)> = pattern.find_iter(&message.content)
+            .map(|m| (m.start(), m.end()))
+            .filter(|(s, e)| e > s)
+            .collect();
+        if hits.is_empty() {
+            continue;
+        }
+
+        let (snippet, highlights) = build_snippet(&message.content, &hits);
+        results.push(ChatSearchResult {
+            filename: filename.to_string(),
+            session_title: session.title.clone(),
+            mode: session.mode.clone(),
+            message_index: index,
+            message_id: message.id.clone(),
+            role: message.role,
+            timestamp: message.timestamp.clone(),
+            snippet,
+            highlights,
+            match_count: hits.len(),
+        });
+    }
+
+    results
+}
+
+// Tauri command: Search message content across chat sessions
+// Results are grouped by session, newest session first, and carry the message index to scroll to
+#[tauri::command]
+pub async fn search_chat_sessions(
+    project_path: String,
+    query: String,
+    options: Option<ChatSearchOptions>,
+) -> Result<ChatSearchResponse, String> {
+    let options = options.unwrap_or_default();
+    let query = query.trim();
+    if query.is_empty() {
+        return Err("Search query cannot be empty".to_string());
+    }
+
+    let pattern = RegexBuilder::new(&regex::escape(query))
+        .case_insensitive(!options.case_sensitive.unwrap_or(false))
+        .build()
+        .map_err(|e| format!("Invalid search query: {}", e))?;
+    let range = (
+        options.from.as_deref().map(|v| parse_bound(v, false)).transpose()?,
+        options.to.as_deref().map(|v| parse_bound(v, true)).transpose()?,
+    );
+
+    let dir = chat_sessions_dir(&project_path);
+    let mut filenames: Vec<String> = match &options.filename {
+        Some(filename) => {
+            validate_session_filename(filename)?;
+            vec![filename.clone()]
+        }
+        None => {
+            // The index narrows by mode without parsing every session
+            let mut sessions = chat_index::refresh_index(&project_path)?;
+            if let Some(mode) = &options.mode {
+                sessions.retain(|s| s.mode.eq_ignore_ascii_case(mode));
+            }
+            sessions.sort_by_key(|s| std::cmp::Reverse(s.last_modified));
+            sessions.into_iter().map(|s| s.filename).collect()
+        }
+    };
+    if options.filename.is_none() && options.include_active.unwrap_or(true) && dir.join(ACTIVE_SESSION_FILE).is_file() {
+        filenames.insert(0, ACTIVE_SESSION_FILE.to_string());
+    }
+
+    let limit = options.limit.unwrap_or(DEFAULT_RESULT_LIMIT).clamp(1, MAX_RESULT_LIMIT);
+    let mut results = Vec::new();
+    let mut total_messages = 0;
+
+    for filename in filenames {
+        let session = match load_session(&dir, &filename) {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("Skipping chat session {} in search: {}", filename, e.message());
+                continue;
+            }
+        };
+        if let Some(mode) = &options.mode {
+            if !session.mode.eq_ignore_ascii_case(mode) {
+                continue;
+            }
+        }
+
+        let matches = search_session(&filename, &session, &pattern, &options, range);
+        total_messages += matches.len();
+        let room = limit.saturating_sub(results.len());
+        results.extend(matches.into_iter().take(room));
+    }
+
+    log::info!("Chat search for {:?} matched {} messages", query, total_messages);
+    Ok(ChatSearchResponse {
+        truncated: total_messages > results.len(),
+        results,
+        total_messages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_snippet_highlights() {
+        let content = format!("{}The auth flow uses OAuth. Auth tokens expire.", "x".repeat(80));
+        let pattern = RegexBuilder::new("auth").case_insensitive(true).build().unwrap();
+        let hits: Vec<(usize, usize)> = pattern.find_iter(&content).map(|m| (m.start(), m.end())).collect();
+        assert_eq!(hits.len(), 3);
+
+        let (snippet, highlights) = build_snippet(&content, &hits);
+        assert!(snippet.starts_with('…'));
+        assert!(!snippet.ends_with('…'));
+        let units: Vec<u16> = snippet.encode_utf16().collect();
+        for (start, end) in &highlights {
+            assert_eq!(String::from_utf16(&units[*start..*end]).unwrap().to_lowercase(), "auth");
+        }
+        assert_eq!(highlights.len(), 3);
+    }
+
+    #[test]
+    fn test_search_filters() {
+        let root = TempDir::new("chat-search");
+        let project = root.to_string_lossy().to_string();
+        let dir = chat_sessions_dir(&project);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("plan.json"), r#"{"id":"plan","mode":"Planning","messages":[
+            {"id":"u1","role":"user","content":"How does auth work?","timestamp":"2025-03-01T10:00:00Z"},
+            {"id":"a1","role":"assistant","content":"The auth flow redirects to the provider.","timestamp":"2025-03-01T10:00:05Z"}]}"#).unwrap();
+        fs::write(dir.join("build.json"), r#"{"id":"build","mode":"Building","messages":[
+            {"id":"a1","role":"assistant","content":"Added AUTH middleware.","timestamp":"2025-04-02T09:00:00Z"}]}"#).unwrap();
+
+        let search = |options: ChatSearchOptions| {
+            tauri::async_runtime::block_on(search_chat_sessions(project.clone(), "auth".to_string(), Some(options))).unwrap()
+        };
+
+        assert_eq!(search(ChatSearchOptions::default()).total_messages, 3);
+        let assistant = search(ChatSearchOptions { roles: Some(vec![ChatRole::Assistant]), mode: Some("planning".to_string()), ..Default::default() });
+        assert_eq!(assistant.results.len(), 1);
+        assert_eq!((assistant.results[0].filename.as_str(), assistant.results[0].message_index), ("plan.json", 1));
+        let april = search(ChatSearchOptions { from: Some("2025-04-01".to_string()), to: Some("2025-04-02".to_string()), ..Default::default() });
+        assert_eq!(april.results.len(), 1);
+        assert_eq!(april.results[0].filename, "build.json");
+        assert_eq!(search(ChatSearchOptions { case_sensitive: Some(true), ..Default::default() }).total_messages, 2);
+        let limited = search(ChatSearchOptions { limit: Some(1), ..Default::default() });
+        assert!(limited.truncated && limited.results.len() == 1);
+    }
+}
@@ -0,0 +1,737 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::chat_index::{self, INDEX_FILE};
+use crate::file_io;
+
+// Schema version written by this build; older files are migrated on load
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+// The active session, which is never listed with the archived ones
+pub const ACTIVE_SESSION_FILE: &str = "default-chat.json";
+const QUARANTINE_DIR: &str = "quarantine";
+// Mode assumed for sessions saved before the mode was recorded
+const DEFAULT_MODE: &str = "Planning";
+// Tracks the last used session, shared with the frontend
+const PROJECT_CONFIG_FILE: &str = "project-config.json";
+pub const MAX_TITLE_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    User,
+    Assistant,
+    Command,
+    System,
+    Tool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>, // running, success or error
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatAttachment {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>, // Relative to the project root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessage {
+    pub id: String,
+    pub role: ChatRole,
+    pub content: String,
+    pub timestamp: String, // RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<ChatAttachment>,
+    // UI state and fields from newer versions are kept as they are
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Estimated token usage of a session's messages
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCounts {
+    pub input: u64,  // User and system messages
+    pub output: u64, // Assistant messages and tool calls
+    pub total: u64,
+}
+
+/// Generated description of an archived session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatDigest {
+    pub summary: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub token_counts: TokenCounts,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>, // Selected model when the digest was generated
+    pub generated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSession {
+    pub schema_version: u32,
+    pub id: String,
+    #[serde(default)]
+    pub project_name: String,
+    pub mode: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Value>, // Conversation memory (decisions, constraints, open questions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<ChatDigest>,
+    pub created_at: String, // RFC 3339
+    pub updated_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ChatSession {
+    pub fn first_user_message(&self) -> &str {
+        self.messages.iter()
+            .find(|m| m.role == ChatRole::User)
+            .map(|m| m.content.as_str())
+            .unwrap_or("")
+    }
+}
+
+// Chat session metadata structure
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatSessionMetadata {
+    pub filename: String,
+    pub last_modified: u64,
+    pub message_count: usize,
+    pub mode: String,
+    pub first_user_message: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub token_count: Option<u64>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// A session file moved aside because it could not be read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedSession {
+    pub filename: String,          // Name in the quarantine folder
+    pub original_filename: String,
+    pub reason: String,
+    pub quarantined_at: String,
+}
+
+#[derive(Debug)]
+pub enum SessionLoadError {
+    // The file is unreadable as a session and has been quarantined
+    Corrupt(String),
+    // Written by a newer version of Naide; left untouched
+    Unsupported(String),
+    Io(String),
+}
+
+impl SessionLoadError {
+    pub fn message(&self) -> String {
+        match self {
+            SessionLoadError::Corrupt(reason) => format!("Chat session is corrupt: {}", reason),
+            SessionLoadError::Unsupported(reason) | SessionLoadError::Io(reason) => reason.clone(),
+        }
+    }
+}
+
+pub fn now_timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub fn chat_sessions_dir(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(".naide").join("chatsessions")
+}
+
+/// Reject session filenames that could escape the chat sessions folder
+pub fn validate_session_filename(filename: &str) -> Result<(), String> {
+    if filename.is_empty() || filename.contains("..") || filename.contains('/') || filename.contains('\\') {
+        return Err("Invalid filename".to_string());
+    }
+    // Shares the folder with the sessions but is not one
+    if filename == INDEX_FILE {
+        return Err("Invalid filename: reserved for the chat index".to_string());
+    }
+    Ok(())
+}
+
+fn modified_timestamp(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Utc>::from(modified).to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Text of a message whose content was stored as a list of parts
+fn content_to_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts.iter()
+            .filter_map(|part| part.as_str().or_else(|| part.get("text").and_then(|t| t.as_str())))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn rename_key(object: &mut Map<String, Value>, from: &str, to: &str) {
+    if let Some(value) = object.remove(from) {
+        object.entry(to.to_string()).or_insert(value);
+    }
+}
+
+/// v0 -> v1: sessions written by the frontend before the schema was versioned.
+/// Fills in ids, mode and timestamps, normalizes roles and content, and adopts
+/// snake_case keys written by early builds.
+fn migrate_v0_to_v1(session: &mut Map<String, Value>, filename: &str, file_timestamp: &str) -> Result<(), String> {
+    for (from, to) in [("project_name", "projectName"), ("created_at", "createdAt"), ("updated_at", "updatedAt"), ("saved_at", "savedAt")] {
+        rename_key(session, from, to);
+    }
+
+    if !session.get("id").map(|v| v.is_string()).unwrap_or(false) {
+        session.insert("id".to_string(), Value::String(filename.trim_end_matches(".json").to_string()));
+    }
+    if !session.get("mode").map(|v| v.is_string()).unwrap_or(false) {
+        session.insert("mode".to_string(), Value::String(DEFAULT_MODE.to_string()));
+    }
+
+    let messages = match session.get_mut("messages") {
+        Some(Value::Array(messages)) => messages,
+        Some(_) => return Err("\"messages\" is not a list".to_string()),
+        None => return Err("missing \"messages\"".to_string()),
+    };
+
+    let mut first_timestamp = None;
+    for (index, message) in messages.iter_mut().enumerate() {
+        let message = message.as_object_mut()
+            .ok_or_else(|| format!("message {} is not an object", index))?;
+        rename_key(message, "tool_calls", "toolCalls");
+        rename_key(message, "command_status", "commandStatus");
+
+        if !message.get("id").map(|v| v.is_string()).unwrap_or(false) {
+            message.insert("id".to_string(), Value::String(format!("msg-{}", index)));
+        }
+        let role = message.get("role").and_then(|r| r.as_str()).map(|r| r.to_lowercase());
+        let role = match role.as_deref() {
+            Some("user") | Some("human") => "user",
+            Some("assistant") | Some("bot") | Some("copilot") | Some("ai") => "assistant",
+            Some("command") => "command",
+            Some("system") => "system",
+            Some("tool") => "tool",
+            other => return Err(format!("message {} has unknown role {:?}", index, other)),
+        };
+        message.insert("role".to_string(), Value::String(role.to_string()));
+        let content = content_to_text(message.get("content").unwrap_or(&Value::Null));
+        message.insert("content".to_string(), Value::String(content));
+
+        match message.get("timestamp").and_then(|t| t.as_str()) {
+            Some(timestamp) => {
+                first_timestamp.get_or_insert_with(|| timestamp.to_string());
+            }
+            None => {
+                message.insert("timestamp".to_string(), Value::String(file_timestamp.to_string()));
+            }
+        }
+    }
+
+    let created = first_timestamp.unwrap_or_else(|| file_timestamp.to_string());
+    if !session.get("createdAt").map(|v| v.is_string()).unwrap_or(false) {
+        session.insert("createdAt".to_string(), Value::String(created));
+    }
+    if !session.get("updatedAt").map(|v| v.is_string()).unwrap_or(false) {
+        session.insert("updatedAt".to_string(), Value::String(file_timestamp.to_string()));
+    }
+    Ok(())
+}
+
+type Migration = fn(&mut Map<String, Value>, &str, &str) -> Result<(), String>;
+
+// MIGRATIONS[n] upgrades a session from schema version n to n + 1
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+/// Upgrade a raw session to the current schema and type it.
+/// Returns the session and whether any migration ran.
+pub fn migrate_session(raw: Value, filename: &str, file_timestamp: &str) -> Result<(ChatSession, bool), SessionLoadError> {
+    let mut object = match raw {
+        Value::Object(object) => object,
+        _ => return Err(SessionLoadError::Corrupt("not a JSON object".to_string())),
+    };
+
+    let version = match object.get("schemaVersion") {
+        None => 0,
+        Some(v) => v.as_u64()
+            .ok_or_else(|| SessionLoadError::Corrupt("\"schemaVersion\" is not a number".to_string()))? as u32,
+    };
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(SessionLoadError::Unsupported(format!(
+            "Chat session {} uses schema version {}, newer than this version of Naide supports ({})",
+            filename, version, CURRENT_SCHEMA_VERSION
+        )));
+    }
+
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(&mut object, filename, file_timestamp)
+            .map_err(|e| SessionLoadError::Corrupt(format!("migration to version {} failed: {}", step + 1, e)))?;
+    }
+    object.insert("schemaVersion".to_string(), Value::from(CURRENT_SCHEMA_VERSION));
+
+    let session = serde_json::from_value::<ChatSession>(Value::Object(object))
+        .map_err(|e| SessionLoadError::Corrupt(e.to_string()))?;
+    Ok((session, version < CURRENT_SCHEMA_VERSION))
+}
+
+/// Move an unreadable session into the quarantine folder, recording why
+pub fn quarantine_session(dir: &Path, filename: &str, reason: &str) -> Result<QuarantinedSession, String> {
+    let quarantine_dir = dir.join(QUARANTINE_DIR);
+    fs::create_dir_all(&quarantine_dir)
+        .map_err(|e| format!("Failed to create quarantine directory: {}", e))?;
+
+    let stem = filename.trim_end_matches(".json");
+    let mut target_name = filename.to_string();
+    let mut counter = 1;
+    while quarantine_dir.join(&target_name).exists() {
+        target_name = format!("{}-{}.json", stem, counter);
+        counter += 1;
+    }
+
+    fs::rename(dir.join(filename), quarantine_dir.join(&target_name))
+        .map_err(|e| format!("Failed to quarantine chat session: {}", e))?;
+
+    let record = QuarantinedSession {
+        filename: target_name.clone(),
+        original_filename: filename.to_string(),
+        reason: reason.to_string(),
+        quarantined_at: now_timestamp(),
+    };
+    let record_json = serde_json::to_string_pretty(&record)
+        .map_err(|e| format!("Failed to serialize quarantine record: {}", e))?;
+    fs::write(quarantine_dir.join(format!("{}.reason.json", target_name)), record_json)
+        .map_err(|e| format!("Failed to write quarantine record: {}", e))?;
+
+    log::warn!("Quarantined chat session {} as {}: {}", filename, target_name, reason);
+    Ok(record)
+}
+
+/// Load a session file, migrating it (and writing the upgrade back) if it is old.
+/// Corrupt files are quarantined.
+pub fn load_session(dir: &Path, filename: &str) -> Result<ChatSession, SessionLoadError> {
+    let path = dir.join(filename);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| SessionLoadError::Io(format!("Failed to read chat session file: {}", e)))?;
+    let file_timestamp = modified_timestamp(&path).unwrap_or_else(now_timestamp);
+    let original_modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    let result = serde_json::from_str::<Value>(&content)
+        .map_err(|e| SessionLoadError::Corrupt(format!("invalid JSON: {}", e)))
+        .and_then(|raw| migrate_session(raw, filename, &file_timestamp));
+
+    match result {
+        Ok((session, migrated)) => {
+            if migrated {
+                write_session(&path, &session).map_err(SessionLoadError::Io)?;
+                // Sessions are listed by modification time; an upgrade is not activity
+                if let Some(time) = original_modified {
+                    if let Err(e) = fs::File::options().write(true).open(&path).and_then(|file| file.set_modified(time)) {
+                        log::warn!("Failed to restore modification time of {:?}: {}", path, e);
+                    }
+                }
+                log::info!("Migrated chat session {} to schema version {}", filename, CURRENT_SCHEMA_VERSION);
+            }
+            Ok(session)
+        }
+        Err(SessionLoadError::Corrupt(reason)) => {
+            quarantine_session(dir, filename, &reason).map_err(SessionLoadError::Io)?;
+            Err(SessionLoadError::Corrupt(reason))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Write a session atomically
+pub fn write_session(path: &Path, session: &ChatSession) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(session)
+        .map_err(|e| format!("Failed to serialize chat session: {}", e))?;
+    file_io::write_atomic(path, content.as_bytes())
+}
+
+// Tauri command: Load a specific chat session
+// Returns the session JSON, upgraded to the current schema
+#[tauri::command]
+pub async fn load_chat_session_file(project_path: String, filename: String) -> Result<String, String> {
+    // Security check: validate filename
+    validate_session_filename(&filename)?;
+
+    let chat_sessions_dir = chat_sessions_dir(&project_path);
+    if !chat_sessions_dir.join(&filename).exists() {
+        return Err(format!("Chat session file not found: {}", filename));
+    }
+
+    let session = load_session(&chat_sessions_dir, &filename)
+        .map_err(|e| e.message())?;
+    serde_json::to_string_pretty(&session)
+        .map_err(|e| format!("Failed to serialize chat session: {}", e))
+}
+
+// Tauri command: List chat sessions that were quarantined because they could not be read
+#[tauri::command]
+pub async fn list_quarantined_chat_sessions(project_path: String) -> Result<Vec<QuarantinedSession>, String> {
+    let quarantine_dir = chat_sessions_dir(&project_path).join(QUARANTINE_DIR);
+    if !quarantine_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&quarantine_dir)
+        .map_err(|e| format!("Failed to read quarantine directory: {}", e))?;
+
+    let mut records: Vec<QuarantinedSession> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".reason.json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    records.sort_by(|a, b| b.quarantined_at.cmp(&a.quarantined_at));
+
+    Ok(records)
+}
+
+/// Generate an archive id like the frontend did: "YYYY-MM-DD-chat-<millis>-<suffix>"
+pub fn generate_session_id() -> String {
+    session_id_for(&chrono::Local::now().format("%Y-%m-%d").to_string(), "chat")
+}
+
+/// Session id with a given date prefix and kind, e.g. "2025-01-02-import-<millis>-<suffix>"
+pub fn session_id_for(date: &str, kind: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    // Base-36 suffix from the clock, process id and a counter, so ids never repeat
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed) as u128;
+    let mut seed = nanos ^ ((std::process::id() as u128) << 32) ^ count.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    let mut suffix = String::new();
+    for _ in 0..9 {
+        suffix.push(std::char::from_digit((seed % 36) as u32, 36).unwrap_or('0'));
+        seed /= 36;
+    }
+    format!("{}-{}-{}-{}", date, kind, Utc::now().timestamp_millis(), suffix)
+}
+
+/// Strip command output before writing, keeping the command metadata
+fn prepare_for_disk(session: &mut ChatSession) {
+    for message in &mut session.messages {
+        if message.role == ChatRole::Command {
+            message.content = "(command executed)".to_string();
+            message.extra.remove("isExpanded");
+            message.extra.remove("userToggled");
+        }
+    }
+}
+
+/// Type a session sent by the frontend, which may predate the schema version
+fn session_from_value(session: Value, filename: &str) -> Result<ChatSession, String> {
+    migrate_session(session, filename, &now_timestamp())
+        .map(|(session, _)| session)
+        .map_err(|e| e.message())
+}
+
+fn require_session_file(dir: &Path, filename: &str) -> Result<(), String> {
+    validate_session_filename(filename)?;
+    if !dir.join(filename).is_file() {
+        return Err(format!("Chat session file not found: {}", filename));
+    }
+    Ok(())
+}
+
+/// Record the last used session in .naide/project-config.json, keeping other settings
+fn save_last_chat_session(project_path: &str, filename: &str) -> Result<(), String> {
+    let config_path = PathBuf::from(project_path).join(".naide").join(PROJECT_CONFIG_FILE);
+    let mut config = fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Map<String, Value>>(&content).ok())
+        .unwrap_or_default();
+    config.insert("lastChatSession".to_string(), Value::String(filename.to_string()));
+
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize project config: {}", e))?;
+    file_io::write_atomic(&config_path, content.as_bytes())
+}
+
+// Tauri command: Save a chat session atomically (the active session by default)
+#[tauri::command]
+pub async fn save_chat_session(
+    project_path: String,
+    session: Value,
+    filename: Option<String>,
+) -> Result<ChatSession, String> {
+    let filename = filename.unwrap_or_else(|| ACTIVE_SESSION_FILE.to_string());
+    validate_session_filename(&filename)?;
+    if !filename.ends_with(".json") {
+        return Err("Chat session filename must end with .json".to_string());
+    }
+
+    let dir = chat_sessions_dir(&project_path);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create chat sessions directory: {}", e))?;
+
+    let mut session = session_from_value(session, &filename)?;
+    prepare_for_disk(&mut session);
+    session.updated_at = now_timestamp();
+    write_session(&dir.join(&filename), &session)?;
+    save_last_chat_session(&project_path, &filename)?;
+    chat_index::update_index_entry(&project_path, &filename, &session);
+
+    log::info!("Saved chat session {} with {} messages", filename, session.messages.len());
+    Ok(session)
+}
+
+// Tauri command: Archive a chat session under a generated name
+// Returns the new filename, or None when there are no user messages to keep
+#[tauri::command]
+pub async fn archive_chat_session(project_path: String, session: Value) -> Result<Option<String>, String> {
+    let id = generate_session_id();
+    let filename = format!("{}.json", id);
+    let mut session = session_from_value(session, &filename)?;
+
+    if !session.messages.iter().any(|m| m.role == ChatRole::User) {
+        log::info!("No user messages to archive, skipping");
+        return Ok(None);
+    }
+
+    // Welcome messages are generated by the UI and not worth keeping
+    session.messages.retain(|m| m.role == ChatRole::User || !m.id.starts_with("welcome-"));
+    prepare_for_disk(&mut session);
+
+    let now = now_timestamp();
+    if let Some(first) = session.messages.first() {
+        session.created_at = first.timestamp.clone();
+    }
+    session.id = id;
+    session.updated_at = now.clone();
+    session.saved_at = Some(now);
+
+    let dir = chat_sessions_dir(&project_path);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create chat sessions directory: {}", e))?;
+    write_session(&dir.join(&filename), &session)?;
+    chat_index::update_index_entry(&project_path, &filename, &session);
+
+    log::info!("Archived chat session as {}", filename);
+    Ok(Some(filename))
+}
+
+// Tauri command: Give a chat session a human-readable title (the filename is unchanged)
+#[tauri::command]
+pub async fn rename_chat_session(project_path: String, filename: String, title: String) -> Result<ChatSession, String> {
+    let dir = chat_sessions_dir(&project_path);
+    require_session_file(&dir, &filename)?;
+
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+    if title.chars().count() > MAX_TITLE_CHARS {
+        return Err(format!("Title is too long (max {} characters)", MAX_TITLE_CHARS));
+    }
+
+    let mut session = load_session(&dir, &filename).map_err(|e| e.message())?;
+    session.title = Some(title.to_string());
+    session.updated_at = now_timestamp();
+    write_session(&dir.join(&filename), &session)?;
+    chat_index::update_index_entry(&project_path, &filename, &session);
+
+    log::info!("Renamed chat session {} to \"{}\"", filename, title);
+    Ok(session)
+}
+
+// Tauri command: Copy a chat session up to and including a message into a new session
+// Returns the new filename
+#[tauri::command]
+pub async fn fork_chat_session(project_path: String, filename: String, message_index: usize) -> Result<String, String> {
+    let dir = chat_sessions_dir(&project_path);
+    require_session_file(&dir, &filename)?;
+
+    let mut session = load_session(&dir, &filename).map_err(|e| e.message())?;
+    if message_index >= session.messages.len() {
+        return Err(format!("Message index {} is out of range ({} messages)", message_index, session.messages.len()));
+    }
+    session.messages.truncate(message_index + 1);
+
+    let id = generate_session_id();
+    let new_filename = format!("{}.json", id);
+    let now = now_timestamp();
+    let base_title = session.title.clone()
+        .unwrap_or_else(|| session.first_user_message().chars().take(60).collect());
+    session.title = Some(format!("{} (fork)", base_title.trim()).trim().to_string());
+    session.id = id;
+    session.created_at = now.clone();
+    session.updated_at = now.clone();
+    session.saved_at = Some(now);
+    session.extra.insert("forkedFrom".to_string(), serde_json::json!({
+        "filename": filename,
+        "messageIndex": message_index,
+    }));
+
+    write_session(&dir.join(&new_filename), &session)?;
+    chat_index::update_index_entry(&project_path, &new_filename, &session);
+
+    log::info!("Forked chat session {} at message {} into {}", filename, message_index, new_filename);
+    Ok(new_filename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_legacy_session() {
+        let legacy = json!({
+            "projectName": "demo",
+            "messages": [
+                { "role": "user", "content": "Build a todo app", "timestamp": "2025-01-02T10:00:00.000Z" },
+                { "id": "a1", "role": "Copilot", "content": [{ "text": "Sure" }, "!"], "tool_calls": [{ "id": "t1", "name": "write_file" }] },
+                { "id": "c1", "role": "command", "content": "(command executed)", "command": "npm test", "isExpanded": true }
+            ]
+        });
+
+        let (session, migrated) = migrate_session(legacy, "2025-01-02-chat-1.json", "2025-01-03T00:00:00.000Z").unwrap();
+        assert!(migrated);
+        assert_eq!(session.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(session.id, "2025-01-02-chat-1");
+        assert_eq!(session.mode, "Planning");
+        assert_eq!(session.created_at, "2025-01-02T10:00:00.000Z");
+        assert_eq!(session.updated_at, "2025-01-03T00:00:00.000Z");
+        assert_eq!(session.messages[0].id, "msg-0");
+        assert_eq!(session.messages[1].role, ChatRole::Assistant);
+        assert_eq!(session.messages[1].content, "Sure\n!");
+        assert_eq!(session.messages[1].timestamp, "2025-01-03T00:00:00.000Z");
+        assert_eq!(session.messages[1].tool_calls[0].name, "write_file");
+        assert_eq!(session.messages[2].command.as_deref(), Some("npm test"));
+        assert_eq!(session.messages[2].extra.get("isExpanded"), Some(&json!(true)));
+        assert_eq!(session.first_user_message(), "Build a todo app");
+
+        // Current sessions pass through untouched
+        let current = serde_json::to_value(&session).unwrap();
+        let (_, migrated) = migrate_session(current, "x.json", "").unwrap();
+        assert!(!migrated);
+
+        let newer = json!({ "schemaVersion": CURRENT_SCHEMA_VERSION + 1, "messages": [] });
+        assert!(matches!(migrate_session(newer, "x.json", ""), Err(SessionLoadError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_load_session_migrates_and_quarantines() {
+        let dir = TempDir::new("chat-load");
+        fs::write(dir.join("old.json"), r#"{"id":"old","messages":[{"role":"user","content":"hi"}]}"#).unwrap();
+        fs::write(dir.join("broken.json"), "{ not json").unwrap();
+
+        let session = load_session(&dir, "old.json").unwrap();
+        assert_eq!(session.messages.len(), 1);
+        let on_disk: Value = serde_json::from_str(&fs::read_to_string(dir.join("old.json")).unwrap()).unwrap();
+        assert_eq!(on_disk["schemaVersion"], json!(CURRENT_SCHEMA_VERSION));
+
+        assert!(matches!(load_session(&dir, "broken.json"), Err(SessionLoadError::Corrupt(_))));
+        assert!(!dir.join("broken.json").exists());
+        assert!(dir.join(QUARANTINE_DIR).join("broken.json").exists());
+        let record: QuarantinedSession = serde_json::from_str(
+            &fs::read_to_string(dir.join(QUARANTINE_DIR).join("broken.json.reason.json")).unwrap()
+        ).unwrap();
+        assert!(record.reason.starts_with("invalid JSON"));
+    }
+
+    fn run<T>(future: impl std::future::Future<Output = T>) -> T {
+        tauri::async_runtime::block_on(future)
+    }
+
+    #[test]
+    fn test_save_rename_and_fork() {
+        let root = TempDir::new("chat-commands");
+        let project = root.to_string_lossy().to_string();
+        let session = json!({
+            "id": "default-chat",
+            "projectName": "demo",
+            "messages": [
+                { "id": "welcome-1", "role": "assistant", "content": "Welcome!", "timestamp": "2025-01-02T09:59:00.000Z" },
+                { "id": "u1", "role": "user", "content": "Build a todo app", "timestamp": "2025-01-02T10:00:00.000Z" },
+                { "id": "c1", "role": "command", "content": "lots of output", "timestamp": "2025-01-02T10:01:00.000Z", "isExpanded": true },
+                { "id": "a1", "role": "assistant", "content": "Done", "timestamp": "2025-01-02T10:02:00.000Z" }
+            ]
+        });
+
+        let saved = run(save_chat_session(project.clone(), session.clone(), None)).unwrap();
+        assert_eq!(saved.messages[2].content, "(command executed)");
+        assert!(saved.messages[2].extra.get("isExpanded").is_none());
+        let config = fs::read_to_string(root.join(".naide").join(PROJECT_CONFIG_FILE)).unwrap();
+        assert!(config.contains("\"lastChatSession\": \"default-chat.json\""));
+        assert!(run(save_chat_session(project.clone(), session.clone(), Some("../x.json".to_string()))).is_err());
+        // The chat index shares the folder and is never handled as a session
+        fs::write(chat_sessions_dir(&project).join(INDEX_FILE), "{}").unwrap();
+        assert!(run(save_chat_session(project.clone(), session.clone(), Some(INDEX_FILE.to_string()))).is_err());
+        assert!(run(load_chat_session_file(project.clone(), INDEX_FILE.to_string())).is_err());
+        assert!(chat_sessions_dir(&project).join(INDEX_FILE).is_file());
+
+        let archived = run(archive_chat_session(project.clone(), session)).unwrap().unwrap();
+        let dir = chat_sessions_dir(&project);
+        let archive = load_session(&dir, &archived).unwrap();
+        assert_eq!(archive.messages.len(), 3);
+        assert_eq!(archive.created_at, "2025-01-02T10:00:00.000Z");
+        assert!(archive.saved_at.is_some());
+        assert_eq!(run(archive_chat_session(project.clone(), json!({ "messages": [] }))).unwrap(), None);
+
+        let renamed = run(rename_chat_session(project.clone(), archived.clone(), "  Todo app  ".to_string())).unwrap();
+        assert_eq!(renamed.title.as_deref(), Some("Todo app"));
+        assert!(run(rename_chat_session(project.clone(), archived.clone(), " ".to_string())).is_err());
+
+        let forked = run(fork_chat_session(project.clone(), archived.clone(), 0)).unwrap();
+        let fork = load_session(&dir, &forked).unwrap();
+        assert_ne!(forked, archived);
+        assert_eq!(fork.messages.len(), 1);
+        assert_eq!(fork.title.as_deref(), Some("Todo app (fork)"));
+        assert_eq!(fork.extra["forkedFrom"]["filename"], json!(archived));
+        assert!(run(fork_chat_session(project.clone(), archived, 10)).is_err());
+    }
+}
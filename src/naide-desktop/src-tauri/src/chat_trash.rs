@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::chat_index;
+use crate::chat_sessions::{chat_sessions_dir, load_session, migrate_session, now_timestamp, validate_session_filename};
+use crate::file_io;
+
+const TRASH_DIR: &str = "trash";
+// Deletion times of trashed sessions, keyed by their name in the trash folder
+const TRASH_MANIFEST_FILE: &str = "trash.json";
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashRecord {
+    pub original_filename: String,
+    pub deleted_at: String, // RFC 3339
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedChatSession {
+    pub filename: String, // Name in the trash folder
+    pub original_filename: String,
+    pub deleted_at: String,
+    pub message_count: usize,
+    pub mode: String,
+    pub first_user_message: String,
+    pub title: Option<String>,
+}
+
+fn trash_dir(project_path: &str) -> PathBuf {
+    chat_sessions_dir(project_path).join(TRASH_DIR)
+}
+
+fn read_manifest(trash_dir: &Path) -> HashMap<String, TrashRecord> {
+    fs::read_to_string(trash_dir.join(TRASH_MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(trash_dir: &Path, manifest: &HashMap<String, TrashRecord>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize trash manifest: {}", e))?;
+    file_io::write_atomic(&trash_dir.join(TRASH_MANIFEST_FILE), content.as_bytes())
+}
+
+/// First name in `dir` not taken: "name.json", then "name-1.json", "name-2.json", ...
+fn free_filename(dir: &Path, filename: &str, infix: &str) -> String {
+    let stem = filename.trim_end_matches(".json");
+    let mut candidate = filename.to_string();
+    let mut counter = 1;
+    while dir.join(&candidate).exists() {
+        candidate = format!("{}{}{}.json", stem, infix, counter);
+        counter += 1;
+    }
+    candidate
+}
+
+/// Trashed session files: every .json in the trash folder except the manifest
+fn trashed_files(trash_dir: &Path) -> Vec<String> {
+    fs::read_dir(trash_dir)
+        .map(|entries| entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".json") && name != TRASH_MANIFEST_FILE)
+            .collect())
+        .unwrap_or_default()
+}
+
+fn deletion_record(manifest: &HashMap<String, TrashRecord>, filename: &str) -> TrashRecord {
+    manifest.get(filename).cloned().unwrap_or_else(|| {
+        TrashRecord { original_filename: filename.to_string(), deleted_at: now_timestamp() }
+    })
+}
+
+/// Record trashed files the manifest does not know (trashed before it existed) as deleted now.
+/// Their modification time is when the session was last saved, not when it was deleted.
+/// Returns whether any were added.
+fn record_unknown_files(trash_dir: &Path, manifest: &mut HashMap<String, TrashRecord>) -> bool {
+    let mut added = false;
+    for filename in trashed_files(trash_dir) {
+        if !manifest.contains_key(&filename) {
+            manifest.insert(filename.clone(), deletion_record(manifest, &filename));
+            added = true;
+        }
+    }
+    added
+}
+
+/// Permanently delete trashed sessions older than the retention period.
+/// A retention of 0 days keeps everything. Returns the purged filenames.
+pub fn apply_retention(project_path: &str, retention_days: u32) -> Result<Vec<String>, String> {
+    let trash_dir = trash_dir(project_path);
+    if !trash_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut manifest = read_manifest(&trash_dir);
+    let recorded = record_unknown_files(&trash_dir, &mut manifest);
+    let mut purged = Vec::new();
+
+    let files = if retention_days == 0 { Vec::new() } else { trashed_files(&trash_dir) };
+    let cutoff = Utc::now() - Duration::days(retention_days as i64);
+    for filename in files {
+        let record = deletion_record(&manifest, &filename);
+        let expired = DateTime::parse_from_rfc3339(&record.deleted_at)
+            .map(|deleted| deleted.with_timezone(&Utc) < cutoff)
+            .unwrap_or(false);
+        if expired && fs::remove_file(trash_dir.join(&filename)).is_ok() {
+            manifest.remove(&filename);
+            purged.push(filename);
+        }
+    }
+
+    if recorded || !purged.is_empty() {
+        write_manifest(&trash_dir, &manifest)?;
+    }
+    if !purged.is_empty() {
+        log::info!("Purged {} chat sessions older than {} days from trash", purged.len(), retention_days);
+    }
+    Ok(purged)
+}
+
+// Tauri command: Delete a chat session (move to trash)
+#[tauri::command]
+pub async fn delete_chat_session(project_path: String, filename: String) -> Result<(), String> {
+    // Security check: validate filename (prevent path traversal)
+    validate_session_filename(&filename)?;
+
+    // Construct paths
+    let source_path = chat_sessions_dir(&project_path).join(&filename);
+    let trash_dir = trash_dir(&project_path);
+
+    // Check source file exists
+    if !source_path.exists() {
+        return Err("Chat file not found".to_string());
+    }
+
+    // Create trash directory if it doesn't exist
+    if !trash_dir.exists() {
+        fs::create_dir_all(&trash_dir)
+            .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+    }
+
+    // A session deleted before under the same name keeps its place in the trash
+    let trash_name = free_filename(&trash_dir, &filename, "-");
+
+    // Move the file to trash
+    fs::rename(&source_path, trash_dir.join(&trash_name))
+        .map_err(|e| format!("Failed to move file to trash: {}", e))?;
+
+    let mut manifest = read_manifest(&trash_dir);
+    manifest.insert(trash_name.clone(), TrashRecord {
+        original_filename: filename.clone(),
+        deleted_at: now_timestamp(),
+    });
+    write_manifest(&trash_dir, &manifest)?;
+    chat_index::remove_index_entry(&project_path, &filename);
+
+    log::info!("Moved chat session to trash: {} (as {})", filename, trash_name);
+
+    if let Err(e) = apply_retention(&project_path, DEFAULT_RETENTION_DAYS) {
+        log::warn!("Failed to apply chat trash retention: {}", e);
+    }
+
+    Ok(())
+}
+
+// Tauri command: List trashed chat sessions, most recently deleted first
+// Sessions past the retention period (default 30 days, 0 keeps all) are purged first
+#[tauri::command]
+pub async fn list_trashed_chat_sessions(
+    project_path: String,
+    retention_days: Option<u32>,
+) -> Result<Vec<TrashedChatSession>, String> {
+    apply_retention(&project_path, retention_days.unwrap_or(DEFAULT_RETENTION_DAYS))?;
+
+    let trash_dir = trash_dir(&project_path);
+    if !trash_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let manifest = read_manifest(&trash_dir);
+    let mut sessions = Vec::new();
+
+    for filename in trashed_files(&trash_dir) {
+        let record = deletion_record(&manifest, &filename);
+
+        // Read without migrating or quarantining: the trash is left as it is
+        let session = fs::read_to_string(trash_dir.join(&filename))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .and_then(|raw| migrate_session(raw, &filename, &record.deleted_at).ok())
+            .map(|(session, _)| session);
+
+        sessions.push(TrashedChatSession {
+            filename,
+            original_filename: record.original_filename,
+            deleted_at: record.deleted_at,
+            message_count: session.as_ref().map(|s| s.messages.len()).unwrap_or(0),
+            mode: session.as_ref().map(|s| s.mode.clone()).unwrap_or_default(),
+            first_user_message: session.as_ref().map(|s| s.first_user_message().to_string()).unwrap_or_default(),
+            title: session.and_then(|s| s.title),
+        });
+    }
+
+    sessions.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(sessions)
+}
+
+// Tauri command: Restore a trashed chat session
+// Returns the restored filename, which gets a suffix if the original name is taken
+#[tauri::command]
+pub async fn restore_chat_session(project_path: String, filename: String) -> Result<String, String> {
+    validate_session_filename(&filename)?;
+    let trash_dir = trash_dir(&project_path);
+    let source_path = trash_dir.join(&filename);
+    if filename == TRASH_MANIFEST_FILE || !source_path.is_file() {
+        return Err(format!("Trashed chat session not found: {}", filename));
+    }
+
+    let mut manifest = read_manifest(&trash_dir);
+    let record = deletion_record(&manifest, &filename);
+    let sessions_dir = chat_sessions_dir(&project_path);
+    // trash.json comes with the project (e.g. a cloned repository), so its names are not trusted
+    let original_filename = match validate_session_filename(&record.original_filename) {
+        Ok(()) => record.original_filename,
+        Err(_) => {
+            log::warn!("Ignoring invalid original name {:?} of trashed session {}", record.original_filename, filename);
+            filename.clone()
+        }
+    };
+    let restored_name = free_filename(&sessions_dir, &original_filename, "-restored-");
+
+    fs::rename(&source_path, sessions_dir.join(&restored_name))
+        .map_err(|e| format!("Failed to restore chat session: {}", e))?;
+    manifest.remove(&filename);
+    write_manifest(&trash_dir, &manifest)?;
+    match load_session(&sessions_dir, &restored_name) {
+        Ok(session) => chat_index::update_index_entry(&project_path, &restored_name, &session),
+        Err(e) => log::warn!("Restored chat session {} could not be read: {}", restored_name, e.message()),
+    }
+
+    log::info!("Restored chat session {} as {}", filename, restored_name);
+    Ok(restored_name)
+}
+
+// Tauri command: Permanently delete one trashed chat session, or all of them when no filename is given
+// Returns the number of sessions deleted
+#[tauri::command]
+pub async fn purge_chat_sessions(project_path: String, filename: Option<String>) -> Result<usize, String> {
+    let trash_dir = trash_dir(&project_path);
+    if !trash_dir.is_dir() {
+        return Ok(0);
+    }
+
+    let targets = match filename {
+        Some(filename) => {
+            validate_session_filename(&filename)?;
+            if filename == TRASH_MANIFEST_FILE || !trash_dir.join(&filename).is_file() {
+                return Err(format!("Trashed chat session not found: {}", filename));
+            }
+            vec![filename]
+        }
+        None => trashed_files(&trash_dir),
+    };
+
+    let mut manifest = read_manifest(&trash_dir);
+    for target in &targets {
+        fs::remove_file(trash_dir.join(target))
+            .map_err(|e| format!("Failed to delete chat session: {}", e))?;
+        manifest.remove(target);
+    }
+    write_manifest(&trash_dir, &manifest)?;
+
+    log::info!("Permanently deleted {} chat sessions from trash", targets.len());
+    Ok(targets.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use chrono::SecondsFormat;
+
+    fn run<T>(future: impl std::future::Future<Output = T>) -> T {
+        tauri::async_runtime::block_on(future)
+    }
+
+    fn write_session(project: &str, filename: &str, text: &str) {
+        let dir = chat_sessions_dir(project);
+        fs::create_dir_all(&dir).unwrap();
+        let content = format!(r#"{{"id":"x","messages":[{{"role":"user","content":"{}"}}]}}"#, text);
+        fs::write(dir.join(filename), content).unwrap();
+    }
+
+    #[test]
+    fn test_delete_restore_and_purge() {
+        let root = TempDir::new("chat-trash");
+        let project = root.to_string_lossy().to_string();
+
+        // Deleting the same name twice no longer collides
+        write_session(&project, "a.json", "first");
+        run(delete_chat_session(project.clone(), "a.json".to_string())).unwrap();
+        write_session(&project, "a.json", "second");
+        run(delete_chat_session(project.clone(), "a.json".to_string())).unwrap();
+
+        let trashed = run(list_trashed_chat_sessions(project.clone(), None)).unwrap();
+        assert_eq!(trashed.len(), 2);
+        assert!(trashed.iter().all(|t| t.original_filename == "a.json"));
+        let second = trashed.iter().find(|t| t.first_user_message == "second").unwrap();
+        assert_eq!(second.filename, "a-1.json");
+
+        // Restoring while the original name is taken picks a new one
+        write_session(&project, "a.json", "third");
+        let restored = run(restore_chat_session(project.clone(), "a-1.json".to_string())).unwrap();
+        assert_eq!(restored, "a-restored-1.json");
+        assert!(chat_sessions_dir(&project).join("a-restored-1.json").is_file());
+
+        // An original name pointing out of the sessions folder is replaced by the trash name
+        write_session(&project, "b.json", "escape");
+        run(delete_chat_session(project.clone(), "b.json".to_string())).unwrap();
+        let trash_dir = trash_dir(&project);
+        let mut manifest = read_manifest(&trash_dir);
+        manifest.get_mut("b.json").unwrap().original_filename = "../../b.json".to_string();
+        write_manifest(&trash_dir, &manifest).unwrap();
+        assert_eq!(run(restore_chat_session(project.clone(), "b.json".to_string())).unwrap(), "b.json");
+        assert!(chat_sessions_dir(&project).join("b.json").is_file());
+
+        assert!(run(purge_chat_sessions(project.clone(), Some("trash.json".to_string()))).is_err());
+        assert_eq!(run(purge_chat_sessions(project.clone(), None)).unwrap(), 1);
+        assert!(run(list_trashed_chat_sessions(project.clone(), None)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_retention_purges_old_sessions() {
+        let root = TempDir::new("chat-retention");
+        let project = root.to_string_lossy().to_string();
+        let trash_dir = trash_dir(&project);
+        fs::create_dir_all(&trash_dir).unwrap();
+        fs::write(trash_dir.join("old.json"), "{}").unwrap();
+        fs::write(trash_dir.join("new.json"), "{}").unwrap();
+
+        let old = (Utc::now() - Duration::days(45)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let mut manifest = HashMap::new();
+        manifest.insert("old.json".to_string(), TrashRecord { original_filename: "old.json".to_string(), deleted_at: old });
+        manifest.insert("new.json".to_string(), TrashRecord { original_filename: "new.json".to_string(), deleted_at: now_timestamp() });
+        write_manifest(&trash_dir, &manifest).unwrap();
+
+        assert!(apply_retention(&project, 0).unwrap().is_empty());
+        assert_eq!(apply_retention(&project, DEFAULT_RETENTION_DAYS).unwrap(), vec!["old.json".to_string()]);
+        assert!(trash_dir.join("new.json").exists());
+        assert!(!read_manifest(&trash_dir).contains_key("old.json"));
+
+        // A file missing from the manifest counts as deleted now, however old it is
+        let unknown = trash_dir.join("unknown.json");
+        fs::write(&unknown, "{}").unwrap();
+        let saved = std::time::SystemTime::now() - std::time::Duration::from_secs(60 * 24 * 60 * 60);
+        fs::File::options().write(true).open(&unknown).unwrap().set_modified(saved).unwrap();
+        assert!(apply_retention(&project, DEFAULT_RETENTION_DAYS).unwrap().is_empty());
+        assert!(unknown.exists());
+        assert!(read_manifest(&trash_dir).contains_key("unknown.json"));
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::markdown_outline::{parse_front_matter, parse_outline};
+
+/// Completion counts of the task-list items (`- [ ]` / `- [x]`) in a spec
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct TaskCounts {
+    pub total: usize,
+    pub done: usize,
+}
+
+impl TaskCounts {
+    pub fn add(&mut self, other: TaskCounts) {
+        self.total += other.total;
+        self.done += other.done;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureProgress {
+    pub path: String, // Relative path from .prompts/features/
+    pub date: Option<String>,
+    pub status: String,
+    pub tasks_total: usize,
+    pub tasks_done: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProgressSummary {
+    pub features: usize,
+    pub features_with_tasks: usize,
+    pub features_complete: usize, // All tasks checked (features without tasks are not counted)
+    pub tasks_total: usize,
+    pub tasks_done: usize,
+    pub percent_complete: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupProgress {
+    pub key: String,
+    pub summary: ProgressSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressReport {
+    pub overall: ProgressSummary,
+    pub by_folder: Vec<GroupProgress>, // "" is the top level of .prompts/features/
+    pub by_status: Vec<GroupProgress>,
+    pub features: Vec<FeatureProgress>,
+}
+
+impl ProgressSummary {
+    fn add_feature(&mut self, feature: &FeatureProgress) {
+        self.features += 1;
+        if feature.tasks_total > 0 {
+            self.features_with_tasks += 1;
+            if feature.tasks_done == feature.tasks_total {
+                self.features_complete += 1;
+            }
+        }
+        self.tasks_total += feature.tasks_total;
+        self.tasks_done += feature.tasks_done;
+        self.percent_complete = if self.tasks_total == 0 {
+            0.0
+        } else {
+            (self.tasks_done as f64 / self.tasks_total as f64 * 1000.0).round() / 10.0
+        };
+    }
+}
+
+/// Count task-list items in markdown content (items inside code blocks are ignored)
+pub fn count_tasks(content: &str) -> TaskCounts {
+    let outline = parse_outline(content);
+    TaskCounts {
+        total: outline.tasks.len(),
+        done: outline.tasks.iter().filter(|t| t.checked).count(),
+    }
+}
+
+/// Count task-list items of a spec file on disk (unreadable files count as empty)
+pub fn count_tasks_in_file(path: &Path) -> TaskCounts {
+    fs::read_to_string(path)
+        .map(|content| count_tasks(&content))
+        .unwrap_or_default()
+}
+
+/// Status of a spec from its front matter (`Status: planned`) or a `**Status**:` line,
+/// normalized to a lowercase word such as "planned", "implemented" or "fixed"
+pub fn spec_status(content: &str) -> Option<String> {
+    let raw = parse_front_matter(content)
+        .into_iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("status"))
+        .map(|(_, value)| value)
+        .or_else(|| {
+            content.lines().take(20).find_map(|line| {
+                let cleaned = line.replace("**", "");
+                let cleaned = cleaned.trim();
+                let lower = cleaned.to_lowercase();
+                lower.starts_with("status:").then(|| cleaned["status:".len()..].to_string())
+            })
+        })?;
+
+    // Drop emoji and decorations, keep the first word
+    raw.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '-').to_lowercase())
+        .find(|word| !word.is_empty())
+}
+
+fn collect_features(
+    base_dir: &Path,
+    current_dir: &Path,
+    include_bugs: bool,
+    include_removed: bool,
+    features: &mut Vec<FeatureProgress>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(current_dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        let rel_path = path.strip_prefix(base_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        if path.is_dir() {
+            if (!include_bugs && rel_path == "bugs") || (!include_removed && rel_path == "removed-features") {
+                continue;
+            }
+            collect_features(base_dir, &path, include_bugs, include_removed, features)?;
+        } else if file_name.ends_with(".md") && file_name != "FEATURES_INDEX.md" {
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            let counts = count_tasks(&content);
+            features.push(FeatureProgress {
+                path: rel_path,
+                date: crate::parse_date_from_filename(&file_name),
+                status: spec_status(&content).unwrap_or_else(|| "unknown".to_string()),
+                tasks_total: counts.total,
+                tasks_done: counts.done,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a progress report from a list of features
+pub fn build_report(features: Vec<FeatureProgress>) -> ProgressReport {
+    let mut overall = ProgressSummary::default();
+    let mut by_folder: BTreeMap<String, ProgressSummary> = BTreeMap::new();
+    let mut by_status: BTreeMap<String, ProgressSummary> = BTreeMap::new();
+
+    for feature in &features {
+        overall.add_feature(feature);
+
+        let folder = feature.path.rsplit_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+        by_folder.entry(folder).or_default().add_feature(feature);
+        by_status.entry(feature.status.clone()).or_default().add_feature(feature);
+    }
+
+    let into_groups = |map: BTreeMap<String, ProgressSummary>| {
+        map.into_iter().map(|(key, summary)| GroupProgress { key, summary }).collect()
+    };
+
+    ProgressReport {
+        overall,
+        by_folder: into_groups(by_folder),
+        by_status: into_groups(by_status),
+        features,
+    }
+}
+
+// Tauri command: Aggregate task completion across feature specs
+// from_date/to_date (YYYY-MM-DD, inclusive) filter on the date prefix of the filename
+// Bugs and removed features are left out unless asked for, as in the feature viewer
+#[tauri::command]
+pub async fn get_feature_progress_report(
+    project_path: String,
+    include_bugs: Option<bool>,
+    include_removed: Option<bool>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<ProgressReport, String> {
+    let features_dir = PathBuf::from(&project_path).join(".prompts").join("features");
+
+    let mut features = Vec::new();
+    if features_dir.is_dir() {
+        collect_features(
+            &features_dir,
+            &features_dir,
+            include_bugs.unwrap_or(false),
+            include_removed.unwrap_or(false),
+            &mut features,
+        )?;
+    }
+
+    if from_date.is_some() || to_date.is_some() {
+        features.retain(|f| match &f.date {
+            Some(date) => {
+                from_date.as_ref().map(|from| date >= from).unwrap_or(true)
+                    && to_date.as_ref().map(|to| date <= to).unwrap_or(true)
+            }
+            None => false,
+        });
+    }
+
+    // Most recent first, like the feature tree
+    features.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.path.cmp(&b.path)));
+
+    Ok(build_report(features))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_tasks_ignores_code_blocks() {
+        let content = "- [x] done\n- [ ] todo\n\n```md\n- [ ] example\n```\n";
+        assert_eq!(count_tasks(content), TaskCounts { total: 2, done: 1 });
+    }
+
+    #[test]
+    fn test_spec_status() {
+        assert_eq!(spec_status("---\nStatus: planned\nArea: ui\n---\n# Title").as_deref(), Some("planned"));
+        assert_eq!(spec_status("# Bug\n**Status:** ✅ Fixed (2026-02-03)\n").as_deref(), Some("fixed"));
+        assert_eq!(spec_status("# Nothing here\n"), None);
+    }
+
+    #[test]
+    fn test_build_report_groups() {
+        let feature = |path: &str, status: &str, total, done| FeatureProgress {
+            path: path.to_string(),
+            date: None,
+            status: status.to_string(),
+            tasks_total: total,
+            tasks_done: done,
+        };
+        let report = build_report(vec![
+            feature("a.md", "planned", 4, 1),
+            feature("b.md", "shipped", 2, 2),
+            feature("bugs/c.md", "fixed", 0, 0),
+        ]);
+
+        assert_eq!(report.overall.tasks_total, 6);
+        assert_eq!(report.overall.tasks_done, 3);
+        assert_eq!(report.overall.percent_complete, 50.0);
+        assert_eq!(report.overall.features_complete, 1);
+        assert_eq!(report.by_folder.len(), 2);
+        assert_eq!(report.by_folder[1].key, "bugs");
+        assert_eq!(report.by_status.iter().map(|g| g.key.as_str()).collect::<Vec<_>>(), vec!["fixed", "planned", "shipped"]);
+    }
+}
@@ -0,0 +1,511 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+
+// Local history store layout (all under <project>/.naide/history/):
+//   objects/<sha256>      - content-addressed snapshots, shared across files
+//   index/<sha256>.json   - revision list for one file (keyed by hash of its relative path)
+const HISTORY_DIR: &str = "history";
+const OBJECTS_DIR: &str = "objects";
+const INDEX_DIR: &str = "index";
+
+// Retention limits applied every time a new revision is recorded
+const MAX_REVISIONS_PER_FILE: usize = 50;
+const MAX_REVISION_AGE_DAYS: i64 = 30;
+// Revisions that are always kept regardless of age
+const MIN_REVISIONS_PER_FILE: usize = 5;
+
+// Number of unchanged lines shown around each change in a diff
+const DIFF_CONTEXT_LINES: usize = 3;
+
+// Serializes index updates and garbage collection, so no revision or object is lost between them
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRevision {
+    pub id: String,
+    pub hash: String,
+    pub size: u64,
+    pub timestamp: String, // ISO 8601
+    pub source: String,    // What produced the revision, e.g. "write_feature_file"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileHistoryIndex {
+    path: String, // Relative path from project root, forward slashes
+    revisions: Vec<FileRevision>, // Oldest first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: String, // "context", "added" or "removed"
+    pub content: String,
+    pub old_line: Option<usize>, // 1-based
+    pub new_line: Option<usize>, // 1-based
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub from_revision: String,
+    pub to_revision: Option<String>, // None means the current content on disk
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Compute the hex-encoded SHA-256 of some content
+pub fn content_hash(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn history_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".naide").join(HISTORY_DIR)
+}
+
+fn index_path(project_root: &Path, relative_path: &str) -> PathBuf {
+    let key = content_hash(relative_path.as_bytes());
+    history_dir(project_root).join(INDEX_DIR).join(format!("{}.json", key))
+}
+
+fn object_path(project_root: &Path, hash: &str) -> PathBuf {
+    history_dir(project_root).join(OBJECTS_DIR).join(hash)
+}
+
+/// Normalize a path relative to the project root into the key used by the history store
+pub fn relative_key(project_root: &Path, full_path: &Path) -> Option<String> {
+    full_path
+        .strip_prefix(project_root)
+        .ok()
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+}
+
+fn read_index(project_root: &Path, relative_path: &str) -> Result<FileHistoryIndex, String> {
+    let path = index_path(project_root, relative_path);
+
+    if !path.exists() {
+        return Ok(FileHistoryIndex {
+            path: relative_path.to_string(),
+            revisions: Vec::new(),
+        });
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read history index: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse history index: {}", e))
+}
+
+fn write_index(project_root: &Path, index: &FileHistoryIndex) -> Result<(), String> {
+    let path = index_path(project_root, &index.path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create history index directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize history index: {}", e))?;
+    crate::file_io::write_atomic(&path, json.as_bytes())
+        .map_err(|e| format!("Failed to write history index: {}", e))
+}
+
+/// Record a snapshot of a file's content in the history store.
+/// Returns None when the content is identical to the latest revision.
+pub fn record_snapshot(
+    project_root: &Path,
+    relative_path: &str,
+    content: &[u8],
+    source: &str,
+) -> Result<Option<FileRevision>, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = read_index(project_root, relative_path)?;
+    let hash = content_hash(content);
+
+    if index.revisions.last().map(|r| r.hash == hash).unwrap_or(false) {
+        return Ok(None);
+    }
+
+    // Store the content object (shared by every revision with the same content)
+    let object = object_path(project_root, &hash);
+    if !object.exists() {
+        if let Some(parent) = object.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create history objects directory: {}", e))?;
+        }
+        // Atomic, since an existing object is never rewritten
+        crate::file_io::write_atomic(&object, content)
+            .map_err(|e| format!("Failed to write history object: {}", e))?;
+    }
+
+    let now = chrono::Utc::now();
+    let revision = FileRevision {
+        id: format!("{}-{}", now.timestamp_millis(), &hash[..12]),
+        hash,
+        size: content.len() as u64,
+        timestamp: now.to_rfc3339(),
+        source: source.to_string(),
+    };
+    index.revisions.push(revision.clone());
+
+    let pruned = apply_retention(&mut index.revisions, now);
+    write_index(project_root, &index)?;
+
+    if pruned > 0 {
+        log::debug!("Pruned {} old revisions of {}", pruned, relative_path);
+        if let Err(e) = collect_garbage(project_root) {
+            log::warn!("Failed to clean up history objects: {}", e);
+        }
+    }
+
+    Ok(Some(revision))
+}
+
+/// History key for a file, or None for files outside the project or Naide's own bookkeeping files
+fn tracked_key(project_root: &Path, full_path: &Path) -> Option<String> {
+    let relative_path = relative_key(project_root, full_path)?;
+    if relative_path == ".naide" || relative_path.starts_with(".naide/") {
+        return None;
+    }
+    Some(relative_path)
+}
+
+/// Record the content a file had before a write and the content that was written.
+/// Call after the write succeeded, with `previous` read before it.
+/// History failures are logged and never block the write itself.
+pub fn record_write(project_root: &Path, full_path: &Path, previous: Option<&[u8]>, new_content: &[u8], source: &str) {
+    let relative_path = match tracked_key(project_root, full_path) {
+        Some(rel) => rel,
+        None => return,
+    };
+
+    if let Some(previous) = previous {
+        if let Err(e) = record_snapshot(project_root, &relative_path, previous, source) {
+            log::warn!("Failed to record history for {}: {}", relative_path, e);
+            return;
+        }
+    }
+
+    if let Err(e) = record_snapshot(project_root, &relative_path, new_content, source) {
+        log::warn!("Failed to record history for {}: {}", relative_path, e);
+    }
+}
+
+/// Record the content an editor has just read, so it can serve as a merge base later.
+/// Safe to call without file_io's write lock: record_snapshot serializes index updates itself.
+pub fn record_read(project_root: &Path, full_path: &Path, content: &[u8], source: &str) {
+    if let Some(relative_path) = tracked_key(project_root, full_path) {
+        if let Err(e) = record_snapshot(project_root, &relative_path, content, source) {
+            log::warn!("Failed to record history for {}: {}", relative_path, e);
+        }
+    }
+}
+
+/// Drop revisions beyond the per-file count and age limits.
+/// Returns the number of revisions removed.
+fn apply_retention(revisions: &mut Vec<FileRevision>, now: chrono::DateTime<chrono::Utc>) -> usize {
+    let before = revisions.len();
+
+    if revisions.len() > MAX_REVISIONS_PER_FILE {
+        let excess = revisions.len() - MAX_REVISIONS_PER_FILE;
+        revisions.drain(0..excess);
+    }
+
+    let cutoff = now - chrono::Duration::days(MAX_REVISION_AGE_DAYS);
+    while revisions.len() > MIN_REVISIONS_PER_FILE {
+        let expired = chrono::DateTime::parse_from_rfc3339(&revisions[0].timestamp)
+            .map(|t| t.with_timezone(&chrono::Utc) < cutoff)
+            .unwrap_or(false);
+        if !expired {
+            break;
+        }
+        revisions.remove(0);
+    }
+
+    before - revisions.len()
+}
+
+/// Remove content objects that are no longer referenced by any file index.
+/// Called with HISTORY_LOCK held. An index that cannot be read stops the collection,
+/// since the objects it refers to are unknown.
+fn collect_garbage(project_root: &Path) -> Result<(), String> {
+    let objects_dir = history_dir(project_root).join(OBJECTS_DIR);
+    let index_dir = history_dir(project_root).join(INDEX_DIR);
+
+    if !objects_dir.exists() || !index_dir.exists() {
+        return Ok(());
+    }
+
+    let mut referenced = std::collections::HashSet::new();
+    for entry in fs::read_dir(&index_dir).map_err(|e| format!("Failed to read history index: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        // Skip temporary files of atomic writes
+        if !entry.file_name().to_string_lossy().ends_with(".json") {
+            continue;
+        }
+        let content = fs::read_to_string(entry.path())
+            .map_err(|e| format!("Failed to read history index {:?}: {}", entry.file_name(), e))?;
+        let index = serde_json::from_str::<FileHistoryIndex>(&content)
+            .map_err(|e| format!("Failed to parse history index {:?}: {}", entry.file_name(), e))?;
+        referenced.extend(index.revisions.into_iter().map(|r| r.hash));
+    }
+
+    for entry in fs::read_dir(&objects_dir).map_err(|e| format!("Failed to read history objects: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !referenced.contains(&name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the stored content of a revision
+pub fn read_revision_content(project_root: &Path, relative_path: &str, revision_id: &str) -> Result<Vec<u8>, String> {
+    let index = read_index(project_root, relative_path)?;
+    let revision = index.revisions.iter()
+        .find(|r| r.id == revision_id)
+        .ok_or_else(|| format!("Revision not found: {}", revision_id))?;
+
+    fs::read(object_path(project_root, &revision.hash))
+        .map_err(|e| format!("Failed to read revision content: {}", e))
+}
+
+/// Find the most recent revision of a file whose content has the given hash
+pub fn find_revision_by_hash(project_root: &Path, relative_path: &str, hash: &str) -> Result<Option<FileRevision>, String> {
+    let index = read_index(project_root, relative_path)?;
+    Ok(index.revisions.into_iter().rev().find(|r| r.hash == hash))
+}
+
+/// Compute a line-based diff between two texts, grouped into hunks with context
+pub fn diff_text(old: &str, new: &str) -> Vec<DiffHunk> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut hunks = Vec::new();
+
+    for group in diff.grouped_ops(DIFF_CONTEXT_LINES) {
+        let (first, last) = match (group.first(), group.last()) {
+            (Some(f), Some(l)) => (f, l),
+            _ => continue,
+        };
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+
+        let mut lines = Vec::new();
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let kind = match change.tag() {
+                    ChangeTag::Equal => "context",
+                    ChangeTag::Insert => "added",
+                    ChangeTag::Delete => "removed",
+                };
+                lines.push(DiffLine {
+                    kind: kind.to_string(),
+                    content: change.value().trim_end_matches(['\r', '\n']).to_string(),
+                    old_line: change.old_index().map(|i| i + 1),
+                    new_line: change.new_index().map(|i| i + 1),
+                });
+            }
+        }
+
+        hunks.push(DiffHunk {
+            old_start: old_range.start + 1,
+            old_lines: old_range.len(),
+            new_start: new_range.start + 1,
+            new_lines: new_range.len(),
+            lines,
+        });
+    }
+
+    hunks
+}
+
+/// Resolve a project-relative file path, ensuring it stays inside the project
+fn resolve_in_project(project_path: &str, file_path: &str) -> Result<(PathBuf, PathBuf), String> {
+    let project_root = PathBuf::from(project_path).canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+    let full_path = project_root.join(file_path);
+
+    let parent = full_path.parent()
+        .ok_or_else(|| "Invalid file path: no parent directory".to_string())?;
+    let canonical_parent = parent.canonicalize()
+        .map_err(|e| format!("Invalid parent directory: {}", e))?;
+
+    if !canonical_parent.starts_with(&project_root) {
+        return Err("Access denied: path outside of project directory".to_string());
+    }
+
+    let file_name = full_path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    Ok((project_root, canonical_parent.join(file_name)))
+}
+
+// Tauri command: List recorded revisions of a project file (most recent first)
+#[tauri::command]
+pub async fn list_file_revisions(project_path: String, file_path: String) -> Result<Vec<FileRevision>, String> {
+    let (project_root, full_path) = resolve_in_project(&project_path, &file_path)?;
+    let relative_path = relative_key(&project_root, &full_path)
+        .ok_or_else(|| "Access denied: path outside of project directory".to_string())?;
+
+    let mut revisions = read_index(&project_root, &relative_path)?.revisions;
+    revisions.reverse();
+    Ok(revisions)
+}
+
+// Tauri command: Diff two revisions of a file (to_revision = None diffs against the file on disk)
+#[tauri::command]
+pub async fn diff_file_revisions(
+    project_path: String,
+    file_path: String,
+    from_revision: String,
+    to_revision: Option<String>,
+) -> Result<FileDiff, String> {
+    let (project_root, full_path) = resolve_in_project(&project_path, &file_path)?;
+    let relative_path = relative_key(&project_root, &full_path)
+        .ok_or_else(|| "Access denied: path outside of project directory".to_string())?;
+
+    let old = read_revision_content(&project_root, &relative_path, &from_revision)?;
+    let new = match &to_revision {
+        Some(id) => read_revision_content(&project_root, &relative_path, id)?,
+        None => fs::read(&full_path).unwrap_or_default(),
+    };
+
+    let hunks = diff_text(&String::from_utf8_lossy(&old), &String::from_utf8_lossy(&new));
+
+    Ok(FileDiff {
+        path: relative_path,
+        from_revision,
+        to_revision,
+        hunks,
+    })
+}
+
+// Tauri command: Restore a file to a previous revision (the current content is kept in history)
+#[tauri::command]
+pub async fn restore_file_revision(project_path: String, file_path: String, revision_id: String) -> Result<(), String> {
+    let (project_root, full_path) = resolve_in_project(&project_path, &file_path)?;
+    let relative_path = relative_key(&project_root, &full_path)
+        .ok_or_else(|| "Access denied: path outside of project directory".to_string())?;
+
+    let content = read_revision_content(&project_root, &relative_path, &revision_id)?;
+    crate::file_io::write_recorded(&project_root, &full_path, &content, "restore_file_revision")?;
+
+    log::info!("Restored {} to revision {}", relative_path, revision_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_record_snapshot_skips_unchanged_content() {
+        let project = TempDir::new("history-dedupe");
+
+        let first = record_snapshot(&project, "spec.md", b"one", "test").unwrap();
+        assert!(first.is_some());
+        let second = record_snapshot(&project, "spec.md", b"one", "test").unwrap();
+        assert!(second.is_none());
+        record_snapshot(&project, "spec.md", b"two", "test").unwrap();
+
+        let index = read_index(&project, "spec.md").unwrap();
+        assert_eq!(index.revisions.len(), 2);
+
+        let content = read_revision_content(&project, "spec.md", &index.revisions[0].id).unwrap();
+        assert_eq!(content, b"one");
+    }
+
+    #[test]
+    fn test_retention_limits_revision_count() {
+        let project = TempDir::new("history-retention");
+
+        for i in 0..(MAX_REVISIONS_PER_FILE + 5) {
+            record_snapshot(&project, "spec.md", format!("content {}", i).as_bytes(), "test").unwrap();
+        }
+
+        let index = read_index(&project, "spec.md").unwrap();
+        assert_eq!(index.revisions.len(), MAX_REVISIONS_PER_FILE);
+
+        // Objects of pruned revisions are garbage collected
+        let objects = fs::read_dir(history_dir(&project).join(OBJECTS_DIR)).unwrap().count();
+        assert_eq!(objects, MAX_REVISIONS_PER_FILE);
+    }
+
+    #[test]
+    fn test_garbage_collection_stops_at_unreadable_index() {
+        let project = TempDir::new("history-gc-corrupt");
+
+        record_snapshot(&project, "notes.md", b"notes", "test").unwrap();
+        fs::write(index_path(&project, "notes.md"), "{ half written").unwrap();
+        for i in 0..(MAX_REVISIONS_PER_FILE + 1) {
+            record_snapshot(&project, "spec.md", format!("content {}", i).as_bytes(), "test").unwrap();
+        }
+
+        // The snapshot of notes.md may still be referenced, so it is kept
+        assert!(object_path(&project, &content_hash(b"notes")).exists());
+    }
+
+    #[test]
+    fn test_concurrent_reads_and_writes_keep_every_revision() {
+        let project = TempDir::new("history-concurrent");
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let project = project.to_path_buf();
+                std::thread::spawn(move || {
+                    record_snapshot(&project, "spec.md", format!("version {}", i).as_bytes(), "test").unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(read_index(&project, "spec.md").unwrap().revisions.len(), 8);
+    }
+
+    #[test]
+    fn test_retention_drops_expired_revisions() {
+        let now = chrono::Utc::now();
+        let old = (now - chrono::Duration::days(MAX_REVISION_AGE_DAYS + 1)).to_rfc3339();
+        let mut revisions: Vec<FileRevision> = (0..(MIN_REVISIONS_PER_FILE + 3))
+            .map(|i| FileRevision {
+                id: i.to_string(),
+                hash: i.to_string(),
+                size: 0,
+                timestamp: old.clone(),
+                source: "test".to_string(),
+            })
+            .collect();
+
+        let removed = apply_retention(&mut revisions, now);
+        assert_eq!(removed, 3);
+        assert_eq!(revisions.len(), MIN_REVISIONS_PER_FILE);
+    }
+
+    #[test]
+    fn test_diff_text() {
+        let hunks = diff_text("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(hunks.len(), 1);
+
+        let kinds: Vec<&str> = hunks[0].lines.iter().map(|l| l.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["context", "removed", "added", "context", "added"]);
+        assert_eq!(hunks[0].lines[1].content, "b");
+        assert_eq!(hunks[0].lines[2].content, "B");
+        assert_eq!(hunks[0].old_start, 1);
+        assert_eq!(hunks[0].new_lines, 4);
+    }
+}
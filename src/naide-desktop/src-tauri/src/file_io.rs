@@ -0,0 +1,243 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::Serialize;
+
+use crate::file_history::{self, content_hash};
+
+// Serializes check-then-write in write_checked, so two writers cannot both pass the token check
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Content read together with the token a later write must present
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionedContent {
+    pub content: String,
+    pub token: String,         // SHA-256 of the content on disk
+    pub modified: Option<u64>, // Last modified time in milliseconds since epoch
+}
+
+/// Details of a write rejected because the file changed on disk since it was read
+#[derive(Debug, Clone, Serialize)]
+pub struct WriteConflict {
+    pub kind: String, // Always "conflict", lets the frontend tell it apart from plain errors
+    pub path: String,
+    pub expected_token: String,
+    pub actual_token: Option<String>,  // None if the file no longer exists
+    pub disk_content: Option<String>,  // Content currently on disk
+    pub attempted_content: String,     // Content the caller tried to write
+}
+
+/// Error returned by conflict-aware write commands.
+/// Plain failures serialize as a string so existing callers keep working.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum FileWriteError {
+    Conflict(Box<WriteConflict>),
+    Failed(String),
+}
+
+impl From<String> for FileWriteError {
+    fn from(message: String) -> Self {
+        FileWriteError::Failed(message)
+    }
+}
+
+impl std::fmt::Display for FileWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileWriteError::Conflict(c) => write!(f, "Write conflict: {} changed on disk", c.path),
+            FileWriteError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Read a text file along with its concurrency token
+pub fn read_versioned(path: &Path) -> Result<VersionedContent, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let modified = fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64);
+    let token = content_hash(&bytes);
+    let content = String::from_utf8(bytes)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    Ok(VersionedContent { content, token, modified })
+}
+
+/// Verify that a file still matches the token the caller read it with.
+/// An empty expected token means the file must not exist yet.
+pub fn check_expected_token(
+    path: &Path,
+    display_path: &str,
+    expected_token: Option<&str>,
+    attempted_content: &str,
+) -> Result<(), FileWriteError> {
+    let expected = match expected_token {
+        Some(token) => token,
+        None => return Ok(()),
+    };
+
+    let current = fs::read(path).ok();
+    let actual_token = current.as_deref().map(content_hash);
+
+    let matches = match &actual_token {
+        Some(actual) => actual == expected,
+        None => expected.is_empty(),
+    };
+
+    if matches {
+        return Ok(());
+    }
+
+    log::warn!("Write conflict on {}: expected token {}, found {:?}", display_path, expected, actual_token);
+    Err(FileWriteError::Conflict(Box::new(WriteConflict {
+        kind: "conflict".to_string(),
+        path: display_path.to_string(),
+        expected_token: expected.to_string(),
+        actual_token,
+        disk_content: current.map(|bytes| String::from_utf8_lossy(&bytes).to_string()),
+        attempted_content: attempted_content.to_string(),
+    })))
+}
+
+/// Write a file atomically: write to a temporary file in the same directory,
+/// flush it to disk, then rename it over the destination.
+/// The destination's permissions are carried over to the new file.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    let parent = path.parent()
+        .ok_or_else(|| "Invalid file path: no parent directory".to_string())?;
+    let file_name = path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?
+        .to_string_lossy();
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let temp_path = parent.join(format!(".{}.{}-{}.naide-tmp", file_name, std::process::id(), nanos));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        drop(file);
+        // The rename replaces the inode, so e.g. an executable script would otherwise lose its mode
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        fs::rename(&temp_path, path)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write file: {}", e));
+    }
+
+    Ok(())
+}
+
+/// Resolve an existing file inside base_dir, rejecting paths that escape it.
+/// `scope` names the directory in error messages, e.g. "features".
+pub fn resolve_existing(base_dir: &Path, file_path: &str, scope: &str) -> Result<PathBuf, String> {
+    let canonical_full_path = base_dir.join(file_path).canonicalize()
+        .map_err(|e| format!("Invalid file path: {}", e))?;
+    let canonical_base_dir = base_dir.canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+
+    if !canonical_full_path.starts_with(&canonical_base_dir) {
+        return Err(format!("Access denied: path outside of {} directory", scope));
+    }
+
+    Ok(canonical_full_path)
+}
+
+/// Write content after checking the expected token, recording history and
+/// replacing the file atomically. Returns the token of the written content.
+pub fn write_checked(
+    project_root: &Path,
+    target_path: &Path,
+    display_path: &str,
+    content: &str,
+    expected_token: Option<&str>,
+    source: &str,
+) -> Result<String, FileWriteError> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    check_expected_token(target_path, display_path, expected_token, content)?;
+    write_and_record(project_root, target_path, content.as_bytes(), source)?;
+    Ok(content_hash(content.as_bytes()))
+}
+
+/// Write content atomically and record it in history, without a token check.
+/// Serialized with write_checked, so neither loses the other's revisions.
+pub fn write_recorded(project_root: &Path, target_path: &Path, content: &[u8], source: &str) -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    write_and_record(project_root, target_path, content, source)
+}
+
+fn write_and_record(project_root: &Path, target_path: &Path, content: &[u8], source: &str) -> Result<(), String> {
+    let previous = fs::read(target_path).ok();
+    write_atomic(target_path, content)?;
+
+    // Keep the previous and new content in the local history store, once the new content is on disk
+    file_history::record_write(project_root, target_path, previous.as_deref(), content, source);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_write_atomic_replaces_content() {
+        let dir = TempDir::new("file-io-atomic");
+        let path = dir.join("spec.md");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new("file-io-mode");
+        let path = dir.join("build.sh");
+
+        write_atomic(&path, b"#!/bin/sh\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        write_atomic(&path, b"#!/bin/sh\necho hi\n").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o755);
+    }
+
+    #[test]
+    fn test_check_expected_token() {
+        let dir = TempDir::new("file-io-token");
+        let path = dir.join("spec.md");
+
+        // Empty token: file must not exist yet
+        assert!(check_expected_token(&path, "spec.md", Some(""), "new").is_ok());
+
+        fs::write(&path, "original").unwrap();
+        let token = read_versioned(&path).unwrap().token;
+        assert!(check_expected_token(&path, "spec.md", Some(&token), "mine").is_ok());
+        assert!(check_expected_token(&path, "spec.md", None, "mine").is_ok());
+
+        fs::write(&path, "changed by someone else").unwrap();
+        match check_expected_token(&path, "spec.md", Some(&token), "mine") {
+            Err(FileWriteError::Conflict(conflict)) => {
+                assert_eq!(conflict.disk_content.as_deref(), Some("changed by someone else"));
+                assert_eq!(conflict.attempted_content, "mine");
+            }
+            other => panic!("Expected conflict, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,609 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::file_io;
+
+// Operations that can be undone, newest last
+const JOURNAL_FILE: &str = "file-operations.json";
+const MAX_JOURNAL_ENTRIES: usize = 100;
+// Deleted items are moved here instead of being removed
+const TRASH_DIR: &str = "trash";
+
+static OPERATION_COUNTER: AtomicU64 = AtomicU64::new(0);
+// Serializes read-modify-write of the journal, commands run concurrently
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOperationKind {
+    CreateFile,
+    CreateFolder,
+    Rename,
+    Move,
+    Copy,
+    Delete,
+}
+
+/// A completed file operation, as recorded in .naide/file-operations.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOperation {
+    pub id: String,
+    pub kind: FileOperationKind,
+    pub source: Option<String>,     // Relative path before the operation (rename, move, copy, delete)
+    pub target: Option<String>,     // Relative path after the operation (create, rename, move, copy)
+    pub trash_path: Option<String>, // Where a deleted item lives, relative to the project root
+    pub is_folder: bool,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileOperationJournal {
+    operations: Vec<FileOperation>,
+}
+
+fn naide_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".naide")
+}
+
+fn new_operation_id() -> String {
+    let millis = chrono::Utc::now().timestamp_millis();
+    let counter = OPERATION_COUNTER.fetch_add(1, Ordering::SeqCst);
+    format!("{}-{}-{}", millis, std::process::id(), counter)
+}
+
+fn to_relative(project_root: &Path, path: &Path) -> String {
+    path.strip_prefix(project_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn canonical_root(project_path: &str) -> Result<PathBuf, String> {
+    PathBuf::from(project_path).canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))
+}
+
+/// Resolve an existing path inside the project. The project root itself is rejected.
+pub fn resolve_existing_path(project_root: &Path, relative_path: &str) -> Result<PathBuf, String> {
+    let full_path = project_root.join(relative_path);
+    // Keep symlinks themselves (rename or delete the link, not its target)
+    let parent = full_path.parent()
+        .ok_or_else(|| "Invalid file path: no parent directory".to_string())?
+        .canonicalize()
+        .map_err(|e| format!("Invalid file path: {}", e))?;
+    let file_name = full_path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let resolved = parent.join(file_name);
+
+    if !parent.starts_with(project_root) || resolved == project_root {
+        return Err("Access denied: path outside of project directory".to_string());
+    }
+    if fs::symlink_metadata(&resolved).is_err() {
+        return Err(format!("Path does not exist: {}", relative_path));
+    }
+
+    Ok(resolved)
+}
+
+/// Resolve a path that may not exist yet, including missing parent folders.
+/// `..` segments and absolute paths are rejected; the nearest existing ancestor
+/// must resolve inside the project.
+pub fn resolve_new_path(project_root: &Path, relative_path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(relative_path);
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_os_string()),
+            Component::CurDir => {}
+            _ => return Err("Access denied: path outside of project directory".to_string()),
+        }
+    }
+    if parts.is_empty() {
+        return Err("Invalid file path".to_string());
+    }
+
+    // Find the deepest ancestor that exists and canonicalize it
+    let mut existing = project_root.to_path_buf();
+    let mut index = 0;
+    while index < parts.len() - 1 && existing.join(&parts[index]).exists() {
+        existing = existing.join(&parts[index]);
+        index += 1;
+    }
+    let canonical_existing = existing.canonicalize()
+        .map_err(|e| format!("Invalid parent directory: {}", e))?;
+    if !canonical_existing.starts_with(project_root) {
+        return Err("Access denied: path outside of project directory".to_string());
+    }
+
+    let mut resolved = canonical_existing;
+    for part in &parts[index..] {
+        resolved.push(part);
+    }
+    Ok(resolved)
+}
+
+// The journal and trash are managed by these commands and must not be touched through them,
+// nor can the .naide folder holding them be renamed, moved or deleted as a whole
+fn ensure_not_internal(project_root: &Path, path: &Path) -> Result<(), String> {
+    let rel = to_relative(project_root, path);
+    let internal = [".git", ".naide/trash", &format!(".naide/{}", JOURNAL_FILE)];
+    if rel == ".naide" || internal.iter().any(|p| rel == *p || rel.starts_with(&format!("{}/", p))) {
+        return Err(format!("Access denied: {} is managed by Naide", rel));
+    }
+    Ok(())
+}
+
+fn read_journal(project_root: &Path) -> FileOperationJournal {
+    let path = naide_dir(project_root).join(JOURNAL_FILE);
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_journal(project_root: &Path, journal: &FileOperationJournal) -> Result<(), String> {
+    let dir = naide_dir(project_root);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create .naide directory: {}", e))?;
+    let json = serde_json::to_string_pretty(journal)
+        .map_err(|e| format!("Failed to serialize file operations: {}", e))?;
+    file_io::write_atomic(&dir.join(JOURNAL_FILE), json.as_bytes())
+}
+
+fn record_operation(
+    project_root: &Path,
+    kind: FileOperationKind,
+    source: Option<&Path>,
+    target: Option<&Path>,
+    trash_path: Option<&Path>,
+    is_folder: bool,
+) -> Result<FileOperation, String> {
+    let operation = FileOperation {
+        id: new_operation_id(),
+        kind,
+        source: source.map(|p| to_relative(project_root, p)),
+        target: target.map(|p| to_relative(project_root, p)),
+        trash_path: trash_path.map(|p| to_relative(project_root, p)),
+        is_folder,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut journal = read_journal(project_root);
+    journal.operations.push(operation.clone());
+    if journal.operations.len() > MAX_JOURNAL_ENTRIES {
+        let excess = journal.operations.len() - MAX_JOURNAL_ENTRIES;
+        journal.operations.drain(..excess);
+    }
+    write_journal(project_root, &journal)?;
+
+    log::info!("File operation {:?}: {:?} -> {:?}", kind, operation.source, operation.target);
+    Ok(operation)
+}
+
+fn copy_recursively(source: &Path, target: &Path) -> std::io::Result<()> {
+    let metadata = fs::symlink_metadata(source)?;
+    if metadata.is_dir() {
+        fs::create_dir(target)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursively(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else if metadata.file_type().is_symlink() {
+        let link = fs::read_link(source)?;
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(link, target)
+        }
+        #[cfg(not(unix))]
+        {
+            // Creating symlinks needs extra privileges on Windows, copy what they point to
+            let _ = link;
+            fs::copy(source, target).map(|_| ())
+        }
+    } else {
+        fs::copy(source, target).map(|_| ())
+    }
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+fn is_cross_device(error: &std::io::Error) -> bool {
+    #[cfg(windows)]
+    const CROSS_DEVICE: i32 = 17; // ERROR_NOT_SAME_DEVICE
+    #[cfg(not(windows))]
+    const CROSS_DEVICE: i32 = 18; // EXDEV
+    error.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+/// Rename, falling back to copy and delete when source and target are on different devices
+fn move_path(source: &Path, target: &Path) -> Result<(), String> {
+    match fs::rename(source, target) {
+        Ok(()) => return Ok(()),
+        Err(e) if is_cross_device(&e) => {}
+        Err(e) => return Err(format!("Failed to move {}: {}", source.display(), e)),
+    }
+    copy_recursively(source, target)
+        .map_err(|e| format!("Failed to move {}: {}", source.display(), e))?;
+    remove_path(source)
+        .map_err(|e| format!("Failed to remove {} after copying: {}", source.display(), e))
+}
+
+fn ensure_target_free(target: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(target).is_ok() {
+        return Err(format!("Target already exists: {}", target.display()));
+    }
+    Ok(())
+}
+
+/// Whether two paths name the same file system entry, e.g. "Readme.md" and "README.md"
+/// on a case-insensitive file system
+fn same_entry(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        match (a.canonicalize(), b.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+fn ensure_parent(target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Move an item into .naide/trash, keeping its name. Returns the trash location.
+fn move_to_trash(project_root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let name = path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let trash_path = naide_dir(project_root).join(TRASH_DIR).join(new_operation_id()).join(name);
+    ensure_parent(&trash_path)?;
+    move_path(path, &trash_path)?;
+    Ok(trash_path)
+}
+
+/// "name copy.ext", "name copy 2.ext", ... next to the source
+fn duplicate_name(source: &Path) -> Result<PathBuf, String> {
+    let parent = source.parent().ok_or_else(|| "Invalid file path".to_string())?;
+    let is_dir = source.is_dir();
+    let stem = if is_dir { source.file_name() } else { source.file_stem() }
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let extension = if is_dir {
+        String::new()
+    } else {
+        source.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default()
+    };
+
+    for n in 1..1000 {
+        let suffix = if n == 1 { " copy".to_string() } else { format!(" copy {}", n) };
+        let candidate = parent.join(format!("{}{}{}", stem, suffix, extension));
+        if fs::symlink_metadata(&candidate).is_err() {
+            return Ok(candidate);
+        }
+    }
+    Err("Could not find a free name for the copy".to_string())
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') || name.contains('\\') {
+        return Err(format!("Invalid name: {}", name));
+    }
+    Ok(())
+}
+
+// Tauri command: Create a file (and any missing parent folders) inside the project
+#[tauri::command]
+pub async fn create_project_file(project_path: String, file_path: String, content: Option<String>) -> Result<FileOperation, String> {
+    let root = canonical_root(&project_path)?;
+    let target = resolve_new_path(&root, &file_path)?;
+    ensure_not_internal(&root, &target)?;
+    ensure_target_free(&target)?;
+    ensure_parent(&target)?;
+
+    file_io::write_atomic(&target, content.unwrap_or_default().as_bytes())?;
+    record_operation(&root, FileOperationKind::CreateFile, None, Some(&target), None, false)
+}
+
+// Tauri command: Create a folder (and any missing parents) inside the project
+#[tauri::command]
+pub async fn create_project_folder(project_path: String, folder_path: String) -> Result<FileOperation, String> {
+    let root = canonical_root(&project_path)?;
+    let target = resolve_new_path(&root, &folder_path)?;
+    ensure_not_internal(&root, &target)?;
+    ensure_target_free(&target)?;
+
+    fs::create_dir_all(&target)
+        .map_err(|e| format!("Failed to create folder: {}", e))?;
+    record_operation(&root, FileOperationKind::CreateFolder, None, Some(&target), None, true)
+}
+
+// Tauri command: Rename a file or folder in place
+#[tauri::command]
+pub async fn rename_project_path(project_path: String, path: String, new_name: String) -> Result<FileOperation, String> {
+    validate_name(&new_name)?;
+    let root = canonical_root(&project_path)?;
+    let source = resolve_existing_path(&root, &path)?;
+    ensure_not_internal(&root, &source)?;
+    let target = source.with_file_name(&new_name);
+    ensure_not_internal(&root, &target)?;
+    if target == source {
+        return Err(format!("{} already has that name", path));
+    }
+
+    // Changing only the case of a name on a case-insensitive file system finds the source itself
+    if !same_entry(&source, &target) {
+        ensure_target_free(&target)?;
+    }
+
+    let is_folder = source.is_dir();
+    move_path(&source, &target)?;
+    record_operation(&root, FileOperationKind::Rename, Some(&source), Some(&target), None, is_folder)
+}
+
+// Tauri command: Move a file or folder into another folder of the project
+#[tauri::command]
+pub async fn move_project_path(project_path: String, path: String, target_folder: String) -> Result<FileOperation, String> {
+    let root = canonical_root(&project_path)?;
+    let source = resolve_existing_path(&root, &path)?;
+    ensure_not_internal(&root, &source)?;
+
+    let folder = if target_folder.trim_matches('/').is_empty() {
+        root.clone()
+    } else {
+        resolve_new_path(&root, &target_folder)?
+    };
+    let name = source.file_name().ok_or_else(|| "Invalid file path".to_string())?;
+    let target = folder.join(name);
+    ensure_not_internal(&root, &target)?;
+    ensure_target_free(&target)?;
+
+    if target.starts_with(&source) {
+        return Err("Cannot move a folder into itself".to_string());
+    }
+
+    let is_folder = source.is_dir();
+    fs::create_dir_all(&folder)
+        .map_err(|e| format!("Failed to create folder: {}", e))?;
+    move_path(&source, &target)?;
+    record_operation(&root, FileOperationKind::Move, Some(&source), Some(&target), None, is_folder)
+}
+
+// Tauri command: Copy a file or folder; without target_path it is duplicated next to the source
+#[tauri::command]
+pub async fn copy_project_path(project_path: String, path: String, target_path: Option<String>) -> Result<FileOperation, String> {
+    let root = canonical_root(&project_path)?;
+    let source = resolve_existing_path(&root, &path)?;
+    ensure_not_internal(&root, &source)?;
+
+    let target = match target_path {
+        Some(target_path) => resolve_new_path(&root, &target_path)?,
+        None => duplicate_name(&source)?,
+    };
+    ensure_not_internal(&root, &target)?;
+    ensure_target_free(&target)?;
+
+    if target.starts_with(&source) {
+        return Err("Cannot copy a folder into itself".to_string());
+    }
+
+    let is_folder = source.is_dir();
+    ensure_parent(&target)?;
+    copy_recursively(&source, &target)
+        .map_err(|e| format!("Failed to copy: {}", e))?;
+    record_operation(&root, FileOperationKind::Copy, Some(&source), Some(&target), None, is_folder)
+}
+
+// Tauri command: Delete a file or folder by moving it to .naide/trash
+#[tauri::command]
+pub async fn delete_project_path(project_path: String, path: String) -> Result<FileOperation, String> {
+    let root = canonical_root(&project_path)?;
+    let source = resolve_existing_path(&root, &path)?;
+    ensure_not_internal(&root, &source)?;
+
+    let is_folder = source.is_dir();
+    let trash_path = move_to_trash(&root, &source)?;
+    record_operation(&root, FileOperationKind::Delete, Some(&source), None, Some(&trash_path), is_folder)
+}
+
+// Tauri command: List recorded file operations, newest first
+#[tauri::command]
+pub async fn list_file_operations(project_path: String) -> Result<Vec<FileOperation>, String> {
+    let root = canonical_root(&project_path)?;
+    let mut operations = read_journal(&root).operations;
+    operations.reverse();
+    Ok(operations)
+}
+
+/// Reverse one operation on disk
+fn undo(root: &Path, operation: &FileOperation) -> Result<(), String> {
+    let path_of = |rel: &Option<String>| -> Result<PathBuf, String> {
+        rel.as_ref()
+            .map(|rel| root.join(rel))
+            .ok_or_else(|| format!("Operation {} is missing a path", operation.id))
+    };
+
+    match operation.kind {
+        // Undoing a create or copy keeps the content recoverable in the trash
+        FileOperationKind::CreateFile | FileOperationKind::CreateFolder | FileOperationKind::Copy => {
+            let target = path_of(&operation.target)?;
+            if fs::symlink_metadata(&target).is_ok() {
+                move_to_trash(root, &target)?;
+            }
+            Ok(())
+        }
+        FileOperationKind::Rename | FileOperationKind::Move => {
+            let source = path_of(&operation.source)?;
+            let target = path_of(&operation.target)?;
+            if fs::symlink_metadata(&target).is_err() {
+                return Err(format!("Cannot undo: {} no longer exists", to_relative(root, &target)));
+            }
+            ensure_target_free(&source)?;
+            ensure_parent(&source)?;
+            move_path(&target, &source)
+        }
+        FileOperationKind::Delete => {
+            let source = path_of(&operation.source)?;
+            let trash_path = path_of(&operation.trash_path)?;
+            if fs::symlink_metadata(&trash_path).is_err() {
+                return Err("Cannot undo: the deleted item is no longer in the trash".to_string());
+            }
+            ensure_target_free(&source)?;
+            ensure_parent(&source)?;
+            move_path(&trash_path, &source)?;
+            // Drop the now empty per-item trash folder
+            if let Some(parent) = trash_path.parent() {
+                let _ = fs::remove_dir(parent);
+            }
+            Ok(())
+        }
+    }
+}
+
+// Tauri command: Undo a file operation (the most recent one if no id is given)
+#[tauri::command]
+pub async fn undo_file_operation(project_path: String, operation_id: Option<String>) -> Result<FileOperation, String> {
+    let root = canonical_root(&project_path)?;
+    // Held through the undo so the same operation cannot be undone twice
+    let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut journal = read_journal(&root);
+
+    let index = match &operation_id {
+        Some(id) => journal.operations.iter().position(|op| &op.id == id)
+            .ok_or_else(|| format!("File operation not found: {}", id))?,
+        None => journal.operations.len().checked_sub(1)
+            .ok_or_else(|| "Nothing to undo".to_string())?,
+    };
+
+    let operation = journal.operations[index].clone();
+    undo(&root, &operation)?;
+
+    journal.operations.remove(index);
+    write_journal(&root, &journal)?;
+
+    log::info!("Undid file operation {:?} ({})", operation.kind, operation.id);
+    Ok(operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    // The TempDir must be kept alive for the length of the test
+    fn project(name: &str) -> (TempDir, PathBuf, String) {
+        let dir = TempDir::new(&format!("file-ops-{}", name));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src").join("main.ts"), "main").unwrap();
+        let path = dir.to_string_lossy().to_string();
+        let root = dir.canonicalize().unwrap();
+        (dir, root, path)
+    }
+
+    fn run<T>(future: impl std::future::Future<Output = T>) -> T {
+        tauri::async_runtime::block_on(future)
+    }
+
+    #[test]
+    fn test_resolve_new_path_rejects_escapes() {
+        let (_dir, root, _) = project("resolve");
+        assert_eq!(resolve_new_path(&root, "a/b/c.md").unwrap(), root.join("a/b/c.md"));
+        assert!(resolve_new_path(&root, "../outside.md").is_err());
+        assert!(resolve_new_path(&root, "src/../../outside.md").is_err());
+        assert!(resolve_existing_path(&root, "").is_err());
+    }
+
+    #[test]
+    fn test_operations_and_undo() {
+        let (_dir, root, path) = project("ops");
+
+        run(create_project_file(path.clone(), "docs/notes/todo.md".to_string(), Some("todo".to_string()))).unwrap();
+        assert_eq!(fs::read_to_string(root.join("docs/notes/todo.md")).unwrap(), "todo");
+
+        let copy = run(copy_project_path(path.clone(), "src/main.ts".to_string(), None)).unwrap();
+        assert_eq!(copy.target.as_deref(), Some("src/main copy.ts"));
+
+        run(rename_project_path(path.clone(), "src".to_string(), "lib".to_string())).unwrap();
+        assert!(root.join("lib/main.ts").exists());
+
+        run(delete_project_path(path.clone(), "lib/main.ts".to_string())).unwrap();
+        assert!(!root.join("lib/main.ts").exists());
+        assert!(run(delete_project_path(path.clone(), ".naide/trash".to_string())).is_err());
+
+        // Undo in reverse order: delete, rename, copy
+        run(undo_file_operation(path.clone(), None)).unwrap();
+        assert_eq!(fs::read_to_string(root.join("lib/main.ts")).unwrap(), "main");
+        run(undo_file_operation(path.clone(), None)).unwrap();
+        assert!(root.join("src/main.ts").exists());
+        run(undo_file_operation(path.clone(), None)).unwrap();
+        assert!(!root.join("src/main copy.ts").exists());
+
+        let remaining = run(list_file_operations(path.clone())).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].kind, FileOperationKind::CreateFile);
+    }
+
+    #[test]
+    fn test_move_rejects_moving_into_itself() {
+        let (_dir, root, path) = project("move");
+        fs::create_dir_all(root.join("src/inner")).unwrap();
+        assert!(run(move_project_path(path.clone(), "src".to_string(), "src/inner".to_string())).is_err());
+
+        run(move_project_path(path.clone(), "src/main.ts".to_string(), "".to_string())).unwrap();
+        assert!(root.join("main.ts").exists());
+    }
+
+    #[test]
+    fn test_rename_never_replaces_another_entry() {
+        let (_dir, root, path) = project("rename");
+        assert!(run(rename_project_path(path.clone(), "src/main.ts".to_string(), "main.ts".to_string())).is_err());
+
+        fs::write(root.join("src/Main.ts"), "other").unwrap();
+        if same_entry(&root.join("src/main.ts"), &root.join("src/Main.ts")) {
+            // Case-insensitive file system: the write above replaced main.ts
+            run(rename_project_path(path.clone(), "src/main.ts".to_string(), "MAIN.ts".to_string())).unwrap();
+        } else {
+            assert!(run(rename_project_path(path.clone(), "src/Main.ts".to_string(), "main.ts".to_string())).is_err());
+            assert_eq!(fs::read_to_string(root.join("src/main.ts")).unwrap(), "main");
+        }
+        assert!(run(list_file_operations(path.clone())).unwrap().len() <= 1);
+    }
+
+    #[test]
+    fn test_naide_folder_is_protected() {
+        let (_dir, root, path) = project("internal");
+        fs::create_dir_all(root.join(".naide/trash")).unwrap();
+
+        assert!(run(rename_project_path(path.clone(), ".naide".to_string(), "naide".to_string())).is_err());
+        assert!(run(move_project_path(path.clone(), ".naide".to_string(), "src".to_string())).is_err());
+        assert!(run(delete_project_path(path.clone(), ".naide".to_string())).is_err());
+        assert!(root.join(".naide/trash").is_dir());
+
+        // Only a cross-device rename falls back to copying
+        assert!(is_cross_device(&std::io::Error::from_raw_os_error(if cfg!(windows) { 17 } else { 18 })));
+        assert!(!is_cross_device(&std::io::Error::from(std::io::ErrorKind::PermissionDenied)));
+        assert!(move_path(&root.join("missing.ts"), &root.join("moved.ts")).is_err());
+    }
+}
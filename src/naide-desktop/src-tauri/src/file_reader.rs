@@ -0,0 +1,461 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::file_io;
+
+// Largest slice of a file returned by one call; larger files are paged
+pub const MAX_READ_BYTES: u64 = 2 * 1024 * 1024;
+// Images are returned whole, up to this size
+pub const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+// UTF-16 files are decoded as a whole for line ranges, up to this size
+const MAX_UTF16_LINE_SCAN_BYTES: u64 = 16 * 1024 * 1024;
+// Bytes inspected to detect encoding and binary content
+const SNIFF_BYTES: usize = 8192;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16le,
+    Utf16be,
+    Latin1,
+    Binary,
+}
+
+impl TextEncoding {
+    fn bom_len(self) -> u64 {
+        match self {
+            TextEncoding::Utf8Bom => 3,
+            TextEncoding::Utf16le | TextEncoding::Utf16be => 2,
+            _ => 0,
+        }
+    }
+
+    fn is_utf16(self) -> bool {
+        matches!(self, TextEncoding::Utf16le | TextEncoding::Utf16be)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReadOptions {
+    pub offset: Option<u64>,       // Byte range start (ignored when start_line is set)
+    pub length: Option<u64>,       // Byte range length, capped at MAX_READ_BYTES
+    pub start_line: Option<usize>, // 1-based first line of a line range
+    pub line_count: Option<usize>,
+    pub binary_as_base64: Option<bool>, // Return the byte range of a binary file as base64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReadResult {
+    pub path: String,
+    pub size: u64,
+    pub encoding: TextEncoding,
+    pub is_binary: bool,
+    pub mime_type: Option<String>,
+    pub content: Option<String>, // Decoded text of the range (None for binary files)
+    pub base64: Option<String>,  // Whole image, or the range of a binary file if requested
+    pub start_byte: u64,
+    pub end_byte: u64,           // Exclusive; pass as the next offset to continue
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>, // Last line included (line ranges only)
+    pub eof: bool,
+    pub truncated: bool,         // The last line was cut at MAX_READ_BYTES; continue from end_byte as a byte range
+}
+
+/// MIME type of the image formats the viewer can display
+pub fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    Some(match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        _ => return None,
+    })
+}
+
+/// Detect the encoding from the first bytes of a file
+pub fn detect_encoding(head: &[u8]) -> TextEncoding {
+    if head.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return TextEncoding::Utf8Bom;
+    }
+    if head.starts_with(&[0xFF, 0xFE]) {
+        return TextEncoding::Utf16le;
+    }
+    if head.starts_with(&[0xFE, 0xFF]) {
+        return TextEncoding::Utf16be;
+    }
+    if head.contains(&0) {
+        return TextEncoding::Binary;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => TextEncoding::Utf8,
+        // A multi-byte character cut off by a full sniff window is still UTF-8
+        Err(e) if e.error_len().is_none() && head.len() >= SNIFF_BYTES => TextEncoding::Utf8,
+        Err(_) => TextEncoding::Latin1,
+    }
+}
+
+fn decode(bytes: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => String::from_utf8_lossy(bytes).to_string(),
+        TextEncoding::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+        TextEncoding::Utf16le | TextEncoding::Utf16be => {
+            let units: Vec<u16> = bytes.chunks_exact(2)
+                .map(|pair| if encoding == TextEncoding::Utf16le {
+                    u16::from_le_bytes([pair[0], pair[1]])
+                } else {
+                    u16::from_be_bytes([pair[0], pair[1]])
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        TextEncoding::Binary => String::new(),
+    }
+}
+
+/// Move a byte range to character boundaries: the start forward past UTF-8
+/// continuation bytes, the end back before an incomplete character
+fn align_range(bytes: &[u8], encoding: TextEncoding, at_eof: bool) -> (usize, usize) {
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+            let start = bytes.iter().take(3).take_while(|&&b| b & 0xC0 == 0x80).count();
+            let mut end = bytes.len();
+            if !at_eof {
+                // Walk back over a trailing partial character
+                let mut i = end;
+                while i > start && end - i < 4 {
+                    i -= 1;
+                    let b = bytes[i];
+                    if b & 0xC0 != 0x80 {
+                        let needed = if b >= 0xF0 { 4 } else if b >= 0xE0 { 3 } else if b >= 0xC0 { 2 } else { 1 };
+                        if end - i < needed {
+                            end = i;
+                        }
+                        break;
+                    }
+                }
+            }
+            (start, end.max(start))
+        }
+        TextEncoding::Utf16le | TextEncoding::Utf16be => (0, bytes.len() - bytes.len() % 2),
+        _ => (0, bytes.len()),
+    }
+}
+
+fn read_byte_range(file: &mut File, offset: u64, length: u64) -> Result<Vec<u8>, String> {
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut buffer = Vec::with_capacity(length as usize);
+    file.take(length).read_to_end(&mut buffer)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(buffer)
+}
+
+/// Lines of a byte-oriented file read by `read_line_range`
+struct LineRange {
+    bytes: Vec<u8>,
+    start: u64,
+    lines_read: usize,
+    eof: bool,
+    truncated: bool, // The last line did not fit in MAX_READ_BYTES
+}
+
+/// Skip one line without keeping it. Returns the bytes skipped (0 at the end of the file).
+fn skip_line(reader: &mut impl BufRead) -> Result<u64, String> {
+    let mut skipped = 0;
+    loop {
+        let buffer = reader.fill_buf()
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if buffer.is_empty() {
+            return Ok(skipped);
+        }
+        let (used, done) = match buffer.iter().position(|&b| b == b'\n') {
+            Some(index) => (index + 1, true),
+            None => (buffer.len(), false),
+        };
+        reader.consume(used);
+        skipped += used as u64;
+        if done {
+            return Ok(skipped);
+        }
+    }
+}
+
+/// Read a line range of a byte-oriented (UTF-8 or Latin-1) file by streaming it.
+/// At most MAX_READ_BYTES are held, however long the lines are.
+fn read_line_range(file: File, first_line: usize, line_count: usize, skip: u64) -> Result<LineRange, String> {
+    let mut reader = BufReader::new(file);
+    reader.seek(SeekFrom::Start(skip))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let mut position = skip;
+    for _ in 1..first_line {
+        let read = skip_line(&mut reader)?;
+        if read == 0 {
+            return Ok(LineRange { bytes: Vec::new(), start: position, lines_read: 0, eof: true, truncated: false });
+        }
+        position += read;
+    }
+
+    let mut range = LineRange { bytes: Vec::new(), start: position, lines_read: 0, eof: false, truncated: false };
+    let mut line = Vec::new();
+    while range.lines_read < line_count && (range.bytes.len() as u64) < MAX_READ_BYTES {
+        line.clear();
+        let room = MAX_READ_BYTES - range.bytes.len() as u64;
+        let read = (&mut reader).take(room).read_until(b'\n', &mut line)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            range.eof = true;
+            return Ok(range);
+        }
+        range.bytes.extend_from_slice(&line);
+        range.lines_read += 1;
+        if read as u64 == room && !line.ends_with(b"\n") {
+            range.truncated = true;
+        }
+    }
+
+    range.eof = reader.fill_buf().map(|buf| buf.is_empty()).unwrap_or(true);
+    range.truncated &= !range.eof;
+    Ok(range)
+}
+
+/// Read a file (or part of it) with encoding and binary detection
+pub fn read_file(path: &Path, display_path: &str, options: &ReadOptions) -> Result<FileReadResult, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+    let mut file = File::open(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let head = read_byte_range(&mut file, 0, SNIFF_BYTES as u64)?;
+    let mut encoding = detect_encoding(&head);
+    let mime_type = image_mime_type(path);
+
+    // Raster images are binary whatever their first bytes look like
+    if mime_type.is_some() && mime_type != Some("image/svg+xml") {
+        encoding = TextEncoding::Binary;
+    }
+    let is_binary = encoding == TextEncoding::Binary;
+
+    let mut result = FileReadResult {
+        path: display_path.to_string(),
+        size,
+        encoding,
+        is_binary,
+        mime_type: mime_type.map(|m| m.to_string()),
+        content: None,
+        base64: None,
+        start_byte: 0,
+        end_byte: 0,
+        start_line: None,
+        end_line: None,
+        eof: true,
+        truncated: false,
+    };
+
+    // Images are returned whole so the viewer can show them as a data URL
+    if mime_type.is_some() {
+        if size > MAX_IMAGE_BYTES {
+            return Err(format!("Image is too large to display ({} bytes, limit {})", size, MAX_IMAGE_BYTES));
+        }
+        let bytes = read_byte_range(&mut file, 0, size)?;
+        result.base64 = Some(base64::engine::general_purpose::STANDARD.encode(&bytes));
+        if !is_binary {
+            result.content = Some(decode(&bytes, encoding));
+        }
+        result.end_byte = size;
+        return Ok(result);
+    }
+
+    let bom = encoding.bom_len();
+
+    if let Some(first_line) = options.start_line {
+        let first_line = first_line.max(1);
+        let line_count = options.line_count.unwrap_or(usize::MAX);
+        if is_binary {
+            return Err("Line ranges are not available for binary files".to_string());
+        }
+
+        let (text, start_byte, end_byte, lines_read, eof, truncated) = if encoding.is_utf16() {
+            if size > MAX_UTF16_LINE_SCAN_BYTES {
+                return Err("Line ranges are not supported for UTF-16 files this large; use byte ranges".to_string());
+            }
+            let all = read_byte_range(&mut file, bom, size - bom)?;
+            let text = decode(&all, encoding);
+            let lines: Vec<&str> = text.split_inclusive('\n').collect();
+            let from = (first_line - 1).min(lines.len());
+            let to = from.saturating_add(line_count).min(lines.len());
+            let before: usize = lines[..from].iter().map(|l| l.encode_utf16().count() * 2).sum();
+            let selected = lines[from..to].concat();
+            let start_byte = bom + before as u64;
+            let end_byte = start_byte + selected.encode_utf16().count() as u64 * 2;
+            (selected, start_byte, end_byte, to - from, to == lines.len(), false)
+        } else {
+            let range = read_line_range(file, first_line, line_count, bom)?;
+            // A cut line may end inside a character
+            let end = if range.truncated { align_range(&range.bytes, encoding, false).1 } else { range.bytes.len() };
+            let end_byte = range.start + end as u64;
+            (decode(&range.bytes[..end], encoding), range.start, end_byte, range.lines_read, range.eof, range.truncated)
+        };
+
+        result.content = Some(text);
+        result.start_byte = start_byte;
+        result.end_byte = end_byte;
+        result.start_line = Some(first_line);
+        result.end_line = Some(first_line + lines_read.saturating_sub(1));
+        result.eof = eof;
+        result.truncated = truncated;
+        return Ok(result);
+    }
+
+    let offset = options.offset.unwrap_or(0).max(bom).min(size);
+    let length = options.length.unwrap_or(MAX_READ_BYTES).min(MAX_READ_BYTES);
+    let bytes = read_byte_range(&mut file, offset, length)?;
+    let at_eof = offset + bytes.len() as u64 >= size;
+
+    if is_binary {
+        if options.binary_as_base64.unwrap_or(false) {
+            result.base64 = Some(base64::engine::general_purpose::STANDARD.encode(&bytes));
+        }
+        result.start_byte = offset;
+        result.end_byte = offset + bytes.len() as u64;
+        result.eof = at_eof;
+        return Ok(result);
+    }
+
+    // UTF-16 ranges are counted from the BOM so code units stay aligned
+    let aligned_offset = if encoding.is_utf16() && (offset - bom) % 2 == 1 { offset + 1 } else { offset };
+    let skew = (aligned_offset - offset) as usize;
+    let bytes = &bytes[skew.min(bytes.len())..];
+    let (start, end) = align_range(bytes, encoding, at_eof);
+
+    result.content = Some(decode(&bytes[start..end], encoding));
+    result.start_byte = aligned_offset + start as u64;
+    result.end_byte = aligned_offset + end as u64;
+    result.eof = result.end_byte >= size;
+    Ok(result)
+}
+
+// Tauri command: Read a project file with encoding detection, binary detection and paging
+// Text is returned for the requested byte or line range; images are returned whole as base64
+#[tauri::command]
+pub async fn read_project_file_range(
+    project_path: String,
+    file_path: String,
+    options: Option<ReadOptions>,
+) -> Result<FileReadResult, String> {
+    let full_path = file_io::resolve_existing(&PathBuf::from(&project_path), &file_path, "project")?;
+    if !full_path.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+    read_file(&full_path, &file_path, &options.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    // The file is removed with the returned TempDir
+    fn temp_file(name: &str, bytes: &[u8]) -> (TempDir, PathBuf) {
+        let dir = TempDir::new(&format!("file-reader-{}", name));
+        let path = dir.join(name);
+        fs::write(&path, bytes).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(detect_encoding(b"plain text"), TextEncoding::Utf8);
+        assert_eq!(detect_encoding("héllo".as_bytes()), TextEncoding::Utf8);
+        let mut cut_off = vec![b'a'; SNIFF_BYTES - 1];
+        cut_off.push(0xC3);
+        assert_eq!(detect_encoding(&cut_off), TextEncoding::Utf8);
+        assert_eq!(detect_encoding(&[0xEF, 0xBB, 0xBF, b'a']), TextEncoding::Utf8Bom);
+        assert_eq!(detect_encoding(&[0xFF, 0xFE, b'a', 0]), TextEncoding::Utf16le);
+        assert_eq!(detect_encoding(&[b'c', b'a', b'f', 0xE9]), TextEncoding::Latin1);
+        assert_eq!(detect_encoding(&[0x7F, b'E', b'L', b'F', 0, 1]), TextEncoding::Binary);
+    }
+
+    #[test]
+    fn test_byte_ranges_stay_on_character_boundaries() {
+        let (_dir, path) = temp_file("ranges.txt", "aé€b".as_bytes()); // 1 + 2 + 3 + 1 bytes
+
+        // Ends inside '€': cut back to before it
+        let result = read_file(&path, "ranges.txt", &ReadOptions { offset: Some(0), length: Some(4), ..Default::default() }).unwrap();
+        assert_eq!(result.content.as_deref(), Some("aé"));
+        assert_eq!(result.end_byte, 3);
+        assert!(!result.eof);
+
+        // Starts inside 'é': skip forward to '€'
+        let result = read_file(&path, "ranges.txt", &ReadOptions { offset: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(result.content.as_deref(), Some("€b"));
+        assert_eq!(result.start_byte, 3);
+        assert!(result.eof);
+    }
+
+    #[test]
+    fn test_line_ranges_and_encodings() {
+        let (_dir, path) = temp_file("lines.txt", b"one\ntwo\nthree\nfour\n");
+        let result = read_file(&path, "lines.txt", &ReadOptions { start_line: Some(2), line_count: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(result.content.as_deref(), Some("two\nthree\n"));
+        assert_eq!((result.start_byte, result.end_byte), (4, 14));
+        assert_eq!((result.start_line, result.end_line), (Some(2), Some(3)));
+        assert!(!result.eof);
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("a\nb\n".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        let (_dir, path) = temp_file("utf16.txt", &utf16);
+        let result = read_file(&path, "utf16.txt", &ReadOptions { start_line: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(result.encoding, TextEncoding::Utf16le);
+        assert_eq!(result.content.as_deref(), Some("b\n"));
+        assert_eq!(result.start_byte, 6);
+        assert!(result.eof);
+
+        let (_dir, path) = temp_file("latin1.txt", &[b'c', b'a', b'f', 0xE9]);
+        let result = read_file(&path, "latin1.txt", &ReadOptions::default()).unwrap();
+        assert_eq!(result.content.as_deref(), Some("café"));
+    }
+
+    #[test]
+    fn test_long_line_is_cut_at_read_limit() {
+        let mut bytes = b"short\n".to_vec();
+        bytes.extend(vec![b'x'; MAX_READ_BYTES as usize + 10]);
+        bytes.extend_from_slice(b"\nlast\n");
+        let (_dir, path) = temp_file("long-line.txt", &bytes);
+
+        let result = read_file(&path, "long-line.txt", &ReadOptions { start_line: Some(1), ..Default::default() }).unwrap();
+        assert!(result.truncated);
+        assert!(!result.eof);
+        assert_eq!(result.end_byte - result.start_byte, MAX_READ_BYTES);
+        assert_eq!(result.end_line, Some(2));
+
+        // Skipping over the long line does not need to hold it
+        let result = read_file(&path, "long-line.txt", &ReadOptions { start_line: Some(3), ..Default::default() }).unwrap();
+        assert_eq!(result.content.as_deref(), Some("last\n"));
+        assert!(!result.truncated);
+        assert!(result.eof);
+    }
+
+    #[test]
+    fn test_binary_and_images() {
+        let (_dir, path) = temp_file("data.bin", &[1, 0, 2, 3]);
+        let result = read_file(&path, "data.bin", &ReadOptions::default()).unwrap();
+        assert!(result.is_binary);
+        assert!(result.content.is_none() && result.base64.is_none());
+
+        let (_dir, path) = temp_file("logo.png", &[0x89, b'P', b'N', b'G']);
+        let result = read_file(&path, "logo.png", &ReadOptions::default()).unwrap();
+        assert_eq!(result.mime_type.as_deref(), Some("image/png"));
+        assert_eq!(result.base64.as_deref(), Some("iVBORw=="));
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+
+// Project-level ignore file, same syntax as .gitignore
+pub const NAIDE_IGNORE_FILE: &str = ".naideignore";
+
+// Ignored unless a project re-includes them (e.g. "!target/" in .naideignore)
+const DEFAULT_IGNORES: &[&str] = &[
+    "node_modules/",
+    "bin/",
+    "obj/",
+    "dist/",
+    "build/",
+    "out/",
+    "target/", // Rust
+    ".naide/",
+    "__pycache__/", // Python
+    ".venv/", // Python
+    "venv/", // Python
+];
+
+/// Ignore rules of one project: built-in defaults, `.git/info/exclude`, `.gitignore`
+/// files (the root one and nested ones) and `.naideignore`, in increasing priority.
+/// Shared by the project tree, the project watcher, app detection and file search.
+pub struct ProjectIgnore {
+    root: PathBuf,
+    root_matcher: Gitignore,
+    // .naideignore on its own, so it can overrule nested .gitignore files too
+    naide_matcher: Gitignore,
+    // Nested .gitignore matchers by directory, loaded on first use
+    nested: Mutex<HashMap<PathBuf, Option<Gitignore>>>,
+}
+
+impl ProjectIgnore {
+    pub fn load(project_root: &Path) -> ProjectIgnore {
+        let mut builder = GitignoreBuilder::new(project_root);
+
+        for pattern in DEFAULT_IGNORES {
+            let _ = builder.add_line(None, pattern);
+        }
+
+        for file in [
+            project_root.join(".git").join("info").join("exclude"),
+            project_root.join(".gitignore"),
+        ] {
+            if file.is_file() {
+                if let Some(e) = builder.add(&file) {
+                    log::warn!("Problem reading ignore file {:?}: {}", file, e);
+                }
+            }
+        }
+
+        let root_matcher = builder.build().unwrap_or_else(|e| {
+            log::warn!("Failed to build ignore rules for {:?}: {}", project_root, e);
+            Gitignore::empty()
+        });
+
+        let naide_file = project_root.join(NAIDE_IGNORE_FILE);
+        let naide_matcher = if naide_file.is_file() {
+            let (matcher, error) = Gitignore::new(&naide_file);
+            if let Some(e) = error {
+                log::warn!("Problem reading ignore file {:?}: {}", naide_file, e);
+            }
+            matcher
+        } else {
+            Gitignore::empty()
+        };
+
+        ProjectIgnore {
+            root: project_root.to_path_buf(),
+            root_matcher,
+            naide_matcher,
+            nested: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn nested_matcher(&self, dir: &Path) -> Option<Gitignore> {
+        let mut nested = self.nested.lock().unwrap();
+        nested.entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let file = self.root.join(dir).join(".gitignore");
+                if !file.is_file() {
+                    return None;
+                }
+                let (matcher, error) = Gitignore::new(&file);
+                if let Some(e) = error {
+                    log::warn!("Problem reading ignore file {:?}: {}", file, e);
+                }
+                Some(matcher)
+            })
+            .clone()
+    }
+
+    /// Whether a path relative to the project root (forward slashes) is ignored
+    pub fn is_ignored_relative(&self, rel_path: &str, is_dir: bool) -> bool {
+        let rel = Path::new(rel_path.trim_start_matches('/'));
+        if rel.as_os_str().is_empty() {
+            return false;
+        }
+
+        // Git metadata is never part of the project tree
+        if rel.components().any(|c| c.as_os_str() == ".git") {
+            return true;
+        }
+
+        // .naideignore has the last word, over every .gitignore
+        match self.naide_matcher.matched_path_or_any_parents(rel, is_dir) {
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+            Match::None => {}
+        }
+
+        // The deepest .gitignore that has an opinion wins
+        let ancestors: Vec<&Path> = rel.ancestors().skip(1).filter(|a| !a.as_os_str().is_empty()).collect();
+        for dir in ancestors {
+            if let Some(matcher) = self.nested_matcher(dir) {
+                let sub = rel.strip_prefix(dir).unwrap_or(rel);
+                match matcher.matched_path_or_any_parents(sub, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+        }
+
+        self.root_matcher.matched_path_or_any_parents(rel, is_dir).is_ignore()
+    }
+
+    /// Whether an absolute path inside the project is ignored (paths outside are not)
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        match path.strip_prefix(&self.root) {
+            Ok(rel) => self.is_ignored_relative(&rel.to_string_lossy().replace('\\', "/"), is_dir),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Whether a changed file is one of the ignore files, so rules need reloading
+pub fn is_ignore_file(rel_path: &str) -> bool {
+    rel_path.ends_with(".gitignore") || rel_path == NAIDE_IGNORE_FILE || rel_path == ".git/info/exclude"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_ignore_sources_and_priority() {
+        let root = TempDir::new("ignore");
+        fs::create_dir_all(root.join(".git").join("info")).unwrap();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(root.join(".git").join("info").join("exclude"), "secret.txt\n").unwrap();
+        fs::write(root.join(".gitignore"), "*.log\ncoverage/\n").unwrap();
+        fs::write(root.join(NAIDE_IGNORE_FILE), "!target/\ndrafts/\n").unwrap();
+        fs::write(root.join("web").join(".gitignore"), "generated/\n!keep.log\n").unwrap();
+
+        let rules = ProjectIgnore::load(&root);
+
+        // Defaults
+        assert!(rules.is_ignored_relative("node_modules", true));
+        assert!(rules.is_ignored_relative("web/node_modules/react/index.js", false));
+        assert!(rules.is_ignored_relative(".git/HEAD", false));
+        // Re-included by .naideignore
+        assert!(!rules.is_ignored_relative("target/debug/app", false));
+        // .git/info/exclude, .gitignore and .naideignore
+        assert!(rules.is_ignored_relative("secret.txt", false));
+        assert!(rules.is_ignored_relative("server.log", false));
+        assert!(rules.is_ignored_relative("coverage/index.html", false));
+        assert!(rules.is_ignored_relative("drafts", true));
+        // Nested .gitignore, including overriding the root rules
+        assert!(rules.is_ignored_relative("web/generated/api.ts", false));
+        assert!(!rules.is_ignored_relative("web/keep.log", false));
+        assert!(!rules.is_ignored_relative("src/main.rs", false));
+
+        assert!(rules.is_ignored(&root.join("server.log"), false));
+        assert!(!rules.is_ignored(Path::new("/elsewhere/server.log"), false));
+    }
+
+    #[test]
+    fn test_naideignore_overrules_nested_gitignore() {
+        let root = TempDir::new("ignore-nested");
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(root.join(NAIDE_IGNORE_FILE), "!web/generated/\nweb/notes.md\n").unwrap();
+        fs::write(root.join("web").join(".gitignore"), "generated/\n!notes.md\n").unwrap();
+
+        let rules = ProjectIgnore::load(&root);
+        assert!(!rules.is_ignored_relative("web/generated/api.ts", false));
+        assert!(rules.is_ignored_relative("web/notes.md", false));
+    }
+}
@@ -0,0 +1,281 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+use base64::Engine;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::file_history::content_hash;
+use crate::file_io;
+use crate::file_reader::MAX_IMAGE_BYTES;
+
+// Thumbnails live under the project's .naide directory
+const THUMBNAIL_DIR: &str = "cache/thumbnails";
+const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+const MIN_THUMBNAIL_SIZE: u32 = 16;
+const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImagePreview {
+    pub path: String,
+    pub format: String,             // png, jpeg, gif, webp, ico or svg
+    pub width: Option<u32>,         // None for SVGs without a size or viewBox
+    pub height: Option<u32>,
+    pub color_type: Option<String>, // e.g. "rgba8"; None for SVG
+    pub has_alpha: bool,
+    pub file_size: u64,
+    pub thumbnail: Option<String>,  // Base64 PNG; None for SVG (the viewer renders it directly)
+    pub thumbnail_width: Option<u32>,
+    pub thumbnail_height: Option<u32>,
+    pub thumbnail_path: Option<String>, // Relative to the project root
+}
+
+fn format_name(format: ImageFormat) -> Option<&'static str> {
+    Some(match format {
+        ImageFormat::Png => "png",
+        ImageFormat::Jpeg => "jpeg",
+        ImageFormat::Gif => "gif",
+        ImageFormat::WebP => "webp",
+        ImageFormat::Ico => "ico",
+        _ => return None,
+    })
+}
+
+fn color_type_name(color: image::ColorType) -> String {
+    match color {
+        image::ColorType::L8 => "l8",
+        image::ColorType::La8 => "la8",
+        image::ColorType::Rgb8 => "rgb8",
+        image::ColorType::Rgba8 => "rgba8",
+        image::ColorType::L16 => "l16",
+        image::ColorType::La16 => "la16",
+        image::ColorType::Rgb16 => "rgb16",
+        image::ColorType::Rgba16 => "rgba16",
+        image::ColorType::Rgb32F => "rgb32f",
+        image::ColorType::Rgba32F => "rgba32f",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn svg_root_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"(?s)<svg\b[^>]*>").unwrap())
+}
+
+fn svg_size_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"\s(width|height)\s*=\s*["']\s*([0-9.]+)\s*(px)?\s*["']"#).unwrap())
+}
+
+fn svg_view_box_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"\sviewBox\s*=\s*["']([^"']*)["']"#).unwrap())
+}
+
+/// Read the size of an SVG from its width/height attributes, falling back to the viewBox
+pub fn svg_dimensions(svg: &str) -> Option<(u32, u32)> {
+    let root = svg_root_regex().find(svg)?.as_str();
+    let attribute = |name: &str| -> Option<f64> {
+        svg_size_regex().captures_iter(root)
+            .find(|captures| &captures[1] == name)?
+            .get(2)?.as_str().parse().ok()
+    };
+
+    if let (Some(width), Some(height)) = (attribute("width"), attribute("height")) {
+        return Some((width.round() as u32, height.round() as u32));
+    }
+
+    let view_box = svg_view_box_regex().captures(root)?.get(1)?.as_str();
+    let values: Vec<f64> = view_box
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    match values.as_slice() {
+        [_, _, width, height] => Some((width.round() as u32, height.round() as u32)),
+        _ => None,
+    }
+}
+
+fn thumbnail_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".naide").join(THUMBNAIL_DIR)
+}
+
+/// Cache file for one version of an image: "<path hash>-<version hash>.png".
+/// The version covers size, modification time and thumbnail size, so edits invalidate it.
+fn thumbnail_file_name(relative_path: &str, file_size: u64, modified_nanos: u128, max_size: u32) -> (String, String) {
+    let path_key = content_hash(relative_path.as_bytes())[..16].to_string();
+    let version = format!("{}:{}:{}", file_size, modified_nanos, max_size);
+    let version_key = &content_hash(version.as_bytes())[..16];
+    (path_key.clone(), format!("{}-{}.png", path_key, version_key))
+}
+
+/// Remove cached thumbnails of older versions of the same image
+fn remove_stale_thumbnails(dir: &Path, path_key: &str, keep: &str) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(&format!("{}-", path_key)) && name != keep {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(bytes)
+}
+
+/// Extract metadata of an image and create (or reuse) its cached thumbnail
+pub fn preview_image(project_root: &Path, full_path: &Path, relative_path: &str, max_size: u32) -> Result<ImagePreview, String> {
+    let metadata = fs::metadata(full_path)
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    let file_size = metadata.len();
+    if file_size > MAX_IMAGE_BYTES {
+        return Err(format!("Image is too large to preview ({} bytes, limit {})", file_size, MAX_IMAGE_BYTES));
+    }
+
+    let is_svg = full_path.extension()
+        .map(|ext| ext.to_string_lossy().eq_ignore_ascii_case("svg"))
+        .unwrap_or(false);
+    if is_svg {
+        let svg = fs::read_to_string(full_path)
+            .map_err(|e| format!("Failed to read image: {}", e))?;
+        let dimensions = svg_dimensions(&svg);
+        return Ok(ImagePreview {
+            path: relative_path.to_string(),
+            format: "svg".to_string(),
+            width: dimensions.map(|d| d.0),
+            height: dimensions.map(|d| d.1),
+            color_type: None,
+            has_alpha: true,
+            file_size,
+            thumbnail: None,
+            thumbnail_width: None,
+            thumbnail_height: None,
+            thumbnail_path: None,
+        });
+    }
+
+    let reader = ImageReader::open(full_path)
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    let format = reader.format()
+        .and_then(format_name)
+        .ok_or_else(|| "Unsupported image format".to_string())?;
+    let decoder = reader.into_decoder()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let (width, height) = decoder.dimensions();
+    let color = decoder.color_type();
+
+    let modified_nanos = metadata.modified().ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let (path_key, file_name) = thumbnail_file_name(relative_path, file_size, modified_nanos, max_size);
+    let dir = thumbnail_dir(project_root);
+    let cached = dir.join(&file_name);
+
+    let thumbnail_bytes = match fs::read(&cached) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let image = DynamicImage::from_decoder(decoder)
+                .map_err(|e| format!("Failed to decode image: {}", e))?;
+            // Never upscale small images
+            let thumbnail = if width > max_size || height > max_size {
+                image.thumbnail(max_size, max_size)
+            } else {
+                image
+            };
+            let bytes = encode_png(&thumbnail)?;
+
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create thumbnail cache: {}", e))?;
+            fs::write(&cached, &bytes)
+                .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+            remove_stale_thumbnails(&dir, &path_key, &file_name);
+            log::info!("Created thumbnail for {} ({}x{})", relative_path, thumbnail.width(), thumbnail.height());
+            bytes
+        }
+    };
+
+    let (thumbnail_width, thumbnail_height) = image::load_from_memory_with_format(&thumbnail_bytes, ImageFormat::Png)
+        .map(|t| (t.width(), t.height()))
+        .map_err(|e| format!("Failed to read thumbnail: {}", e))?;
+
+    Ok(ImagePreview {
+        path: relative_path.to_string(),
+        format: format.to_string(),
+        width: Some(width),
+        height: Some(height),
+        color_type: Some(color_type_name(color)),
+        has_alpha: color.has_alpha(),
+        file_size,
+        thumbnail: Some(base64::engine::general_purpose::STANDARD.encode(&thumbnail_bytes)),
+        thumbnail_width: Some(thumbnail_width),
+        thumbnail_height: Some(thumbnail_height),
+        thumbnail_path: Some(format!(".naide/{}/{}", THUMBNAIL_DIR, file_name)),
+    })
+}
+
+// Tauri command: Get dimensions, format and color type of an image, with a cached thumbnail
+// Thumbnails are stored in .naide/cache/thumbnails and reused until the image changes
+#[tauri::command]
+pub async fn get_image_preview(
+    project_path: String,
+    file_path: String,
+    max_size: Option<u32>,
+) -> Result<ImagePreview, String> {
+    let project_root = PathBuf::from(&project_path);
+    let full_path = file_io::resolve_existing(&project_root, &file_path, "project")?;
+    if !full_path.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+    let max_size = max_size.unwrap_or(DEFAULT_THUMBNAIL_SIZE).clamp(MIN_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE);
+    preview_image(&project_root, &full_path, &file_path, max_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn test_svg_dimensions() {
+        assert_eq!(svg_dimensions(r#"<svg xmlns="http://www.w3.org/2000/svg" width="120px" height="40"></svg>"#), Some((120, 40)));
+        assert_eq!(svg_dimensions(r#"<?xml version="1.0"?><svg viewBox="0 0 24 24.4"><path/></svg>"#), Some((24, 24)));
+        assert_eq!(svg_dimensions(r#"<svg width="100%"></svg>"#), None);
+    }
+
+    #[test]
+    fn test_png_preview_and_thumbnail_cache() {
+        let root = TempDir::new("image-preview");
+        let path = root.join("logo.png");
+        RgbaImage::from_pixel(400, 200, Rgba([255, 0, 0, 128])).save(&path).unwrap();
+
+        let preview = preview_image(&root, &path, "logo.png", 100).unwrap();
+        assert_eq!(preview.format, "png");
+        assert_eq!((preview.width, preview.height), (Some(400), Some(200)));
+        assert_eq!(preview.color_type.as_deref(), Some("rgba8"));
+        assert!(preview.has_alpha);
+        assert_eq!((preview.thumbnail_width, preview.thumbnail_height), (Some(100), Some(50)));
+        let cached = root.join(preview.thumbnail_path.clone().unwrap());
+        assert!(cached.is_file());
+
+        // A changed image gets a new thumbnail and the old one is removed
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        RgbaImage::from_pixel(50, 50, Rgba([0, 0, 255, 255])).save(&path).unwrap();
+        let preview = preview_image(&root, &path, "logo.png", 100).unwrap();
+        assert_eq!((preview.thumbnail_width, preview.thumbnail_height), (Some(50), Some(50)));
+        assert!(!cached.exists());
+    }
+}
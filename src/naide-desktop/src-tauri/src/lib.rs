@@ -1,4 +1,4 @@
-use std::process::{Command, Child, Stdio};
+use std::process::Child;
 use std::sync::Mutex;
 use std::env;
 use std::fs;
@@ -6,8 +6,6 @@ use std::path::PathBuf;
 use tauri::{Manager, Emitter};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use notify::{Watcher, RecursiveMode, recommended_watcher, Event, EventKind};
-use std::sync::mpsc::channel;
 
 mod settings;
 use settings::{LastProject, read_settings, write_settings, add_recent_project, remove_recent_project, get_recent_projects as get_recent_projects_from_settings};
@@ -18,16 +16,86 @@ use app_runner::{detect_dotnet_app, detect_npm_app, detect_all_runnable_apps, st
 mod project_files;
 use project_files::list_project_files;
 
-// Global state to track the sidecar process
-struct SidecarState {
-    process: Option<Child>,
-}
+mod project_tree;
+use project_tree::get_project_tree;
 
-// Global state to track file watchers
-struct WatcherState {
-    _feature_watcher: Option<Box<dyn Watcher + Send>>,
-    _project_watcher: Option<Box<dyn Watcher + Send>>,
-}
+mod file_ops;
+use file_ops::{
+    create_project_file, create_project_folder, rename_project_path, move_project_path,
+    copy_project_path, delete_project_path, list_file_operations, undo_file_operation,
+};
+
+mod file_history;
+use file_history::{list_file_revisions, diff_file_revisions, restore_file_revision};
+
+mod file_reader;
+use file_reader::read_project_file_range;
+
+mod image_preview;
+use image_preview::get_image_preview;
+
+mod sidecar_client;
+
+mod sidecar;
+use sidecar::{SidecarLaunch, SidecarState, get_sidecar_status, restart_sidecar, get_sidecar_logs, get_sidecar_connection};
+
+mod chat_sessions;
+use chat_sessions::{
+    load_chat_session_file, list_quarantined_chat_sessions,
+    save_chat_session, archive_chat_session, rename_chat_session, fork_chat_session,
+};
+
+mod chat_index;
+use chat_index::{list_chat_sessions, list_chat_sessions_page};
+
+mod chat_search;
+use chat_search::search_chat_sessions;
+
+mod chat_export;
+use chat_export::export_chat_session;
+
+mod chat_import;
+use chat_import::import_chat_sessions;
+
+mod chat_digest;
+use chat_digest::summarize_chat_session;
+
+mod chat_trash;
+use chat_trash::{delete_chat_session, list_trashed_chat_sessions, restore_chat_session, purge_chat_sessions};
+
+mod file_io;
+use file_io::{FileWriteError, VersionedContent};
+
+mod merge;
+use merge::{merge_feature_file, merge_project_file};
+
+mod markdown_outline;
+use markdown_outline::{get_feature_outline, toggle_feature_task, replace_feature_section};
+
+mod feature_progress;
+use feature_progress::{get_feature_progress_report, TaskCounts};
+
+mod spec_lint;
+use spec_lint::lint_feature_specs;
+
+mod prompt_docs;
+use prompt_docs::{
+    list_plan_files, read_plan_file, write_plan_file,
+    list_system_prompt_files, read_system_prompt_file, write_system_prompt_file,
+    validate_system_prompts, watch_prompt_files,
+};
+
+mod ignore_rules;
+use ignore_rules::ProjectIgnore;
+
+mod watchers;
+use watchers::{
+    ChangeKind, FileChange, FileChangesPayload, WatchHandler, WatcherRegistry,
+    subscribe_watch, unsubscribe_watch, list_watches, set_watch_debounce, stop_all_watches,
+};
+
+#[cfg(test)]
+mod test_support;
 
 // Global state to track running app process
 struct RunningAppState {
@@ -64,6 +132,9 @@ async fn save_last_project(app: tauri::AppHandle, path: String) -> Result<(), St
         return Err(format!("Path is not a directory: {}", path));
     }
     
+    // Opening a different project stops the previous project's watchers
+    app.state::<Mutex<WatcherRegistry>>().lock().unwrap().switch_project(&path_buf);
+    
     // Get settings file path for logging
     let settings_path = settings::get_settings_path(&app)?;
     println!("[Settings] Settings file path: {:?}", settings_path);
@@ -114,6 +185,9 @@ async fn clear_last_project(app: tauri::AppHandle) -> Result<(), String> {
     settings.last_used_project = None;
     write_settings(&app, &settings)?;
     
+    // No project is open anymore, so nothing needs watching
+    app.state::<Mutex<WatcherRegistry>>().lock().unwrap().clear();
+    
     println!("[Settings] Cleared last project");
     Ok(())
 }
@@ -248,6 +322,10 @@ struct FeatureFileNode {
     date: Option<String>,  // Parsed date (YYYY-MM-DD)
     is_folder: bool,
     children: Option<Vec<FeatureFileNode>>,
+    #[serde(default)]
+    tasks_total: usize,    // Task-list items in the file (summed over children for folders)
+    #[serde(default)]
+    tasks_done: usize,     // Checked task-list items
 }
 
 // Tauri command: List feature files from .prompts/features/
@@ -315,6 +393,12 @@ fn scan_directory(base_dir: &PathBuf, current_dir: &PathBuf, options: &ViewOptio
             // Recursively scan subdirectories
             let children = scan_directory(base_dir, &path, options)?;
             
+            // Folder progress is the sum of its children
+            let mut counts = TaskCounts::default();
+            for child in &children {
+                counts.add(TaskCounts { total: child.tasks_total, done: child.tasks_done });
+            }
+            
             nodes.push(FeatureFileNode {
                 name: file_name.clone(),
                 full_name: file_name.clone(),
@@ -322,6 +406,8 @@ fn scan_directory(base_dir: &PathBuf, current_dir: &PathBuf, options: &ViewOptio
                 date: None,
                 is_folder: true,
                 children: Some(children),
+                tasks_total: counts.total,
+                tasks_done: counts.done,
             });
         } else if file_name.ends_with(".md") {
             // Parse date prefix and display name based on show_raw option
@@ -331,6 +417,8 @@ fn scan_directory(base_dir: &PathBuf, current_dir: &PathBuf, options: &ViewOptio
                 parse_filename(&file_name)
             };
             
+            let counts = feature_progress::count_tasks_in_file(&path);
+            
             nodes.push(FeatureFileNode {
                 name: display_name,
                 full_name: file_name.clone(),
@@ -338,12 +426,20 @@ fn scan_directory(base_dir: &PathBuf, current_dir: &PathBuf, options: &ViewOptio
                 date,
                 is_folder: false,
                 children: None,
+                tasks_total: counts.total,
+                tasks_done: counts.done,
             });
         }
     }
     
-    // Sort nodes: files by date (most recent first), then alphabetically
-    // Folders alphabetically
+    sort_feature_nodes(&mut nodes);
+    
+    Ok(nodes)
+}
+
+// Sort nodes: files by date (most recent first), then alphabetically
+// Folders alphabetically
+fn sort_feature_nodes(nodes: &mut [FeatureFileNode]) {
     nodes.sort_by(|a, b| {
         match (a.is_folder, b.is_folder) {
             (true, true) => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
@@ -363,8 +459,6 @@ fn scan_directory(base_dir: &PathBuf, current_dir: &PathBuf, options: &ViewOptio
             }
         }
     });
-    
-    Ok(nodes)
 }
 
 // Parse filename to extract date prefix and display name
@@ -373,17 +467,10 @@ fn parse_filename(filename: &str) -> (String, Option<String>) {
     // Remove .md extension
     let name_without_ext = filename.strip_suffix(".md").unwrap_or(filename);
     
-    // Check if it starts with a date pattern (YYYY-MM-DD-)
-    if name_without_ext.len() >= 11 {
-        let potential_date = &name_without_ext[0..10];
-        if potential_date.chars().nth(4) == Some('-') 
-            && potential_date.chars().nth(7) == Some('-')
-            && name_without_ext.chars().nth(10) == Some('-') {
-            // Looks like a date prefix
-            let date = potential_date.to_string();
-            let display_name = name_without_ext[11..].to_string();
-            return (display_name, Some(date));
-        }
+    // Check if it starts with a date prefix (YYYY-MM-DD-)
+    if let Some(date) = parse_date_prefix(name_without_ext) {
+        let display_name = name_without_ext[11..].to_string();
+        return (display_name, Some(date));
     }
     
     (name_without_ext.to_string(), None)
@@ -392,17 +479,24 @@ fn parse_filename(filename: &str) -> (String, Option<String>) {
 // Parse just the date from filename without modifying the display name
 fn parse_date_from_filename(filename: &str) -> Option<String> {
     let name_without_ext = filename.strip_suffix(".md").unwrap_or(filename);
-    
-    if name_without_ext.len() >= 11 {
-        let potential_date = &name_without_ext[0..10];
-        if potential_date.chars().nth(4) == Some('-') 
-            && potential_date.chars().nth(7) == Some('-')
-            && name_without_ext.chars().nth(10) == Some('-') {
-            return Some(potential_date.to_string());
-        }
+    parse_date_prefix(name_without_ext)
+}
+
+// Return the leading YYYY-MM-DD if it is a real calendar date followed by '-'
+fn parse_date_prefix(name: &str) -> Option<String> {
+    let potential_date = name.get(0..10)?;
+    if name.as_bytes().get(10) != Some(&b'-') {
+        return None;
     }
-    
-    None
+    // NaiveDate accepts unpadded fields, so require the exact digit layout first
+    let well_formed = potential_date.char_indices().all(|(i, c)| match i {
+        4 | 7 => c == '-',
+        _ => c.is_ascii_digit(),
+    });
+    if !well_formed || chrono::NaiveDate::parse_from_str(potential_date, "%Y-%m-%d").is_err() {
+        return None;
+    }
+    Some(potential_date.to_string())
 }
 
 // Tauri command: Read feature file content
@@ -428,9 +522,45 @@ async fn read_feature_file(project_path: String, file_path: String) -> Result<St
         .map_err(|e| format!("Failed to read file: {}", e))
 }
 
+// Tauri command: Read feature file content with a token for conflict-aware writes
+#[tauri::command]
+async fn read_feature_file_versioned(project_path: String, file_path: String) -> Result<VersionedContent, String> {
+    let full_path = PathBuf::from(&project_path)
+        .join(".prompts")
+        .join("features")
+        .join(&file_path);
+    
+    // Security check: ensure the path is within .prompts/features/
+    let base_dir = PathBuf::from(&project_path).join(".prompts").join("features");
+    let canonical_full_path = full_path.canonicalize()
+        .map_err(|e| format!("Invalid file path: {}", e))?;
+    let canonical_base_dir = base_dir.canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+    
+    if !canonical_full_path.starts_with(&canonical_base_dir) {
+        return Err("Access denied: path outside of features directory".to_string());
+    }
+    
+    let versioned = file_io::read_versioned(&canonical_full_path)?;
+    
+    // Remember what the editor read so it can be used as a merge base on conflict
+    if let Ok(project_root) = PathBuf::from(&project_path).canonicalize() {
+        file_history::record_read(&project_root, &canonical_full_path, versioned.content.as_bytes(), "read_feature_file_versioned");
+    }
+    
+    Ok(versioned)
+}
+
 // Tauri command: Write feature file content
+// If expected_token is given, the write fails with a conflict when the file changed since it was read.
+// Returns the token of the newly written content.
 #[tauri::command]
-async fn write_feature_file(project_path: String, file_path: String, content: String) -> Result<(), String> {
+async fn write_feature_file(
+    project_path: String,
+    file_path: String,
+    content: String,
+    expected_token: Option<String>,
+) -> Result<String, FileWriteError> {
     let full_path = PathBuf::from(&project_path)
         .join(".prompts")
         .join("features")
@@ -446,7 +576,7 @@ async fn write_feature_file(project_path: String, file_path: String, content: St
     
     // Ensure parent directory exists
     if !parent.exists() {
-        return Err("Parent directory does not exist".to_string());
+        return Err("Parent directory does not exist".to_string().into());
     }
     
     let canonical_parent = parent.canonicalize()
@@ -455,12 +585,18 @@ async fn write_feature_file(project_path: String, file_path: String, content: St
         .map_err(|e| format!("Invalid base directory: {}", e))?;
     
     if !canonical_parent.starts_with(&canonical_base_dir) {
-        return Err("Access denied: path outside of features directory".to_string());
+        return Err("Access denied: path outside of features directory".to_string().into());
     }
     
-    // Write the file
-    fs::write(&full_path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+    let file_name = full_path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let target_path = canonical_parent.join(file_name);
+    
+    let project_root = PathBuf::from(&project_path).canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+    
+    // Conflict check, history snapshot, then temp file + rename so a crash never leaves a half-written spec
+    file_io::write_checked(&project_root, &target_path, &file_path, &content, expected_token.as_deref(), "write_feature_file")
 }
 
 // Tauri command: Read project file content
@@ -484,9 +620,41 @@ async fn read_project_file(project_path: String, file_path: String) -> Result<St
         .map_err(|e| format!("Failed to read file: {}", e))
 }
 
+// Tauri command: Read project file content with a token for conflict-aware writes
+#[tauri::command]
+async fn read_project_file_versioned(project_path: String, file_path: String) -> Result<VersionedContent, String> {
+    let full_path = PathBuf::from(&project_path).join(&file_path);
+    
+    // Security check: ensure the path is within the project directory
+    let base_dir = PathBuf::from(&project_path);
+    let canonical_full_path = full_path.canonicalize()
+        .map_err(|e| format!("Invalid file path: {}", e))?;
+    let canonical_base_dir = base_dir.canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+    
+    if !canonical_full_path.starts_with(&canonical_base_dir) {
+        return Err("Access denied: path outside of project directory".to_string());
+    }
+    
+    let versioned = file_io::read_versioned(&canonical_full_path)?;
+    
+    // Remember what the editor read so it can be used as a merge base on conflict
+    file_history::record_read(&canonical_base_dir, &canonical_full_path, versioned.content.as_bytes(), "read_project_file_versioned");
+    
+    Ok(versioned)
+}
+
 // Tauri command: Write project file content
+// If expected_token is given, the write fails with a conflict when the file changed since it was read.
+// Returns the token of the newly written content. With create_parents, missing parent folders are created.
 #[tauri::command]
-async fn write_project_file(project_path: String, file_path: String, content: String) -> Result<(), String> {
+async fn write_project_file(
+    project_path: String,
+    file_path: String,
+    content: String,
+    expected_token: Option<String>,
+    create_parents: Option<bool>,
+) -> Result<String, FileWriteError> {
     let full_path = PathBuf::from(&project_path).join(&file_path);
     
     // Security check: ensure the path is within the project directory
@@ -497,9 +665,18 @@ async fn write_project_file(project_path: String, file_path: String, content: St
     let parent = full_path.parent()
         .ok_or_else(|| "Invalid file path: no parent directory".to_string())?;
     
-    // Ensure parent directory exists
+    // Ensure parent directory exists, creating it inside the project if requested
     if !parent.exists() {
-        return Err("Parent directory does not exist".to_string());
+        if !create_parents.unwrap_or(false) {
+            return Err("Parent directory does not exist".to_string().into());
+        }
+        let canonical_base_dir = base_dir.canonicalize()
+            .map_err(|e| format!("Invalid base directory: {}", e))?;
+        let target = file_ops::resolve_new_path(&canonical_base_dir, &file_path)?;
+        if let Some(target_parent) = target.parent() {
+            fs::create_dir_all(target_parent)
+                .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+        }
     }
     
     let canonical_parent = parent.canonicalize()
@@ -508,12 +685,15 @@ async fn write_project_file(project_path: String, file_path: String, content: St
         .map_err(|e| format!("Invalid base directory: {}", e))?;
     
     if !canonical_parent.starts_with(&canonical_base_dir) {
-        return Err("Access denied: path outside of project directory".to_string());
+        return Err("Access denied: path outside of project directory".to_string().into());
     }
     
-    // Write the file
-    fs::write(&full_path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))
+    let file_name = full_path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let target_path = canonical_parent.join(file_name);
+    
+    // Conflict check, history snapshot, then temp file + rename so a crash never leaves a half-written file
+    file_io::write_checked(&canonical_base_dir, &target_path, &file_path, &content, expected_token.as_deref(), "write_project_file")
 }
 
 // Tauri command: Get file size
@@ -539,172 +719,9 @@ async fn get_file_size(project_path: String, relative_path: String) -> Result<u6
     Ok(metadata.len())
 }
 
-// Chat session metadata structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ChatSessionMetadata {
-    filename: String,
-    last_modified: u64,
-    message_count: usize,
-    mode: String,
-    first_user_message: String,
-}
-
-// Tauri command: List chat sessions (excluding active session)
-#[tauri::command]
-async fn list_chat_sessions(project_path: String) -> Result<Vec<ChatSessionMetadata>, String> {
-    let chat_sessions_dir = PathBuf::from(&project_path)
-        .join(".naide")
-        .join("chatsessions");
-    
-    if !chat_sessions_dir.exists() {
-        return Ok(Vec::new());
-    }
-    
-    if !chat_sessions_dir.is_dir() {
-        return Err("Chat sessions path is not a directory".to_string());
-    }
-    
-    let mut sessions = Vec::new();
-    
-    let entries = fs::read_dir(&chat_sessions_dir)
-        .map_err(|e| format!("Failed to read chat sessions directory: {}", e))?;
-    
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
-        let file_name = entry.file_name().to_string_lossy().to_string();
-        
-        // Skip the active chat file and non-JSON files
-        if file_name == "default-chat.json" || !file_name.ends_with(".json") {
-            continue;
-        }
-        
-        // Read file metadata
-        let metadata = fs::metadata(&path)
-            .map_err(|e| format!("Failed to read file metadata: {}", e))?;
-        
-        let last_modified = metadata.modified()
-            .ok()
-            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|duration| duration.as_secs())
-            .unwrap_or(0);
-        
-        // Read and parse the chat session file
-        match fs::read_to_string(&path) {
-            Ok(content) => {
-                match serde_json::from_str::<serde_json::Value>(&content) {
-                    Ok(session) => {
-                        let messages = session.get("messages")
-                            .and_then(|m| m.as_array())
-                            .cloned()
-                            .unwrap_or_default();
-                        
-                        let mode = session.get("mode")
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("Planning")
-                            .to_string();
-                        
-                        // Find first user message
-                        let first_user_message = messages.iter()
-                            .find(|msg| {
-                                msg.get("role")
-                                    .and_then(|r| r.as_str())
-                                    .map(|r| r == "user")
-                                    .unwrap_or(false)
-                            })
-                            .and_then(|msg| msg.get("content"))
-                            .and_then(|c| c.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        
-                        sessions.push(ChatSessionMetadata {
-                            filename: file_name,
-                            last_modified,
-                            message_count: messages.len(),
-                            mode,
-                            first_user_message,
-                        });
-                    }
-                    Err(_) => {
-                        // Skip corrupted files
-                        log::warn!("Skipping corrupted chat session file: {}", file_name);
-                    }
-                }
-            }
-            Err(_) => {
-                // Skip files that can't be read
-                log::warn!("Skipping unreadable chat session file: {}", file_name);
-            }
-        }
-    }
-    
-    // Sort by last_modified descending (most recent first)
-    sessions.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
-    
-    Ok(sessions)
-}
-
-// Tauri command: Load a specific chat session
-#[tauri::command]
-async fn load_chat_session_file(project_path: String, filename: String) -> Result<String, String> {
-    // Security check: validate filename
-    if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
-        return Err("Invalid filename".to_string());
-    }
-    
-    let session_path = PathBuf::from(&project_path)
-        .join(".naide")
-        .join("chatsessions")
-        .join(&filename);
-    
-    if !session_path.exists() {
-        return Err(format!("Chat session file not found: {}", filename));
-    }
-    
-    fs::read_to_string(&session_path)
-        .map_err(|e| format!("Failed to read chat session file: {}", e))
-}
-
-// Tauri command: Delete a chat session (move to trash)
-#[tauri::command]
-async fn delete_chat_session(project_path: String, filename: String) -> Result<(), String> {
-    // Security check: validate filename (prevent path traversal)
-    if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
-        return Err("Invalid filename".to_string());
-    }
-    
-    // Construct paths
-    let chatsessions_dir = PathBuf::from(&project_path)
-        .join(".naide")
-        .join("chatsessions");
-    
-    let source_path = chatsessions_dir.join(&filename);
-    let trash_dir = chatsessions_dir.join("trash");
-    let dest_path = trash_dir.join(&filename);
-    
-    // Check source file exists
-    if !source_path.exists() {
-        return Err("Chat file not found".to_string());
-    }
-    
-    // Create trash directory if it doesn't exist
-    if !trash_dir.exists() {
-        fs::create_dir_all(&trash_dir)
-            .map_err(|e| format!("Failed to create trash directory: {}", e))?;
-    }
-    
-    // Move the file to trash
-    fs::rename(&source_path, &dest_path)
-        .map_err(|e| format!("Failed to move file to trash: {}", e))?;
-    
-    log::info!("Moved chat session to trash: {}", filename);
-    
-    Ok(())
-}
-
 // Tauri command: Watch feature files directory for changes
 #[tauri::command]
-async fn watch_feature_files(window: tauri::Window, project_path: String) -> Result<(), String> {
+async fn watch_feature_files(window: tauri::Window, project_path: String, debounce_ms: Option<u64>) -> Result<(), String> {
     let features_path = PathBuf::from(&project_path)
         .join(".prompts")
         .join("features");
@@ -716,58 +733,48 @@ async fn watch_feature_files(window: tauri::Window, project_path: String) -> Res
     
     log::info!("Starting file watcher for: {:?}", features_path);
     
-    let (tx, rx) = channel();
-    
-    // Create the watcher
-    let mut watcher = recommended_watcher(move |res: Result<Event, notify::Error>| {
-        match res {
-            Ok(event) => {
-                // Filter for events we care about
-                match event.kind {
-                    EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_) => {
-                        log::debug!("File change detected: {:?}", event);
-                        // Send through channel
-                        let _ = tx.send(());
-                    }
-                    _ => {}
-                }
-            }
-            Err(e) => {
-                log::error!("Watch error: {:?}", e);
-            }
-        }
-    }).map_err(|e| format!("Failed to create watcher: {}", e))?;
-    
-    // Start watching
-    watcher.watch(&features_path, RecursiveMode::Recursive)
-        .map_err(|e| format!("Failed to watch directory: {}", e))?;
-    
-    log::info!("File watcher started successfully");
-    
-    // Clone window for the thread, keep original for state access
+    // Clone window for the handler thread, keep original for state access
     let window_clone = window.clone();
     
-    // Spawn a thread to listen for events and emit to frontend
-    std::thread::spawn(move || {
-        while let Ok(_) = rx.recv() {
-            // Emit event to frontend
-            log::debug!("Emitting feature-files-changed event");
-            if let Err(e) = window_clone.emit("feature-files-changed", ()) {
-                log::error!("Failed to emit event: {}", e);
+    let lint_root = PathBuf::from(&project_path);
+    
+    // Changes arrive debounced, so a burst becomes one reload and one lint pass
+    let handler: WatchHandler = Box::new(move |changes| {
+        // Paths in the payload are relative to .prompts/features/, like FeatureFileNode.path
+        let changes: Vec<FileChange> = changes.iter()
+            .filter_map(|c| c.relative_to(".prompts/features"))
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        
+        // Emit event to frontend
+        log::debug!("Emitting feature-files-changed event with {} change(s)", changes.len());
+        if let Err(e) = window_clone.emit("feature-files-changed", FileChangesPayload { changes }) {
+            log::error!("Failed to emit event: {}", e);
+        }
+        
+        match spec_lint::lint_project(&lint_root) {
+            Ok(diagnostics) => {
+                if let Err(e) = window_clone.emit("feature-lint-diagnostics", diagnostics) {
+                    log::error!("Failed to emit lint diagnostics: {}", e);
+                }
             }
+            Err(e) => log::warn!("Failed to lint feature specs: {}", e),
         }
     });
     
-    // Store watcher to keep it alive
-    // Note: The watcher will be dropped when the app closes, which is fine
-    window.state::<Mutex<WatcherState>>().lock().unwrap()._feature_watcher = Some(Box::new(watcher));
+    // Register the watcher; it is replaced on the next call and stopped when the project changes
+    watchers::subscribe(window.state::<Mutex<WatcherRegistry>>().inner(), "features", &project_path, &[".prompts/features"], debounce_ms, handler)?;
+    
+    log::info!("File watcher started successfully");
     
     Ok(())
 }
 
 // Tauri command: Watch project files directory for changes
 #[tauri::command]
-async fn watch_project_files(window: tauri::Window, project_path: String) -> Result<(), String> {
+async fn watch_project_files(window: tauri::Window, project_path: String, debounce_ms: Option<u64>) -> Result<(), String> {
     let project_path_buf = PathBuf::from(&project_path);
     
     if !project_path_buf.exists() {
@@ -777,89 +784,57 @@ async fn watch_project_files(window: tauri::Window, project_path: String) -> Res
     
     log::info!("Starting project file watcher for: {:?}", project_path_buf);
     
-    let (tx, rx) = channel();
-    
-    // List of directories to exclude from watching
-    let excluded_dirs = vec![
-        "node_modules",
-        ".git",
-        "bin",
-        "obj",
-        "dist",
-        "build",
-        "out",
-        ".naide",
-        "target", // Rust
-        "__pycache__", // Python
-        ".venv", // Python
-        "venv", // Python
-    ];
-    
-    // Create the watcher
-    let project_path_clone = project_path_buf.clone();
-    let mut watcher = recommended_watcher(move |res: Result<Event, notify::Error>| {
-        match res {
-            Ok(event) => {
-                // Filter for events we care about (Create, Remove, Rename only - ignore Modify)
-                match event.kind {
-                    EventKind::Create(_) | EventKind::Remove(_) => {
-                        // Check if any of the paths should be excluded
-                        let should_exclude = event.paths.iter().any(|path| {
-                            // Get relative path from project root
-                            if let Ok(rel_path) = path.strip_prefix(&project_path_clone) {
-                                // Check if any path component matches an excluded directory
-                                rel_path.components().any(|component| {
-                                    if let std::path::Component::Normal(os_str) = component {
-                                        if let Some(name) = os_str.to_str() {
-                                            return excluded_dirs.contains(&name);
-                                        }
-                                    }
-                                    false
-                                })
-                            } else {
-                                false
-                            }
-                        });
-                        
-                        if !should_exclude {
-                            log::debug!("Project file change detected: {:?}", event);
-                            // Send through channel
-                            let _ = tx.send(());
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            Err(e) => {
-                log::error!("Watch error: {:?}", e);
-            }
-        }
-    }).map_err(|e| format!("Failed to create watcher: {}", e))?;
-    
-    // Start watching
-    watcher.watch(&project_path_buf, RecursiveMode::Recursive)
-        .map_err(|e| format!("Failed to watch directory: {}", e))?;
-    
-    log::info!("Project file watcher started successfully");
-    
-    // Clone window for the thread, keep original for state access
     let window_clone = window.clone();
+    let mut rules = ProjectIgnore::load(&project_path_buf);
     
-    // Spawn a thread to listen for events and emit to frontend
-    std::thread::spawn(move || {
-        while let Ok(_) = rx.recv() {
+    let handler: WatchHandler = Box::new(move |changes| {
+        // Edited ignore rules apply from this batch on
+        if changes.iter().any(|c| ignore_rules::is_ignore_file(&c.path)) {
+            log::info!("Ignore rules changed, reloading");
+            rules = ProjectIgnore::load(rules.root());
+        }
+        
+        // Skip ignored paths (.gitignore, .git/info/exclude, .naideignore and defaults)
+        // A removed path can no longer tell whether it was a directory, so it is matched both ways
+        let is_ignored = |path: &str| {
+            let full_path = rules.root().join(path);
+            if full_path.exists() {
+                rules.is_ignored_relative(path, full_path.is_dir())
+            } else {
+                rules.is_ignored_relative(path, true) || rules.is_ignored_relative(path, false)
+            }
+        };
+        
+        // Only structural changes matter for the tree - ignore content modifications.
+        // Renames and moves are kept as one change so the tree can carry over its expansion state.
+        let changes: Vec<FileChange> = changes.into_iter()
+            .filter(|c| c.kind != ChangeKind::Modified)
+            .filter_map(|c| match (c.kind, c.from.as_deref()) {
+                (ChangeKind::Renamed, Some(from)) => match (is_ignored(from), is_ignored(&c.path)) {
+                    (false, false) => Some(c),
+                    // Moved out of an ignored directory: it appears in the tree
+                    (true, false) => Some(FileChange { kind: ChangeKind::Created, path: c.path, from: None }),
+                    // Moved into an ignored directory: it disappears from the tree
+                    (false, true) => Some(FileChange { kind: ChangeKind::Removed, path: from.to_string(), from: None }),
+                    (true, true) => None,
+                },
+                _ => (!is_ignored(&c.path)).then_some(c),
+            })
+            .collect();
+        
+        if !changes.is_empty() {
             // Emit event to frontend
-            log::debug!("Emitting project-files-changed event");
-            if let Err(e) = window_clone.emit("project-files-changed", ()) {
+            log::debug!("Emitting project-files-changed event with {} change(s)", changes.len());
+            if let Err(e) = window_clone.emit("project-files-changed", FileChangesPayload { changes }) {
                 log::error!("Failed to emit event: {}", e);
             }
         }
     });
     
-    // Store watcher to keep it alive
-    // Note: We're storing in a separate field from feature files watcher
-    // Both can be active simultaneously
-    window.state::<Mutex<WatcherState>>().lock().unwrap()._project_watcher = Some(Box::new(watcher));
+    // Both the feature and project watchers can be active simultaneously
+    watchers::subscribe(window.state::<Mutex<WatcherRegistry>>().inner(), "project", &project_path, &[""], debounce_ms, handler)?;
+    
+    log::info!("Project file watcher started successfully");
     
     Ok(())
 }
@@ -1129,10 +1104,7 @@ pub fn run() {
       log::info!("Logging configured successfully");
       
       // Initialize watcher state
-      app.manage(Mutex::new(WatcherState {
-        _feature_watcher: None,
-        _project_watcher: None,
-      }));
+      app.manage(Mutex::new(WatcherRegistry::default()));
       
       // Initialize running app state
       app.manage(Mutex::new(RunningAppState {
@@ -1170,43 +1142,21 @@ pub fn run() {
           }
         });
       
-      if let Some(path) = sidecar_path {
-        log::info!("Starting copilot sidecar from: {:?}", path);
-        println!("[Tauri] Starting copilot sidecar from: {:?}", path);
-        
-        // Pass log file path to sidecar via environment variable
-        let log_file_path = log_dir.join(&log_filename);
-        
-        match Command::new("node")
-          .arg(path)
-          .env("NAIDE_LOG_FILE", log_file_path.to_string_lossy().to_string())
-          .stdout(Stdio::piped())
-          .stderr(Stdio::piped())
-          .spawn() {
-            Ok(child) => {
-              log::info!("Copilot sidecar started with PID: {:?}", child.id());
-              println!("[Tauri] Copilot sidecar started with PID: {:?}", child.id());
-              println!("[Tauri] Sidecar should be accessible at http://localhost:3001");
-              
-              // Store the process handle for cleanup
-              app.manage(Mutex::new(SidecarState {
-                process: Some(child),
-              }));
-            }
-            Err(e) => {
-              log::error!("Failed to start copilot sidecar: {}", e);
-              eprintln!("[Tauri] Failed to start copilot sidecar: {}", e);
-              eprintln!("[Tauri] Make sure Node.js is installed and in PATH");
-              eprintln!("[Tauri] App will continue, but copilot features will not work");
-            }
-          }
-      } else {
+      if sidecar_path.is_none() {
         log::warn!("Sidecar not found at expected path: {}", sidecar_relative_path);
         eprintln!("[Tauri] Sidecar not found at expected path: {}", sidecar_relative_path);
         eprintln!("[Tauri] Make sure to build the sidecar with: cd src/copilot-sidecar && npm run build");
         eprintln!("[Tauri] App will continue, but copilot features will not work");
       }
       
+      // The supervisor starts the sidecar, checks its health and restarts it when it dies
+      let launch = sidecar_path.map(|script| SidecarLaunch {
+        script,
+        // Pass log file path to sidecar via environment variable
+        log_file: log_dir.join(&log_filename),
+      });
+      sidecar::start_supervisor(app.handle(), launch);
+      
       Ok(())
     })
     .invoke_handler(tauri::generate_handler![
@@ -1224,36 +1174,83 @@ pub fn run() {
       log_to_file,
       list_feature_files,
       read_feature_file,
+      read_feature_file_versioned,
       write_feature_file,
       read_project_file,
+      read_project_file_versioned,
+      read_project_file_range,
+      get_image_preview,
       write_project_file,
       get_file_size,
+      list_file_revisions,
+      diff_file_revisions,
+      restore_file_revision,
+      merge_feature_file,
+      merge_project_file,
+      get_feature_outline,
+      toggle_feature_task,
+      replace_feature_section,
+      get_feature_progress_report,
+      lint_feature_specs,
+      list_plan_files,
+      read_plan_file,
+      write_plan_file,
+      list_system_prompt_files,
+      read_system_prompt_file,
+      write_system_prompt_file,
+      validate_system_prompts,
       list_chat_sessions,
+      list_chat_sessions_page,
+      search_chat_sessions,
+      export_chat_session,
+      import_chat_sessions,
+      summarize_chat_session,
+      get_sidecar_status,
+      restart_sidecar,
+      get_sidecar_logs,
+      get_sidecar_connection,
       load_chat_session_file,
       delete_chat_session,
+      list_trashed_chat_sessions,
+      restore_chat_session,
+      purge_chat_sessions,
+      list_quarantined_chat_sessions,
+      save_chat_session,
+      archive_chat_session,
+      rename_chat_session,
+      fork_chat_session,
       watch_feature_files,
       watch_project_files,
+      watch_prompt_files,
+      subscribe_watch,
+      unsubscribe_watch,
+      list_watches,
+      set_watch_debounce,
+      stop_all_watches,
       detect_runnable_app,
       detect_all_runnable_apps_command,
       start_app,
       stop_app,
-      list_project_files
+      list_project_files,
+      get_project_tree,
+      create_project_file,
+      create_project_folder,
+      rename_project_path,
+      move_project_path,
+      copy_project_path,
+      delete_project_path,
+      list_file_operations,
+      undo_file_operation
     ])
     .on_window_event(|_window, event| {
       // Clean up processes on app exit
       if let tauri::WindowEvent::CloseRequested { .. } = event {
-        // Stop sidecar
-        if let Some(mut state) = _window.state::<Mutex<SidecarState>>().try_lock().ok() {
-          if let Some(mut process) = state.process.take() {
-            let pid = process.id();
-            println!("[Tauri] Stopping sidecar process tree (PID {})...", pid);
-            // Kill the entire process tree to ensure all child processes are terminated
-            if let Err(e) = kill_process_tree(pid) {
-              eprintln!("[Tauri] Warning: Failed to kill sidecar process tree: {}", e);
-              // Fallback to simple kill
-              let _ = process.kill();
-            }
-          }
+        // Stop sidecar; wait for the supervisor rather than skip it, or the sidecar outlives the app
+        {
+          let sidecar = _window.state::<Mutex<SidecarState>>();
+          let mut state = sidecar.lock().unwrap_or_else(|e| e.into_inner());
+          println!("[Tauri] Stopping sidecar process tree...");
+          state.shutdown();
         }
         
         // Stop running app
@@ -0,0 +1,529 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::file_history::content_hash;
+use crate::file_io::{self, FileWriteError};
+
+/// Byte range (end exclusive, line terminator not included) and 1-based inclusive line range
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct TextRange {
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlineHeading {
+    pub level: u8,
+    pub text: String,
+    pub range: TextRange,         // The heading line(s)
+    pub section_range: TextRange, // Heading plus everything up to the next heading of the same or higher level
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskItem {
+    pub checked: bool,
+    pub text: String,
+    pub indent: usize,               // Leading whitespace width, for nested checklists
+    pub heading: Option<String>,     // Text of the closest heading above the task
+    pub range: TextRange,
+    #[serde(skip)]
+    checkbox_byte: usize,            // Offset of the character between the brackets
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub range: TextRange, // Includes the fence lines
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkdownLink {
+    pub text: String,
+    pub target: String,
+    pub is_image: bool,
+    pub range: TextRange,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkdownOutline {
+    pub token: String, // Content hash, usable as expected_token for edits
+    pub headings: Vec<OutlineHeading>,
+    pub tasks: Vec<TaskItem>,
+    pub code_blocks: Vec<CodeBlock>,
+    pub links: Vec<MarkdownLink>,
+}
+
+// A line of the document with its position
+struct Line<'a> {
+    text: &'a str, // Without the line terminator
+    start: usize,
+    number: usize,
+}
+
+impl Line<'_> {
+    fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+fn split_lines(content: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for (i, raw) in content.split_inclusive('\n').enumerate() {
+        let text = raw.trim_end_matches('\n').trim_end_matches('\r');
+        lines.push(Line { text, start, number: i + 1 });
+        start += raw.len();
+    }
+    lines
+}
+
+fn line_range(first: &Line, last: &Line) -> TextRange {
+    TextRange {
+        start_byte: first.start,
+        end_byte: last.end(),
+        start_line: first.number,
+        end_line: last.number,
+    }
+}
+
+/// Parse an ATX heading line ("## Title ##"), returning level and text
+fn parse_atx_heading(text: &str) -> Option<(u8, String)> {
+    let indent = text.len() - text.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &text[indent..];
+    let level = rest.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let after = &rest[level..];
+    if !after.is_empty() && !after.starts_with(' ') && !after.starts_with('\t') {
+        return None;
+    }
+
+    // Strip an optional closing sequence of #s
+    let mut title = after.trim();
+    let without_closing = title.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with(' ') {
+        title = without_closing.trim_end();
+    }
+
+    Some((level as u8, title.to_string()))
+}
+
+/// Parse a code fence opening/closing line, returning fence char, length and info string
+fn parse_fence(text: &str) -> Option<(char, usize, &str)> {
+    let indent = text.len() - text.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &text[indent..];
+    let fence_char = rest.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let length = rest.chars().take_while(|&c| c == fence_char).count();
+    if length < 3 {
+        return None;
+    }
+    Some((fence_char, length, rest[length..].trim()))
+}
+
+fn is_setext_underline(text: &str) -> Option<u8> {
+    let trimmed = text.trim();
+    if text.len() - text.trim_start_matches(' ').len() > 3 || trimmed.is_empty() {
+        return None;
+    }
+    if trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+// Compiled once: task counting parses every spec file on each listing
+fn task_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^(\s*)(?:[-*+]|\d+[.)])\s+\[([ xX])\]\s*(.*)$").unwrap())
+}
+
+fn list_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s").unwrap())
+}
+
+fn link_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"(!?)\[([^\]]*)\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)|<(https?://[^>\s]+)>"#).unwrap())
+}
+
+/// Parse markdown content into an outline of headings, tasks, code blocks and links
+pub fn parse_outline(content: &str) -> MarkdownOutline {
+    let (task_regex, list_regex, link_regex) = (task_regex(), list_regex(), link_regex());
+
+    let lines = split_lines(content);
+    let mut headings: Vec<OutlineHeading> = Vec::new();
+    let mut tasks = Vec::new();
+    let mut code_blocks = Vec::new();
+    let mut links = Vec::new();
+
+    let mut open_fence: Option<(char, usize, Option<String>, usize)> = None; // char, length, language, start line index
+    let mut paragraph_line: Option<usize> = None; // Index of a plain text line that may become a setext heading
+    let mut index = 0;
+
+    // Skip YAML front matter
+    if lines.first().map(|l| l.text == "---").unwrap_or(false) {
+        if let Some(end) = lines.iter().skip(1).position(|l| l.text == "---" || l.text == "...") {
+            index = end + 2;
+        }
+    }
+
+    while index < lines.len() {
+        let line = &lines[index];
+
+        // Inside a fenced code block: only look for the closing fence
+        if let Some((fence_char, length, language, start)) = &open_fence {
+            if let Some((c, l, info)) = parse_fence(line.text) {
+                if c == *fence_char && l >= *length && info.is_empty() {
+                    code_blocks.push(CodeBlock {
+                        language: language.clone(),
+                        range: line_range(&lines[*start], line),
+                    });
+                    open_fence = None;
+                }
+            }
+            index += 1;
+            continue;
+        }
+
+        if let Some((fence_char, length, info)) = parse_fence(line.text) {
+            let language = info.split_whitespace().next().map(|s| s.to_string());
+            open_fence = Some((fence_char, length, language, index));
+            paragraph_line = None;
+            index += 1;
+            continue;
+        }
+
+        if let Some((level, text)) = parse_atx_heading(line.text) {
+            headings.push(OutlineHeading {
+                level,
+                text,
+                range: line_range(line, line),
+                section_range: line_range(line, line),
+            });
+            paragraph_line = None;
+            index += 1;
+            continue;
+        }
+
+        if let (Some(level), Some(previous)) = (is_setext_underline(line.text), paragraph_line) {
+            let first = &lines[previous];
+            headings.push(OutlineHeading {
+                level,
+                text: first.text.trim().to_string(),
+                range: line_range(first, line),
+                section_range: line_range(first, line),
+            });
+            paragraph_line = None;
+            index += 1;
+            continue;
+        }
+
+        if let Some(captures) = task_regex.captures(line.text) {
+            let checkbox = captures.get(2).unwrap();
+            tasks.push(TaskItem {
+                checked: checkbox.as_str() != " ",
+                text: captures.get(3).map(|m| m.as_str().trim().to_string()).unwrap_or_default(),
+                indent: captures.get(1).map(|m| m.as_str().len()).unwrap_or(0),
+                heading: headings.last().map(|h| h.text.clone()),
+                range: line_range(line, line),
+                checkbox_byte: line.start + checkbox.start(),
+            });
+        }
+
+        for captures in link_regex.captures_iter(line.text) {
+            let whole = captures.get(0).unwrap();
+            let (text, target, is_image) = match captures.get(4) {
+                Some(url) => (url.as_str().to_string(), url.as_str().to_string(), false),
+                None => (
+                    captures.get(2).map(|m| m.as_str().to_string()).unwrap_or_default(),
+                    captures.get(3).map(|m| m.as_str().to_string()).unwrap_or_default(),
+                    captures.get(1).map(|m| m.as_str() == "!").unwrap_or(false),
+                ),
+            };
+            links.push(MarkdownLink {
+                text,
+                target,
+                is_image,
+                range: TextRange {
+                    start_byte: line.start + whole.start(),
+                    end_byte: line.start + whole.end(),
+                    start_line: line.number,
+                    end_line: line.number,
+                },
+            });
+        }
+
+        // Only plain paragraph text can be turned into a setext heading by the next line
+        let is_plain = !line.text.trim().is_empty() && !list_regex.is_match(line.text)
+            && !line.text.trim_start().starts_with('>');
+        paragraph_line = if is_plain { Some(index) } else { None };
+        index += 1;
+    }
+
+    // An unclosed fence runs to the end of the document
+    if let (Some((_, _, language, start)), Some(last)) = (open_fence, lines.last()) {
+        code_blocks.push(CodeBlock {
+            language,
+            range: line_range(&lines[start], last),
+        });
+    }
+
+    // Each section runs until the next heading of the same or higher level
+    let document_end = lines.last().map(|l| (l.end(), l.number));
+    for i in 0..headings.len() {
+        let level = headings[i].level;
+        let end = headings[i + 1..].iter()
+            .find(|h| h.level <= level)
+            .map(|next| {
+                let previous_line = &lines[next.range.start_line - 2];
+                (previous_line.end(), previous_line.number)
+            })
+            .or(document_end);
+        if let Some((end_byte, end_line)) = end {
+            headings[i].section_range.end_byte = end_byte;
+            headings[i].section_range.end_line = end_line;
+        }
+    }
+
+    MarkdownOutline {
+        token: content_hash(content.as_bytes()),
+        headings,
+        tasks,
+        code_blocks,
+        links,
+    }
+}
+
+/// Parse the `Key: value` pairs of a YAML front matter block, if the document has one
+pub fn parse_front_matter(content: &str) -> Vec<(String, String)> {
+    let mut lines = content.lines();
+    if lines.next().map(|l| l.trim_end()) != Some("---") {
+        return Vec::new();
+    }
+
+    let mut fields = Vec::new();
+    for line in lines {
+        let line = line.trim_end();
+        if line == "---" || line == "..." {
+            return fields;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            fields.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    // No closing delimiter: not front matter
+    Vec::new()
+}
+
+/// Set or flip the checkbox of the task on the given 1-based line
+pub fn toggle_task(content: &str, line: usize, checked: Option<bool>) -> Result<String, String> {
+    let outline = parse_outline(content);
+    let task = outline.tasks.iter()
+        .find(|t| t.range.start_line == line)
+        .ok_or_else(|| format!("No task item on line {}", line))?;
+
+    let new_state = checked.unwrap_or(!task.checked);
+    let mark = if new_state { "x" } else { " " };
+
+    let mut updated = String::with_capacity(content.len());
+    updated.push_str(&content[..task.checkbox_byte]);
+    updated.push_str(mark);
+    updated.push_str(&content[task.checkbox_byte + 1..]);
+    Ok(updated)
+}
+
+/// Replace the body of the section under a heading (the heading line itself is kept).
+/// `level` disambiguates when several headings share the same text.
+pub fn replace_section(content: &str, heading: &str, level: Option<u8>, body: &str) -> Result<String, String> {
+    let outline = parse_outline(content);
+    let wanted = heading.trim().trim_start_matches('#').trim().to_lowercase();
+    let matches: Vec<&OutlineHeading> = outline.headings.iter()
+        .filter(|h| h.text.to_lowercase() == wanted && level.map(|l| l == h.level).unwrap_or(true))
+        .collect();
+
+    let target = match matches.as_slice() {
+        [] => return Err(format!("Heading not found: {}", heading)),
+        [single] => *single,
+        _ => return Err(format!("Heading is ambiguous, specify its level: {}", heading)),
+    };
+
+    // Body starts on the line after the heading and ends with the section
+    let heading_end = target.range.end_byte;
+    let body_start = content[heading_end..].find('\n')
+        .map(|i| heading_end + i + 1)
+        .unwrap_or(content.len());
+    let section_end = target.section_range.end_byte;
+    let body_end = content[section_end..].find('\n')
+        .map(|i| section_end + i + 1)
+        .unwrap_or(content.len())
+        .max(body_start);
+
+    let mut new_body = body.trim_end_matches(['\r', '\n']).to_string();
+    if !new_body.is_empty() {
+        new_body.push('\n');
+    }
+    // Keep a blank line before whatever heading follows the section
+    if body_end < content.len() {
+        new_body.push('\n');
+    }
+    if body_start == content.len() && !content.ends_with('\n') {
+        new_body.insert(0, '\n');
+    }
+
+    let mut updated = String::with_capacity(content.len() + new_body.len());
+    updated.push_str(&content[..body_start]);
+    updated.push_str(&new_body);
+    updated.push_str(&content[body_end..]);
+    Ok(updated)
+}
+
+fn features_dir(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(".prompts").join("features")
+}
+
+/// Apply an edit to a feature file and write it back through the conflict-aware writer
+fn edit_feature_file<F>(
+    project_path: &str,
+    file_path: &str,
+    expected_token: Option<String>,
+    source: &str,
+    edit: F,
+) -> Result<MarkdownOutline, FileWriteError>
+where
+    F: FnOnce(&str) -> Result<String, String>,
+{
+    let full_path = file_io::resolve_existing(&features_dir(project_path), file_path, "features")?;
+    let project_root = PathBuf::from(project_path).canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+
+    let content = fs::read_to_string(&full_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    // Without a token, guard against changes between our read and write
+    let expected = expected_token.unwrap_or_else(|| content_hash(content.as_bytes()));
+
+    let updated = edit(&content)?;
+    file_io::write_checked(&project_root, &full_path, file_path, &updated, Some(&expected), source)?;
+
+    Ok(parse_outline(&updated))
+}
+
+// Tauri command: Parse a feature file into headings, tasks, code blocks and links
+#[tauri::command]
+pub async fn get_feature_outline(project_path: String, file_path: String) -> Result<MarkdownOutline, String> {
+    let full_path = file_io::resolve_existing(&features_dir(&project_path), &file_path, "features")?;
+    let content = fs::read_to_string(&full_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(parse_outline(&content))
+}
+
+// Tauri command: Check or uncheck a task item in a feature file (checked = None flips it)
+#[tauri::command]
+pub async fn toggle_feature_task(
+    project_path: String,
+    file_path: String,
+    line: usize,
+    checked: Option<bool>,
+    expected_token: Option<String>,
+) -> Result<MarkdownOutline, FileWriteError> {
+    edit_feature_file(&project_path, &file_path, expected_token, "toggle_feature_task", |content| {
+        toggle_task(content, line, checked)
+    })
+}
+
+// Tauri command: Replace the content of a section in a feature file, found by its heading
+#[tauri::command]
+pub async fn replace_feature_section(
+    project_path: String,
+    file_path: String,
+    heading: String,
+    level: Option<u8>,
+    content: String,
+    expected_token: Option<String>,
+) -> Result<MarkdownOutline, FileWriteError> {
+    edit_feature_file(&project_path, &file_path, expected_token, "replace_feature_section", |existing| {
+        replace_section(existing, &heading, level, &content)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "# Add Login\n\nIntro with a [link](./other.md).\n\n## Acceptance Criteria\n- [ ] Users can sign in\n- [x] Errors are shown\n  - [ ] Nested item\n\n```ts\n# not a heading\n- [ ] not a task\n```\n\n## Notes\nSee <https://example.com>\n";
+
+    #[test]
+    fn test_parse_headings_and_sections() {
+        let outline = parse_outline(SPEC);
+        let titles: Vec<(u8, &str)> = outline.headings.iter().map(|h| (h.level, h.text.as_str())).collect();
+        assert_eq!(titles, vec![(1, "Add Login"), (2, "Acceptance Criteria"), (2, "Notes")]);
+
+        let criteria = &outline.headings[1];
+        assert_eq!(criteria.range.start_line, 5);
+        assert_eq!(criteria.section_range.end_line, 14);
+        assert_eq!(outline.headings[0].section_range.end_line, 16);
+        assert_eq!(&SPEC[criteria.range.start_byte..criteria.range.end_byte], "## Acceptance Criteria");
+    }
+
+    #[test]
+    fn test_parse_tasks_code_blocks_and_links() {
+        let outline = parse_outline(SPEC);
+
+        assert_eq!(outline.tasks.len(), 3);
+        assert!(!outline.tasks[0].checked);
+        assert!(outline.tasks[1].checked);
+        assert_eq!(outline.tasks[2].indent, 2);
+        assert_eq!(outline.tasks[0].heading.as_deref(), Some("Acceptance Criteria"));
+
+        assert_eq!(outline.code_blocks.len(), 1);
+        assert_eq!(outline.code_blocks[0].language.as_deref(), Some("ts"));
+        assert_eq!(outline.code_blocks[0].range.start_line, 10);
+        assert_eq!(outline.code_blocks[0].range.end_line, 13);
+
+        let targets: Vec<&str> = outline.links.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(targets, vec!["./other.md", "https://example.com"]);
+    }
+
+    #[test]
+    fn test_setext_heading() {
+        let outline = parse_outline("Title\n=====\n\ntext\n\nSub\n---\n");
+        let titles: Vec<(u8, &str)> = outline.headings.iter().map(|h| (h.level, h.text.as_str())).collect();
+        assert_eq!(titles, vec![(1, "Title"), (2, "Sub")]);
+    }
+
+    #[test]
+    fn test_toggle_task() {
+        let updated = toggle_task(SPEC, 6, None).unwrap();
+        assert!(updated.contains("- [x] Users can sign in"));
+        let updated = toggle_task(&updated, 7, Some(false)).unwrap();
+        assert!(updated.contains("- [ ] Errors are shown"));
+        assert!(toggle_task(SPEC, 1, None).is_err());
+    }
+
+    #[test]
+    fn test_replace_section() {
+        let updated = replace_section(SPEC, "Notes", None, "Nothing to add.").unwrap();
+        assert!(updated.ends_with("## Notes\nNothing to add.\n"));
+
+        let updated = replace_section(SPEC, "## Acceptance Criteria", Some(2), "- [ ] Only item").unwrap();
+        assert!(updated.contains("## Acceptance Criteria\n- [ ] Only item\n\n## Notes\n"));
+
+        assert!(replace_section(SPEC, "Missing", None, "x").is_err());
+    }
+}
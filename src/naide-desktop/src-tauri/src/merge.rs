@@ -0,0 +1,414 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde::Serialize;
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+use crate::file_history;
+
+/// One region of a three-way merge result
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MergeHunk {
+    // Lines that merged cleanly (unchanged, or changed on one side only)
+    Resolved {
+        lines: Vec<String>,
+    },
+    // Both sides changed the same region differently
+    Conflict {
+        base_start_line: usize, // 1-based line in the base text where the region starts
+        base: Vec<String>,
+        ours: Vec<String>,
+        theirs: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeResult {
+    pub clean: bool,
+    pub merged: Option<String>,             // Merged text, only when there are no conflicts
+    pub merged_with_markers: String,        // Merged text with git-style conflict markers
+    pub conflict_count: usize,
+    pub hunks: Vec<MergeHunk>,
+    pub base_source: String,                // "history", "git" or "none"
+    pub theirs_token: Option<String>,       // Token of the on-disk content used as "theirs"
+}
+
+// A change made by one side relative to the base: base[start..end] replaced by lines
+#[derive(Debug, Clone)]
+struct Change {
+    start: usize,
+    end: usize,
+    lines: Vec<String>,
+}
+
+fn split_lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(|l| l.to_string()).collect()
+}
+
+/// Collect the regions one side changed relative to the base
+fn changes_between(base: &[String], side: &[String]) -> Vec<Change> {
+    let mut changes: Vec<Change> = Vec::new();
+
+    for op in capture_diff_slices(Algorithm::Myers, base, side) {
+        let (start, end, lines) = match op {
+            DiffOp::Equal { .. } => continue,
+            DiffOp::Delete { old_index, old_len, .. } => (old_index, old_index + old_len, Vec::new()),
+            DiffOp::Insert { old_index, new_index, new_len } => {
+                (old_index, old_index, side[new_index..new_index + new_len].to_vec())
+            }
+            DiffOp::Replace { old_index, old_len, new_index, new_len } => {
+                (old_index, old_index + old_len, side[new_index..new_index + new_len].to_vec())
+            }
+        };
+
+        // Merge with the previous change when they touch
+        if let Some(last) = changes.last_mut() {
+            if last.end == start {
+                last.end = end;
+                last.lines.extend(lines);
+                continue;
+            }
+        }
+        changes.push(Change { start, end, lines });
+    }
+
+    changes
+}
+
+/// Rebuild one side's content for base[start..end] from its changes inside that range
+fn side_content(base: &[String], changes: &[Change], start: usize, end: usize) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut pos = start;
+
+    for change in changes {
+        result.extend_from_slice(&base[pos..change.start]);
+        result.extend(change.lines.iter().cloned());
+        pos = change.end;
+    }
+    result.extend_from_slice(&base[pos..end]);
+
+    result
+}
+
+fn push_resolved(hunks: &mut Vec<MergeHunk>, lines: Vec<String>) {
+    if lines.is_empty() {
+        return;
+    }
+    if let Some(MergeHunk::Resolved { lines: existing }) = hunks.last_mut() {
+        existing.extend(lines);
+    } else {
+        hunks.push(MergeHunk::Resolved { lines });
+    }
+}
+
+/// Three-way merge of line-based text
+pub fn merge_texts(base: &str, ours: &str, theirs: &str) -> Vec<MergeHunk> {
+    let base_lines = split_lines(base);
+    let our_changes = changes_between(&base_lines, &split_lines(ours));
+    let their_changes = changes_between(&base_lines, &split_lines(theirs));
+
+    let mut hunks = Vec::new();
+    let mut pos = 0;
+    let (mut i, mut j) = (0, 0);
+
+    while i < our_changes.len() || j < their_changes.len() {
+        // Start a cluster with whichever change comes first in the base
+        let take_ours = match (our_changes.get(i), their_changes.get(j)) {
+            (Some(a), Some(b)) => a.start <= b.start,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        let first = if take_ours { &our_changes[i] } else { &their_changes[j] };
+        let cluster_start = first.start;
+        let mut cluster_end = first.end;
+        let (ours_from, theirs_from) = (i, j);
+        if take_ours { i += 1 } else { j += 1 }
+
+        // Grow the cluster with every change that overlaps or touches it
+        loop {
+            if let Some(c) = our_changes.get(i).filter(|c| c.start <= cluster_end) {
+                cluster_end = cluster_end.max(c.end);
+                i += 1;
+            } else if let Some(c) = their_changes.get(j).filter(|c| c.start <= cluster_end) {
+                cluster_end = cluster_end.max(c.end);
+                j += 1;
+            } else {
+                break;
+            }
+        }
+
+        push_resolved(&mut hunks, base_lines[pos..cluster_start].to_vec());
+
+        let ours_in_cluster = &our_changes[ours_from..i];
+        let theirs_in_cluster = &their_changes[theirs_from..j];
+        let our_lines = side_content(&base_lines, ours_in_cluster, cluster_start, cluster_end);
+        let their_lines = side_content(&base_lines, theirs_in_cluster, cluster_start, cluster_end);
+
+        if theirs_in_cluster.is_empty() {
+            push_resolved(&mut hunks, our_lines);
+        } else if ours_in_cluster.is_empty() || our_lines == their_lines {
+            push_resolved(&mut hunks, their_lines);
+        } else {
+            hunks.push(MergeHunk::Conflict {
+                base_start_line: cluster_start + 1,
+                base: base_lines[cluster_start..cluster_end].to_vec(),
+                ours: our_lines,
+                theirs: their_lines,
+            });
+        }
+
+        pos = cluster_end;
+    }
+
+    push_resolved(&mut hunks, base_lines[pos..].to_vec());
+    hunks
+}
+
+/// Render merge hunks as text with git-style conflict markers
+pub fn render_with_markers(hunks: &[MergeHunk]) -> String {
+    fn push_block(out: &mut String, lines: &[String]) {
+        for line in lines {
+            out.push_str(line);
+        }
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    let mut out = String::new();
+    for hunk in hunks {
+        match hunk {
+            MergeHunk::Resolved { lines } => {
+                for line in lines {
+                    out.push_str(line);
+                }
+            }
+            MergeHunk::Conflict { base, ours, theirs, .. } => {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("<<<<<<< yours\n");
+                push_block(&mut out, ours);
+                out.push_str("||||||| base\n");
+                push_block(&mut out, base);
+                out.push_str("=======\n");
+                push_block(&mut out, theirs);
+                out.push_str(">>>>>>> theirs\n");
+            }
+        }
+    }
+    out
+}
+
+fn build_result(hunks: Vec<MergeHunk>, base_source: &str, theirs_token: Option<String>) -> MergeResult {
+    let conflict_count = hunks.iter()
+        .filter(|h| matches!(h, MergeHunk::Conflict { .. }))
+        .count();
+    let merged_with_markers = render_with_markers(&hunks);
+
+    MergeResult {
+        clean: conflict_count == 0,
+        merged: if conflict_count == 0 { Some(merged_with_markers.clone()) } else { None },
+        merged_with_markers,
+        conflict_count,
+        hunks,
+        base_source: base_source.to_string(),
+        theirs_token,
+    }
+}
+
+/// Read a file's content at HEAD from git, if the project is a git repository.
+/// "HEAD:./path" is relative to the -C directory, so this also works when the project is a subfolder of the repository.
+fn read_git_head_content(project_root: &Path, relative_path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(project_root)
+        .arg("show")
+        .arg(format!("HEAD:./{}", relative_path.replace('\\', "/")))
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Find the merge base: the history revision matching the token the editor read,
+/// falling back to the last committed version in git
+fn find_merge_base(project_root: &Path, relative_path: &str, base_token: Option<&str>) -> (String, &'static str) {
+    if let Some(token) = base_token {
+        match file_history::find_revision_by_hash(project_root, relative_path, token) {
+            Ok(Some(revision)) => {
+                if let Ok(content) = file_history::read_revision_content(project_root, relative_path, &revision.id) {
+                    return (String::from_utf8_lossy(&content).to_string(), "history");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to read history for {}: {}", relative_path, e),
+        }
+    }
+
+    if let Some(content) = read_git_head_content(project_root, relative_path) {
+        return (content, "git");
+    }
+
+    (String::new(), "none")
+}
+
+fn merge_in_directory(
+    project_path: &str,
+    base_dir: PathBuf,
+    file_path: &str,
+    base_token: Option<String>,
+    ours: String,
+    theirs: Option<String>,
+) -> Result<MergeResult, String> {
+    let project_root = PathBuf::from(project_path).canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+    let canonical_base_dir = base_dir.canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+
+    let full_path = canonical_base_dir.join(file_path);
+    let parent = full_path.parent()
+        .ok_or_else(|| "Invalid file path: no parent directory".to_string())?
+        .canonicalize()
+        .map_err(|e| format!("Invalid parent directory: {}", e))?;
+    if !parent.starts_with(&canonical_base_dir) {
+        return Err("Access denied: path outside of allowed directory".to_string());
+    }
+    let file_name = full_path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let target_path = parent.join(file_name);
+    let relative_path = file_history::relative_key(&project_root, &target_path)
+        .ok_or_else(|| "Access denied: path outside of project directory".to_string())?;
+
+    // "Theirs" defaults to what is on disk now (e.g. the AI's edit)
+    let (theirs, theirs_token) = match theirs {
+        Some(text) => (text, None),
+        None => {
+            let disk = fs::read(&target_path).unwrap_or_default();
+            let token = file_history::content_hash(&disk);
+            (String::from_utf8_lossy(&disk).to_string(), Some(token))
+        }
+    };
+
+    let (base, base_source) = find_merge_base(&project_root, &relative_path, base_token.as_deref());
+    log::info!("Merging {} using {} merge base", relative_path, base_source);
+
+    let hunks = merge_texts(&base, &ours, &theirs);
+    Ok(build_result(hunks, base_source, theirs_token))
+}
+
+// Tauri command: Three-way merge of edits to a feature spec in .prompts/features/
+// base_token is the token the editor read the file with; theirs defaults to the content on disk
+#[tauri::command]
+pub async fn merge_feature_file(
+    project_path: String,
+    file_path: String,
+    base_token: Option<String>,
+    ours: String,
+    theirs: Option<String>,
+) -> Result<MergeResult, String> {
+    let features_dir = PathBuf::from(&project_path).join(".prompts").join("features");
+    merge_in_directory(&project_path, features_dir, &file_path, base_token, ours, theirs)
+}
+
+// Tauri command: Three-way merge of edits to any project file
+#[tauri::command]
+pub async fn merge_project_file(
+    project_path: String,
+    file_path: String,
+    base_token: Option<String>,
+    ours: String,
+    theirs: Option<String>,
+) -> Result<MergeResult, String> {
+    let project_dir = PathBuf::from(&project_path);
+    merge_in_directory(&project_path, project_dir, &file_path, base_token, ours, theirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    const BASE: &str = "# Feature\n\n## Goals\n- one\n- two\n\n## Tasks\n- [ ] first\n- [ ] second\n";
+
+    #[test]
+    fn test_merge_non_overlapping_edits() {
+        let ours = BASE.replace("- one", "- one (edited)");
+        let theirs = BASE.replace("- [ ] second", "- [x] second");
+
+        let result = build_result(merge_texts(BASE, &ours, &theirs), "history", None);
+        assert!(result.clean);
+        let merged = result.merged.unwrap();
+        assert!(merged.contains("- one (edited)"));
+        assert!(merged.contains("- [x] second"));
+    }
+
+    #[test]
+    fn test_merge_identical_edits_are_clean() {
+        let edited = BASE.replace("- two", "- three");
+        let result = build_result(merge_texts(BASE, &edited, &edited), "history", None);
+        assert!(result.clean);
+        assert_eq!(result.merged.unwrap(), edited);
+    }
+
+    #[test]
+    fn test_merge_conflicting_edits() {
+        let ours = BASE.replace("- two", "- two (mine)");
+        let theirs = BASE.replace("- two", "- two (AI)");
+
+        let hunks = merge_texts(BASE, &ours, &theirs);
+        let conflicts: Vec<&MergeHunk> = hunks.iter()
+            .filter(|h| matches!(h, MergeHunk::Conflict { .. }))
+            .collect();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0], &MergeHunk::Conflict {
+            base_start_line: 5,
+            base: vec!["- two\n".to_string()],
+            ours: vec!["- two (mine)\n".to_string()],
+            theirs: vec!["- two (AI)\n".to_string()],
+        });
+
+        let rendered = render_with_markers(&hunks);
+        assert!(rendered.contains("<<<<<<< yours\n- two (mine)\n||||||| base\n- two\n=======\n- two (AI)\n>>>>>>> theirs\n"));
+        assert!(rendered.starts_with("# Feature\n"));
+        assert!(rendered.ends_with("- [ ] second\n"));
+    }
+
+    #[test]
+    fn test_merge_appends_without_trailing_newline() {
+        let base = "line 1\nline 2";
+        let ours = "line 0\nline 1\nline 2";
+        let theirs = "line 1\nline 2\nline 3";
+
+        let result = build_result(merge_texts(base, ours, theirs), "git", None);
+        assert!(result.clean);
+        assert_eq!(result.merged.unwrap(), "line 0\nline 1\nline 2\nline 3");
+    }
+
+    #[test]
+    fn test_git_base_in_repository_subfolder() {
+        let root = TempDir::new("merge-git");
+        let project = root.join("apps").join("web");
+        std::fs::create_dir_all(project.join(".prompts")).unwrap();
+        std::fs::write(project.join(".prompts").join("spec.md"), BASE).unwrap();
+
+        let git = |args: &[&str]| Command::new("git")
+            .arg("-C").arg(&*root)
+            .args(["-c", "user.name=naide", "-c", "user.email=naide@example.com"])
+            .args(args)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if !git(&["init", "-q"]) {
+            return; // git is not installed
+        }
+        assert!(git(&["add", "."]) && git(&["commit", "-q", "-m", "spec"]));
+
+        assert_eq!(read_git_head_content(&project, ".prompts/spec.md").as_deref(), Some(BASE));
+        assert_eq!(read_git_head_content(&project, "missing.md"), None);
+    }
+}
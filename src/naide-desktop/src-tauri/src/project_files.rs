@@ -2,6 +2,8 @@ use std::fs;
 use std::path::PathBuf;
 use serde::Serialize;
 
+use crate::ignore_rules::ProjectIgnore;
+
 #[derive(Debug, Serialize)]
 pub struct ProjectFileNode {
     pub name: String,
@@ -10,9 +12,6 @@ pub struct ProjectFileNode {
     pub file_extension: Option<String>,
 }
 
-// List of folders/files to exclude from the project file tree
-const EXCLUDED_ITEMS: &[&str] = &["node_modules", ".git", "bin", "obj", "dist"];
-
 /// List immediate children of a directory (non-recursive, lazy loading)
 #[tauri::command]
 pub async fn list_project_files(
@@ -40,6 +39,8 @@ pub async fn list_project_files(
         return Err("Target path is not a directory".to_string());
     }
     
+    let rules = ProjectIgnore::load(&base_path);
+    
     // Read directory entries
     let entries = fs::read_dir(&target_path)
         .map_err(|e| format!("Failed to read directory: {}", e))?;
@@ -55,11 +56,6 @@ pub async fn list_project_files(
         
         let file_name = entry.file_name().to_string_lossy().to_string();
         
-        // Skip excluded items
-        if EXCLUDED_ITEMS.contains(&file_name.as_str()) {
-            continue;
-        }
-        
         // Get metadata
         let metadata = match entry.metadata() {
             Ok(m) => m,
@@ -68,6 +64,11 @@ pub async fn list_project_files(
         
         let is_folder = metadata.is_dir();
         
+        // Skip ignored items (.gitignore, .git/info/exclude, .naideignore and defaults)
+        if rules.is_ignored(&entry.path(), is_folder) {
+            continue;
+        }
+        
         // Calculate relative path from project root
         let full_path = entry.path();
         let relative = full_path
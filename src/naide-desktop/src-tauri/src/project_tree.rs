@@ -0,0 +1,513 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
+use serde::Serialize;
+
+use crate::ignore_rules::ProjectIgnore;
+
+const DEFAULT_PAGE_SIZE: usize = 500;
+const MAX_PAGE_SIZE: usize = 5000;
+const MAX_DEPTH: usize = 32;
+// Bytes inspected to decide whether a file is binary
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectTreeEntry {
+    pub name: String,
+    pub path: String,                   // Relative to the project root, forward slashes
+    pub depth: usize,                   // 1 for the direct children of the listed folder
+    pub is_folder: bool,
+    pub file_extension: Option<String>,
+    pub size: Option<u64>,              // Files only
+    pub modified: Option<u64>,          // Milliseconds since epoch
+    pub readonly: bool,
+    pub mode: Option<u32>,              // Unix permission bits
+    pub is_binary: Option<bool>,        // Files only; None if the file could not be read
+    pub is_symlink: bool,
+    pub symlink_target: Option<String>,
+    pub git_status: Option<String>,     // "modified", "added", "deleted", "renamed", "untracked", "conflicted"
+    pub has_children: Option<bool>,     // Folders at the depth limit; None for files and expanded folders
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectTreePage {
+    pub entries: Vec<ProjectTreeEntry>, // Depth-first, folders before files, names case-insensitive
+    pub next_cursor: Option<String>,    // Pass back to get the next page; None on the last page
+}
+
+// Sort key shared by listing and cursor positioning: folders first, then case-insensitive name
+fn sort_key(is_folder: bool, name: &str) -> (bool, String, String) {
+    (!is_folder, name.to_lowercase(), name.to_string())
+}
+
+struct DirEntryInfo {
+    name: String,
+    path: PathBuf,
+    is_folder: bool,
+}
+
+fn read_sorted_dir(dir: &Path, rules: &ProjectIgnore) -> Vec<DirEntryInfo> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Failed to read directory {:?}: {}", dir, e);
+            return Vec::new();
+        }
+    };
+
+    let mut list: Vec<DirEntryInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let is_folder = path.is_dir();
+            if rules.is_ignored(&path, is_folder) {
+                return None;
+            }
+            Some(DirEntryInfo { name: entry.file_name().to_string_lossy().to_string(), path, is_folder })
+        })
+        .collect();
+
+    list.sort_by_cached_key(|e| sort_key(e.is_folder, &e.name));
+    list
+}
+
+// Whether a folder has any entry that is not ignored, without listing all of it
+fn has_visible_entries(dir: &Path, rules: &ProjectIgnore) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).any(|e| {
+            let path = e.path();
+            !rules.is_ignored(&path, path.is_dir())
+        }))
+        .unwrap_or(false)
+}
+
+fn is_binary_file(path: &Path) -> Option<bool> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut buffer = vec![0u8; BINARY_SNIFF_BYTES];
+    let read = file.read(&mut buffer).ok()?;
+    Some(buffer[..read].contains(&0))
+}
+
+fn git_status_label(code: &str) -> &'static str {
+    let (index, worktree) = (code.chars().next().unwrap_or(' '), code.chars().nth(1).unwrap_or(' '));
+    if code == "??" {
+        "untracked"
+    } else if index == 'U' || worktree == 'U' || code == "AA" || code == "DD" {
+        "conflicted"
+    } else if index == 'R' || worktree == 'R' {
+        "renamed"
+    } else if index == 'A' {
+        "added"
+    } else if index == 'D' || worktree == 'D' {
+        "deleted"
+    } else {
+        "modified"
+    }
+}
+
+/// Git status of changed paths relative to the project root (empty if not a git repository).
+/// Untracked folders are reported with a trailing '/'. Sorted, so a folder's entries can be found by prefix.
+pub fn git_status_map(project_root: &Path) -> BTreeMap<String, String> {
+    let output = match Command::new("git")
+        .arg("-C")
+        .arg(project_root)
+        .args(["status", "--porcelain=v1", "-z", "--untracked-files=normal"])
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return BTreeMap::new(),
+    };
+
+    // git reports paths relative to the repository root, which may be above the project
+    let prefix = Command::new("git")
+        .arg("-C")
+        .arg(project_root)
+        .args(["rev-parse", "--show-prefix"])
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+
+    let mut statuses = BTreeMap::new();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut records = stdout.split('\0');
+    while let Some(record) = records.next() {
+        if record.len() < 4 {
+            continue;
+        }
+        let code = &record[..2];
+        let path = &record[3..];
+        // Renames are followed by the original path
+        if code.contains('R') || code.contains('C') {
+            records.next();
+        }
+        if let Some(rel) = path.strip_prefix(prefix.as_str()) {
+            statuses.insert(rel.to_string(), git_status_label(code).to_string());
+        }
+    }
+    statuses
+}
+
+type GitStatuses = Arc<BTreeMap<String, String>>;
+
+// Git status per project root, taken on a listing's first page and reused by the pages that follow
+fn status_cache() -> &'static Mutex<HashMap<PathBuf, GitStatuses>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, GitStatuses>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Run git status for a first page, or reuse the result of the first page when continuing a listing
+fn statuses_for_page(project_root: &Path, continuing: bool) -> GitStatuses {
+    if continuing {
+        if let Some(statuses) = status_cache().lock().unwrap_or_else(|e| e.into_inner()).get(project_root) {
+            return statuses.clone();
+        }
+    }
+    let statuses = Arc::new(git_status_map(project_root));
+    status_cache().lock().unwrap_or_else(|e| e.into_inner())
+        .insert(project_root.to_path_buf(), statuses.clone());
+    statuses
+}
+
+fn git_status_for(statuses: &BTreeMap<String, String>, rel_path: &str, is_folder: bool) -> Option<String> {
+    if let Some(status) = statuses.get(rel_path) {
+        return Some(status.clone());
+    }
+    // Inside an untracked folder
+    for (i, _) in rel_path.match_indices('/') {
+        if statuses.get(&rel_path[..=i]).is_some_and(|status| status == "untracked") {
+            return Some("untracked".to_string());
+        }
+    }
+    if is_folder {
+        let folder_prefix = format!("{}/", rel_path);
+        if let Some((path, status)) = statuses.range(folder_prefix.clone()..).next() {
+            if *path == folder_prefix {
+                return Some(status.clone());
+            }
+            if path.starts_with(&folder_prefix) {
+                return Some("modified".to_string());
+            }
+        }
+    }
+    None
+}
+
+fn build_entry(
+    info: &DirEntryInfo,
+    project_root: &Path,
+    depth: usize,
+    max_depth: usize,
+    rules: &ProjectIgnore,
+    statuses: &BTreeMap<String, String>,
+) -> ProjectTreeEntry {
+    let rel_path = info.path.strip_prefix(project_root)
+        .unwrap_or(&info.path)
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let link_metadata = fs::symlink_metadata(&info.path).ok();
+    let is_symlink = link_metadata.as_ref().map(|m| m.file_type().is_symlink()).unwrap_or(false);
+    let symlink_target = if is_symlink {
+        fs::read_link(&info.path).ok().map(|target| target.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    // Follow symlinks for size and times, fall back to the link itself if it is dangling
+    let metadata = fs::metadata(&info.path).ok().or(link_metadata);
+    let modified = metadata.as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64);
+    let readonly = metadata.as_ref().map(|m| m.permissions().readonly()).unwrap_or(false);
+
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.as_ref().map(|m| m.permissions().mode() & 0o7777)
+    };
+    #[cfg(not(unix))]
+    let mode = None;
+
+    let has_children = if info.is_folder && (depth >= max_depth || is_symlink) {
+        Some(has_visible_entries(&info.path, rules))
+    } else {
+        None
+    };
+
+    ProjectTreeEntry {
+        name: info.name.clone(),
+        file_extension: if info.is_folder {
+            None
+        } else {
+            info.path.extension().map(|ext| ext.to_string_lossy().to_string())
+        },
+        size: if info.is_folder { None } else { metadata.as_ref().map(|m| m.len()) },
+        modified,
+        readonly,
+        mode,
+        is_binary: if info.is_folder { None } else { is_binary_file(&info.path) },
+        is_symlink,
+        symlink_target,
+        git_status: git_status_for(statuses, &rel_path, info.is_folder),
+        has_children,
+        path: rel_path,
+        depth,
+        is_folder: info.is_folder,
+    }
+}
+
+// One directory being walked: its sorted entries and the next one to visit
+struct Frame {
+    entries: Vec<DirEntryInfo>,
+    next: usize,
+    depth: usize,
+}
+
+fn encode_cursor(entry: &ProjectTreeEntry) -> String {
+    format!("{}:{}", if entry.is_folder { "d" } else { "f" }, entry.path)
+}
+
+/// Rebuild the walk position right after the entry a cursor points to.
+/// Works even if that entry was deleted meanwhile, by using its sort position.
+fn resume_frames(
+    start_dir: &Path,
+    start_rel: &str,
+    cursor: &str,
+    max_depth: usize,
+    rules: &ProjectIgnore,
+) -> Result<Vec<Frame>, String> {
+    let (kind, path) = cursor.split_once(':').ok_or_else(|| "Invalid cursor".to_string())?;
+    let rel = if start_rel.is_empty() {
+        path
+    } else {
+        path.strip_prefix(start_rel).and_then(|p| p.strip_prefix('/')).ok_or_else(|| "Invalid cursor".to_string())?
+    };
+    let components: Vec<&str> = rel.split('/').filter(|c| !c.is_empty()).collect();
+    if components.is_empty() || components.len() > max_depth {
+        return Err("Invalid cursor".to_string());
+    }
+
+    let mut frames = Vec::new();
+    let mut dir = start_dir.to_path_buf();
+    for (i, component) in components.iter().enumerate() {
+        let is_last = i + 1 == components.len();
+        // Ancestors are folders; the cursor entry says what it was
+        let is_folder = !is_last || kind == "d";
+        let key = sort_key(is_folder, component);
+        let entries = read_sorted_dir(&dir, rules);
+        let position = entries.iter()
+            .position(|e| sort_key(e.is_folder, &e.name) > key)
+            .unwrap_or(entries.len());
+        // The cursor entry and its ancestors sit right before `position` if they still exist
+        let exists = position > 0 && entries[position - 1].name == *component;
+        frames.push(Frame { entries, next: position, depth: i + 1 });
+
+        if !is_last {
+            if !exists {
+                // The ancestor is gone: continue with its next sibling
+                break;
+            }
+            dir = dir.join(component);
+        } else if exists && is_folder && i + 1 < max_depth {
+            // The cursor folder was expanded: its children come next
+            let child_dir = dir.join(component);
+            if !fs::symlink_metadata(&child_dir).map(|m| m.file_type().is_symlink()).unwrap_or(false) {
+                frames.push(Frame { entries: read_sorted_dir(&child_dir, rules), next: 0, depth: i + 2 });
+            }
+        }
+    }
+
+    Ok(frames)
+}
+
+/// Walk a folder depth-first up to max_depth, returning at most page_size entries
+pub fn read_tree_page(
+    project_root: &Path,
+    relative_path: &str,
+    max_depth: usize,
+    page_size: usize,
+    cursor: Option<&str>,
+) -> Result<ProjectTreePage, String> {
+    let start_rel = relative_path.replace('\\', "/").trim_matches('/').to_string();
+    let start_dir = project_root.join(&start_rel);
+    if !start_dir.is_dir() {
+        return Err("Target path is not a directory".to_string());
+    }
+
+    let canonical_start = start_dir.canonicalize()
+        .map_err(|e| format!("Invalid directory: {}", e))?;
+    let canonical_root = project_root.canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+    if !canonical_start.starts_with(&canonical_root) {
+        return Err("Access denied: path outside of project directory".to_string());
+    }
+
+    let rules = ProjectIgnore::load(project_root);
+    let statuses = statuses_for_page(project_root, cursor.is_some());
+
+    let mut stack = match cursor {
+        Some(cursor) => resume_frames(&start_dir, &start_rel, cursor, max_depth, &rules)?,
+        None => vec![Frame { entries: read_sorted_dir(&start_dir, &rules), next: 0, depth: 1 }],
+    };
+
+    let mut entries = Vec::new();
+    while let Some(frame) = stack.last_mut() {
+        if frame.next >= frame.entries.len() {
+            stack.pop();
+            continue;
+        }
+        if entries.len() >= page_size {
+            break;
+        }
+
+        let info = &frame.entries[frame.next];
+        frame.next += 1;
+        let depth = frame.depth;
+        let entry = build_entry(info, project_root, depth, max_depth, &rules, &statuses);
+
+        // Do not descend into symlinked folders, they can form cycles
+        let descend = entry.is_folder && !entry.is_symlink && depth < max_depth;
+        let child_dir = info.path.clone();
+        entries.push(entry);
+
+        if descend {
+            stack.push(Frame { entries: read_sorted_dir(&child_dir, &rules), next: 0, depth: depth + 1 });
+        }
+    }
+
+    // More entries remain if any frame still has unvisited entries
+    let has_more = stack.iter().any(|frame| frame.next < frame.entries.len());
+    let next_cursor = if has_more { entries.last().map(encode_cursor) } else { None };
+
+    Ok(ProjectTreePage { entries, next_cursor })
+}
+
+// Tauri command: List a project folder recursively with metadata and git status
+// max_depth defaults to 1 (direct children only); results are paged with next_cursor
+#[tauri::command]
+pub async fn get_project_tree(
+    project_path: String,
+    relative_path: Option<String>,
+    max_depth: Option<usize>,
+    page_size: Option<usize>,
+    cursor: Option<String>,
+) -> Result<ProjectTreePage, String> {
+    let project_root = PathBuf::from(&project_path);
+    if !project_root.exists() {
+        return Err("Project path does not exist".to_string());
+    }
+
+    read_tree_page(
+        &project_root,
+        relative_path.as_deref().unwrap_or(""),
+        max_depth.unwrap_or(1).clamp(1, MAX_DEPTH),
+        page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+        cursor.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn project(name: &str) -> TempDir {
+        let root = TempDir::new(&format!("project-tree-{}", name));
+        fs::create_dir_all(root.join("src").join("components")).unwrap();
+        fs::create_dir_all(root.join("node_modules").join("react")).unwrap();
+        fs::write(root.join("README.md"), "# Readme").unwrap();
+        fs::write(root.join("logo.png"), [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+        fs::write(root.join("src").join("main.ts"), "export {}").unwrap();
+        fs::write(root.join("src").join("components").join("App.tsx"), "").unwrap();
+        root
+    }
+
+    fn paths(page: &ProjectTreePage) -> Vec<&str> {
+        page.entries.iter().map(|e| e.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_recursive_listing_with_metadata() {
+        let root = project("listing");
+
+        let page = read_tree_page(&root, "", 3, 100, None).unwrap();
+        assert_eq!(paths(&page), vec!["src", "src/components", "src/components/App.tsx", "src/main.ts", "logo.png", "README.md"]);
+        assert!(page.next_cursor.is_none());
+
+        let logo = page.entries.iter().find(|e| e.name == "logo.png").unwrap();
+        assert_eq!(logo.is_binary, Some(true));
+        assert_eq!(logo.size, Some(6));
+        let readme = page.entries.iter().find(|e| e.name == "README.md").unwrap();
+        assert_eq!(readme.is_binary, Some(false));
+        assert_eq!(readme.depth, 1);
+
+        // Depth limit: folders report whether they have children instead
+        let page = read_tree_page(&root, "", 1, 100, None).unwrap();
+        assert_eq!(paths(&page), vec!["src", "logo.png", "README.md"]);
+        assert_eq!(page.entries[0].has_children, Some(true));
+    }
+
+    #[test]
+    fn test_pagination_resumes_after_cursor() {
+        let root = project("pages");
+
+        let all = read_tree_page(&root, "", 3, 100, None).unwrap();
+        let mut collected = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = read_tree_page(&root, "", 3, 2, cursor.as_deref()).unwrap();
+            collected.extend(page.entries.iter().map(|e| e.path.clone()));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(collected, paths(&all));
+
+        // A deleted cursor entry still resumes at its sort position
+        let page = read_tree_page(&root, "", 3, 100, Some("d:src/components")).unwrap();
+        fs::remove_dir_all(root.join("src").join("components")).unwrap();
+        let after_deleted = read_tree_page(&root, "", 3, 100, Some("d:src/components")).unwrap();
+        assert_eq!(paths(&after_deleted), vec!["src/main.ts", "logo.png", "README.md"]);
+        assert_eq!(page.entries[0].path, "src/components/App.tsx");
+    }
+
+    #[test]
+    fn test_git_status_labels() {
+        assert_eq!(git_status_label("??"), "untracked");
+        assert_eq!(git_status_label(" M"), "modified");
+        assert_eq!(git_status_label("A "), "added");
+        assert_eq!(git_status_label("R "), "renamed");
+        assert_eq!(git_status_label("UU"), "conflicted");
+
+        let mut statuses = BTreeMap::new();
+        statuses.insert("new/".to_string(), "untracked".to_string());
+        statuses.insert("src/a.ts".to_string(), "modified".to_string());
+        assert_eq!(git_status_for(&statuses, "new/file.ts", false).as_deref(), Some("untracked"));
+        assert_eq!(git_status_for(&statuses, "new", true).as_deref(), Some("untracked"));
+        assert_eq!(git_status_for(&statuses, "src", true).as_deref(), Some("modified"));
+        assert_eq!(git_status_for(&statuses, "docs", true), None);
+        assert_eq!(git_status_for(&statuses, "sr", true), None);
+    }
+
+    #[test]
+    fn test_git_status_reused_across_pages() {
+        let root = project("status-cache");
+        let statuses = BTreeMap::from([("src/main.ts".to_string(), "modified".to_string())]);
+        status_cache().lock().unwrap().insert(root.to_path_buf(), Arc::new(statuses));
+
+        // A continued listing uses the status taken for its first page
+        let page = read_tree_page(&root, "", 3, 100, Some("d:src/components")).unwrap();
+        let main = page.entries.iter().find(|e| e.path == "src/main.ts").unwrap();
+        assert_eq!(main.git_status.as_deref(), Some("modified"));
+
+        // A first page runs git status again (not a repository here)
+        let page = read_tree_page(&root, "", 3, 100, None).unwrap();
+        assert!(page.entries.iter().all(|e| e.git_status.is_none()));
+    }
+}
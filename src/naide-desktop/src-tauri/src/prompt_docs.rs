@@ -0,0 +1,430 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::file_history;
+use crate::file_io::{self, FileWriteError, VersionedContent};
+use crate::watchers::{self, FileChange, FileChangesPayload, WatchHandler, WatcherRegistry};
+use crate::{feature_progress, parse_filename, sort_feature_nodes, FeatureFileNode};
+
+// Size limits the sidecar applies when it loads system prompts (loadSystemPrompts skips
+// files over them). Must match copilot-sidecar/prompt-limits.json.
+// The sidecar loads its prompts from the Naide app repository; the commands below check a
+// project's own .prompts/system/ against the same limits, they do not validate the app's prompts.
+pub const MAX_SYSTEM_PROMPT_BYTES: usize = 32768;          // Any single *.system.md file
+pub const MAX_COMBINED_SYSTEM_PROMPT_BYTES: usize = 49152; // base.system.md plus one mode prompt
+// Report a warning once a prompt uses this share of its limit
+const SIZE_WARNING_PERCENT: usize = 80;
+
+const BASE_SYSTEM_PROMPT: &str = "base.system.md";
+const SYSTEM_PROMPT_SUFFIX: &str = ".system.md";
+
+/// Which `.prompts/` subdirectory a document lives in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromptDir {
+    Plan,
+    System,
+}
+
+impl PromptDir {
+    fn dir_name(self) -> &'static str {
+        match self {
+            PromptDir::Plan => "plan",
+            PromptDir::System => "system",
+        }
+    }
+
+    // File types shown in the tree for this directory
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            PromptDir::Plan => &[".md", ".json"],
+            PromptDir::System => &[".md"],
+        }
+    }
+
+    fn base_dir(self, project_path: &str) -> PathBuf {
+        PathBuf::from(project_path).join(".prompts").join(self.dir_name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptSizeCheck {
+    pub file: String,           // Relative path from .prompts/system/
+    pub bytes: usize,
+    pub combined_bytes: usize,  // Size together with base.system.md (equals bytes for the base itself)
+    pub max_bytes: usize,
+    pub max_combined_bytes: usize,
+    pub status: String,         // "ok", "warning" or "error"
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SystemPromptsChangedPayload {
+    changes: Vec<FileChange>,
+    size_checks: Vec<PromptSizeCheck>,
+}
+
+/// Scan a prompt directory into the same tree structure as the feature viewer
+fn scan_prompt_directory(base_dir: &Path, current_dir: &Path, extensions: &[&str]) -> Result<Vec<FeatureFileNode>, String> {
+    let mut nodes = Vec::new();
+
+    let entries = fs::read_dir(current_dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        // Skip hidden files and directories
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        let rel_path = path.strip_prefix(base_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        if path.is_dir() {
+            let children = scan_prompt_directory(base_dir, &path, extensions)?;
+            let (tasks_total, tasks_done) = children.iter()
+                .fold((0, 0), |(total, done), c| (total + c.tasks_total, done + c.tasks_done));
+
+            nodes.push(FeatureFileNode {
+                name: file_name.clone(),
+                full_name: file_name,
+                path: rel_path,
+                date: None,
+                is_folder: true,
+                children: Some(children),
+                tasks_total,
+                tasks_done,
+            });
+        } else if extensions.iter().any(|ext| file_name.ends_with(ext)) {
+            let (name, date) = parse_filename(&file_name);
+            let counts = if file_name.ends_with(".md") {
+                feature_progress::count_tasks_in_file(&path)
+            } else {
+                Default::default()
+            };
+
+            nodes.push(FeatureFileNode {
+                name,
+                full_name: file_name,
+                path: rel_path,
+                date,
+                is_folder: false,
+                children: None,
+                tasks_total: counts.total,
+                tasks_done: counts.done,
+            });
+        }
+    }
+
+    sort_feature_nodes(&mut nodes);
+
+    Ok(nodes)
+}
+
+fn list_prompt_dir(project_path: &str, dir: PromptDir) -> Result<Vec<FeatureFileNode>, String> {
+    let base_dir = dir.base_dir(project_path);
+
+    if !base_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    if !base_dir.is_dir() {
+        return Err(format!("{} path is not a directory", dir.dir_name()));
+    }
+
+    scan_prompt_directory(&base_dir, &base_dir, dir.extensions())
+}
+
+fn read_prompt_file(project_path: &str, dir: PromptDir, file_path: &str) -> Result<VersionedContent, String> {
+    let full_path = file_io::resolve_existing(&dir.base_dir(project_path), file_path, dir.dir_name())?;
+    let versioned = file_io::read_versioned(&full_path)?;
+
+    // Remember what the editor read so it can be used as a merge base on conflict
+    if let Ok(project_root) = PathBuf::from(project_path).canonicalize() {
+        file_history::record_read(&project_root, &full_path, versioned.content.as_bytes(), "read_prompt_file");
+    }
+
+    Ok(versioned)
+}
+
+// Resolve a write target inside a prompt directory; the parent must already exist
+fn resolve_write_target(project_path: &str, dir: PromptDir, file_path: &str) -> Result<PathBuf, String> {
+    let base_dir = dir.base_dir(project_path);
+    let full_path = base_dir.join(file_path);
+
+    if !dir.extensions().iter().any(|ext| file_path.ends_with(ext)) {
+        return Err(format!("Unsupported file type for {} directory: {}", dir.dir_name(), file_path));
+    }
+
+    let parent = full_path.parent()
+        .ok_or_else(|| "Invalid file path: no parent directory".to_string())?;
+
+    if !parent.exists() {
+        return Err("Parent directory does not exist".to_string());
+    }
+
+    let canonical_parent = parent.canonicalize()
+        .map_err(|e| format!("Invalid parent directory: {}", e))?;
+    let canonical_base_dir = base_dir.canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+
+    if !canonical_parent.starts_with(&canonical_base_dir) {
+        return Err(format!("Access denied: path outside of {} directory", dir.dir_name()));
+    }
+
+    let file_name = full_path.file_name()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+
+    Ok(canonical_parent.join(file_name))
+}
+
+fn write_prompt_file(
+    project_path: &str,
+    dir: PromptDir,
+    file_path: &str,
+    content: &str,
+    expected_token: Option<&str>,
+    source: &str,
+) -> Result<String, FileWriteError> {
+    let target_path = resolve_write_target(project_path, dir, file_path)?;
+    let project_root = PathBuf::from(project_path).canonicalize()
+        .map_err(|e| format!("Invalid base directory: {}", e))?;
+
+    file_io::write_checked(&project_root, &target_path, file_path, content, expected_token, source)
+}
+
+/// Check one system prompt against the size limits.
+/// `base_bytes` is the size of base.system.md (None if it does not exist).
+pub fn check_prompt_size(file: &str, bytes: usize, base_bytes: Option<usize>) -> PromptSizeCheck {
+    let is_base = file == BASE_SYSTEM_PROMPT;
+    let is_mode_prompt = file.ends_with(SYSTEM_PROMPT_SUFFIX) && !is_base;
+    let combined_bytes = if is_mode_prompt { bytes + base_bytes.unwrap_or(0) } else { bytes };
+    let (max_bytes, max_combined_bytes) = (MAX_SYSTEM_PROMPT_BYTES, MAX_COMBINED_SYSTEM_PROMPT_BYTES);
+
+    let (status, message) = if bytes > max_bytes {
+        ("error", Some(format!("{} is {} bytes, over the {} byte limit", file, bytes, max_bytes)))
+    } else if combined_bytes > max_combined_bytes {
+        ("error", Some(format!("{} together with {} is {} bytes, over the {} byte limit",
+            file, BASE_SYSTEM_PROMPT, combined_bytes, max_combined_bytes)))
+    } else if bytes * 100 > max_bytes * SIZE_WARNING_PERCENT
+        || combined_bytes * 100 > max_combined_bytes * SIZE_WARNING_PERCENT {
+        ("warning", Some(format!("{} is close to the system prompt size limit", file)))
+    } else {
+        ("ok", None)
+    };
+
+    PromptSizeCheck {
+        file: file.to_string(),
+        bytes,
+        combined_bytes,
+        max_bytes,
+        max_combined_bytes,
+        status: status.to_string(),
+        message,
+    }
+}
+
+fn collect_prompt_files(nodes: &[FeatureFileNode], files: &mut Vec<String>) {
+    for node in nodes {
+        match &node.children {
+            Some(children) => collect_prompt_files(children, files),
+            None => files.push(node.path.clone()),
+        }
+    }
+}
+
+fn validate_prompt_sizes(project_path: &str) -> Result<Vec<PromptSizeCheck>, String> {
+    let base_dir = PromptDir::System.base_dir(project_path);
+    let mut files = Vec::new();
+    collect_prompt_files(&list_prompt_dir(project_path, PromptDir::System)?, &mut files);
+
+    let base_bytes = fs::metadata(base_dir.join(BASE_SYSTEM_PROMPT)).ok().map(|m| m.len() as usize);
+
+    Ok(files.iter()
+        .filter_map(|file| {
+            let bytes = fs::metadata(base_dir.join(file)).ok()?.len() as usize;
+            Some(check_prompt_size(file, bytes, base_bytes))
+        })
+        .collect())
+}
+
+// Tauri command: List plan documents from .prompts/plan/
+#[tauri::command]
+pub async fn list_plan_files(project_path: String) -> Result<Vec<FeatureFileNode>, String> {
+    list_prompt_dir(&project_path, PromptDir::Plan)
+}
+
+// Tauri command: Read a plan document with a token for conflict-aware writes
+#[tauri::command]
+pub async fn read_plan_file(project_path: String, file_path: String) -> Result<VersionedContent, String> {
+    read_prompt_file(&project_path, PromptDir::Plan, &file_path)
+}
+
+// Tauri command: Write a plan document
+// If expected_token is given, the write fails with a conflict when the file changed since it was read.
+#[tauri::command]
+pub async fn write_plan_file(
+    project_path: String,
+    file_path: String,
+    content: String,
+    expected_token: Option<String>,
+) -> Result<String, FileWriteError> {
+    let token = write_prompt_file(&project_path, PromptDir::Plan, &file_path, &content, expected_token.as_deref(), "write_plan_file")?;
+    log::info!("Wrote plan file: {}", file_path);
+    Ok(token)
+}
+
+// Tauri command: List system prompts from .prompts/system/
+#[tauri::command]
+pub async fn list_system_prompt_files(project_path: String) -> Result<Vec<FeatureFileNode>, String> {
+    list_prompt_dir(&project_path, PromptDir::System)
+}
+
+// Tauri command: Read a system prompt with a token for conflict-aware writes
+#[tauri::command]
+pub async fn read_system_prompt_file(project_path: String, file_path: String) -> Result<VersionedContent, String> {
+    read_prompt_file(&project_path, PromptDir::System, &file_path)
+}
+
+// Tauri command: Write a system prompt, rejecting content over the size limits
+#[tauri::command]
+pub async fn write_system_prompt_file(
+    project_path: String,
+    file_path: String,
+    content: String,
+    expected_token: Option<String>,
+) -> Result<String, FileWriteError> {
+    let file_path = file_path.replace('\\', "/");
+    let base_dir = PromptDir::System.base_dir(&project_path);
+
+    // Validate against the content being written, not what is on disk
+    let check = if file_path == BASE_SYSTEM_PROMPT {
+        // A larger base prompt must still fit with every mode prompt
+        let mut files = Vec::new();
+        collect_prompt_files(&list_prompt_dir(&project_path, PromptDir::System)?, &mut files);
+        files.iter()
+            .filter(|f| f.as_str() != BASE_SYSTEM_PROMPT)
+            .filter_map(|f| fs::metadata(base_dir.join(f)).ok().map(|m| check_prompt_size(f, m.len() as usize, Some(content.len()))))
+            .find(|c| c.status == "error")
+            .unwrap_or_else(|| check_prompt_size(&file_path, content.len(), None))
+    } else {
+        let base_bytes = fs::metadata(base_dir.join(BASE_SYSTEM_PROMPT)).ok().map(|m| m.len() as usize);
+        check_prompt_size(&file_path, content.len(), base_bytes)
+    };
+
+    if check.status == "error" {
+        let message = check.message.unwrap_or_else(|| "System prompt is too large".to_string());
+        log::warn!("Rejected system prompt write: {}", message);
+        return Err(message.into());
+    }
+
+    let token = write_prompt_file(&project_path, PromptDir::System, &file_path, &content, expected_token.as_deref(), "write_system_prompt_file")?;
+    log::info!("Wrote system prompt: {}", file_path);
+    Ok(token)
+}
+
+// Tauri command: Check every system prompt against the size limits
+#[tauri::command]
+pub async fn validate_system_prompts(project_path: String) -> Result<Vec<PromptSizeCheck>, String> {
+    validate_prompt_sizes(&project_path)
+}
+
+// Tauri command: Watch .prompts/plan/ and .prompts/system/ for changes
+// Emits "plan-files-changed" and "system-prompts-changed" with the debounced changes
+#[tauri::command]
+pub async fn watch_prompt_files(window: tauri::Window, project_path: String, debounce_ms: Option<u64>) -> Result<(), String> {
+    if ![PromptDir::Plan, PromptDir::System].iter().any(|dir| dir.base_dir(&project_path).is_dir()) {
+        log::warn!("No plan or system prompt directory in: {}", project_path);
+        return Ok(());
+    }
+
+    let window_clone = window.clone();
+    let project_root = PathBuf::from(&project_path);
+
+    let handler: WatchHandler = Box::new(move |changes| {
+        // Paths in the payloads are relative to the prompt directory
+        let changes_in = |dir: PromptDir| -> Vec<FileChange> {
+            let prefix = format!(".prompts/{}", dir.dir_name());
+            changes.iter().filter_map(|c| c.relative_to(&prefix)).collect()
+        };
+
+        let plan_changes = changes_in(PromptDir::Plan);
+        if !plan_changes.is_empty() {
+            if let Err(e) = window_clone.emit("plan-files-changed", FileChangesPayload { changes: plan_changes }) {
+                log::error!("Failed to emit event: {}", e);
+            }
+        }
+
+        let system_changes = changes_in(PromptDir::System);
+        if !system_changes.is_empty() {
+            let size_checks = validate_prompt_sizes(&project_root.to_string_lossy()).unwrap_or_default();
+            let payload = SystemPromptsChangedPayload { changes: system_changes, size_checks };
+            if let Err(e) = window_clone.emit("system-prompts-changed", payload) {
+                log::error!("Failed to emit event: {}", e);
+            }
+        }
+    });
+
+    watchers::subscribe(window.state::<Mutex<WatcherRegistry>>().inner(), "prompts", &project_path, &[".prompts/plan", ".prompts/system"], debounce_ms, handler)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_limits_match_sidecar() {
+        let limits: serde_json::Value = serde_json::from_str(include_str!("../../../copilot-sidecar/prompt-limits.json")).unwrap();
+        assert_eq!(limits["maxSystemPromptBytes"], MAX_SYSTEM_PROMPT_BYTES);
+        assert_eq!(limits["maxCombinedSystemPromptBytes"], MAX_COMBINED_SYSTEM_PROMPT_BYTES);
+    }
+
+    #[test]
+    fn test_check_prompt_size() {
+        let (max, max_combined) = (MAX_SYSTEM_PROMPT_BYTES, MAX_COMBINED_SYSTEM_PROMPT_BYTES);
+        assert!(max < max_combined);
+
+        assert_eq!(check_prompt_size("planning.system.md", max / 4, Some(max / 4)).status, "ok");
+        assert_eq!(check_prompt_size("planning.system.md", max + 1, None).status, "error");
+
+        // Fits on its own but not together with the base prompt
+        let check = check_prompt_size("building.system.md", max, Some(max_combined - max + 1));
+        assert_eq!(check.status, "error");
+        assert_eq!(check.combined_bytes, max_combined + 1);
+
+        // The base prompt is only measured on its own
+        let check = check_prompt_size("base.system.md", max * 9 / 10, Some(max * 9 / 10));
+        assert_eq!(check.status, "warning");
+        assert_eq!(check.combined_bytes, max * 9 / 10);
+    }
+
+    #[test]
+    fn test_scan_prompt_directory() {
+        let dir = TempDir::new("prompt-docs");
+        fs::create_dir_all(dir.join("archive")).unwrap();
+        fs::write(dir.join("intent.md"), "- [x] a\n- [ ] b\n").unwrap();
+        fs::write(dir.join("tasks.json"), "{}").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        fs::write(dir.join("archive").join("2026-01-05-old-plan.md"), "").unwrap();
+
+        let nodes = scan_prompt_directory(&dir, &dir, PromptDir::Plan.extensions()).unwrap();
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["archive", "intent", "tasks.json"]);
+        assert_eq!(nodes[1].tasks_total, 2);
+
+        let archived = &nodes[0].children.as_ref().unwrap()[0];
+        assert_eq!(archived.path, "archive/2026-01-05-old-plan.md");
+        assert_eq!(archived.date.as_deref(), Some("2026-01-05"));
+    }
+}
@@ -155,6 +155,8 @@ mod tests {
                 last_accessed: "2026-01-31T17:30:00.000Z".to_string(),
             }),
             recent_projects: Vec::new(),
+            project_link_domains: Vec::new(),
+            selected_model: None,
         };
         
         let json = serde_json::to_string(&settings).unwrap();
@@ -0,0 +1,469 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::sidecar_client::{self, SidecarConnection};
+
+// Supervisor timing
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+const HEALTH_INTERVAL: Duration = Duration::from_secs(5);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(3);
+// Node plus Copilot SDK start-up can take a while on a cold machine
+const STARTUP_GRACE: Duration = Duration::from_secs(30);
+// Failed checks in a row before a running sidecar is considered hung
+const MAX_HEALTH_FAILURES: u32 = 3;
+// Healthy uptime after which earlier crashes no longer count towards the backoff
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// Restarts in a row before giving up until the user restarts it
+const MAX_RESTART_ATTEMPTS: u32 = 6;
+const MAX_LOG_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SidecarPhase {
+    Starting,
+    Running,
+    Unhealthy,  // Process is up but failing health checks
+    Restarting, // Waiting out the backoff before the next start
+    Failed,     // Not found, or gave up after repeated failures
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SidecarStatus {
+    pub phase: SidecarPhase,
+    pub url: Option<String>, // Each start gets a new free port
+    pub pid: Option<u32>,
+    pub copilot_ready: bool,
+    pub restarts: u32,              // Automatic restarts since launch
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub started_at: Option<String>, // RFC 3339
+    pub last_healthy_at: Option<String>,
+    pub next_restart_in_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SidecarLogLine {
+    pub timestamp: String,
+    pub stream: &'static str, // stdout, stderr or supervisor
+    pub line: String,
+}
+
+/// How to start the sidecar process
+#[derive(Debug, Clone)]
+pub struct SidecarLaunch {
+    pub script: PathBuf,
+    pub log_file: PathBuf,
+}
+
+// Global state to track the sidecar process
+pub struct SidecarState {
+    launch: Option<SidecarLaunch>,
+    token: Option<String>, // Per launch of the app, shared by restarts
+    process: Option<Child>,
+    status: SidecarStatus,
+    logs: VecDeque<SidecarLogLine>,
+    started: Option<Instant>,
+    healthy_since: Option<Instant>,
+    last_health_check: Option<Instant>,
+    health_failures: u32,
+    restart_at: Option<Instant>,
+    shutting_down: bool,
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339()
+}
+
+/// Delay before restart attempt `attempt` (1-based): 1s, 2s, 4s, ... up to a minute
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    BASE_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+impl SidecarState {
+    pub fn new(launch: Option<SidecarLaunch>) -> Self {
+        let token = sidecar_client::generate_token()
+            .map_err(|e| log::error!("{}", e))
+            .ok();
+        Self {
+            launch,
+            token,
+            process: None,
+            status: SidecarStatus {
+                phase: SidecarPhase::Stopped,
+                url: None,
+                pid: None,
+                copilot_ready: false,
+                restarts: 0,
+                consecutive_failures: 0,
+                last_error: None,
+                started_at: None,
+                last_healthy_at: None,
+                next_restart_in_ms: None,
+            },
+            logs: VecDeque::new(),
+            started: None,
+            healthy_since: None,
+            last_health_check: None,
+            health_failures: 0,
+            restart_at: None,
+            shutting_down: false,
+        }
+    }
+
+    fn push_log(&mut self, stream: &'static str, line: String) {
+        if self.logs.len() == MAX_LOG_LINES {
+            self.logs.pop_front();
+        }
+        self.logs.push_back(SidecarLogLine { timestamp: now(), stream, line });
+    }
+
+    fn note(&mut self, message: String) {
+        log::info!("[Sidecar] {}", message);
+        self.push_log("supervisor", message);
+    }
+
+    /// Record a failure and either schedule a restart or give up
+    fn fail(&mut self, error: String) {
+        log::warn!("[Sidecar] {}", error);
+        self.push_log("supervisor", error.clone());
+        self.status.last_error = Some(error);
+        self.clear_connection();
+        self.status.copilot_ready = false;
+        self.status.consecutive_failures += 1;
+        self.healthy_since = None;
+        self.health_failures = 0;
+
+        if self.status.consecutive_failures > MAX_RESTART_ATTEMPTS {
+            self.status.phase = SidecarPhase::Failed;
+            self.status.next_restart_in_ms = None;
+            self.restart_at = None;
+            self.note(format!("Giving up after {} failed starts; use restart to try again", MAX_RESTART_ATTEMPTS));
+            return;
+        }
+        let delay = backoff_delay(self.status.consecutive_failures);
+        self.status.phase = SidecarPhase::Restarting;
+        self.status.next_restart_in_ms = Some(delay.as_millis() as u64);
+        self.restart_at = Some(Instant::now() + delay);
+    }
+
+    /// Apply the result of a health check
+    fn record_health(&mut self, result: Result<bool, String>) {
+        match result {
+            Ok(copilot_ready) => {
+                self.health_failures = 0;
+                self.status.phase = SidecarPhase::Running;
+                self.status.copilot_ready = copilot_ready;
+                self.status.last_healthy_at = Some(now());
+                let healthy_since = *self.healthy_since.get_or_insert_with(Instant::now);
+                if healthy_since.elapsed() >= STABLE_UPTIME {
+                    self.status.consecutive_failures = 0;
+                }
+            }
+            Err(e) => {
+                let in_grace = self.healthy_since.is_none()
+                    && self.started.map(|s| s.elapsed() < STARTUP_GRACE).unwrap_or(false);
+                if in_grace {
+                    return;
+                }
+                self.health_failures += 1;
+                self.healthy_since = None;
+                self.status.phase = SidecarPhase::Unhealthy;
+                self.status.last_error = Some(e);
+            }
+        }
+    }
+
+    fn clear_connection(&mut self) {
+        sidecar_client::set_connection(None);
+        self.status.url = None;
+        self.status.pid = None;
+    }
+
+    fn kill_process(&mut self) {
+        if let Some(mut process) = self.process.take() {
+            let pid = process.id();
+            // Kill the entire process tree to ensure all child processes are terminated
+            if let Err(e) = crate::kill_process_tree(pid) {
+                log::warn!("Failed to kill sidecar process tree: {}", e);
+            }
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+        self.clear_connection();
+    }
+
+    /// Stop the sidecar for good (app exit)
+    pub fn shutdown(&mut self) {
+        self.shutting_down = true;
+        self.restart_at = None;
+        self.kill_process();
+        self.status.phase = SidecarPhase::Stopped;
+    }
+}
+
+/// Forward one output stream of the sidecar into the log buffer
+fn capture_output(app: &tauri::AppHandle, stream: &'static str, output: impl Read + Send + 'static) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let reader = BufReader::new(output);
+        // Keep reading until the pipe closes, otherwise a full pipe blocks the sidecar
+        for line in reader.lines().map_while(Result::ok) {
+            let state = app.state::<Mutex<SidecarState>>();
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            state.push_log(stream, line);
+        }
+    });
+}
+
+/// Start the sidecar process; failures are recorded in the state
+fn spawn_process(app: &tauri::AppHandle, state: &mut SidecarState) {
+    let Some(launch) = state.launch.clone() else {
+        state.status.phase = SidecarPhase::Failed;
+        state.status.last_error = Some("Sidecar not found. Build it with: cd src/copilot-sidecar && npm run build".to_string());
+        return;
+    };
+
+    state.restart_at = None;
+    state.status.next_restart_in_ms = None;
+
+    let Some(token) = state.token.clone() else {
+        state.status.phase = SidecarPhase::Failed;
+        state.status.last_error = Some("Failed to generate sidecar token".to_string());
+        return;
+    };
+    let port = match sidecar_client::free_port() {
+        Ok(port) => port,
+        Err(e) => {
+            state.fail(e);
+            return;
+        }
+    };
+    state.note(format!("Starting copilot sidecar from {:?} on port {}", launch.script, port));
+
+    match Command::new("node")
+        .arg(&launch.script)
+        // Pass log file path, port and token to sidecar via environment variables
+        .env("NAIDE_LOG_FILE", launch.log_file.to_string_lossy().to_string())
+        .env(sidecar_client::PORT_ENV, port.to_string())
+        .env(sidecar_client::TOKEN_ENV, &token)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn() {
+            Ok(mut child) => {
+                if let Some(stdout) = child.stdout.take() {
+                    capture_output(app, "stdout", stdout);
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    capture_output(app, "stderr", stderr);
+                }
+                state.note(format!("Copilot sidecar started with PID {}", child.id()));
+                let connection = SidecarConnection::new(port, &token);
+                state.status.url = Some(connection.url.clone());
+                sidecar_client::set_connection(Some(connection));
+                state.status.phase = SidecarPhase::Starting;
+                state.status.pid = Some(child.id());
+                state.status.started_at = Some(now());
+                state.started = Some(Instant::now());
+                state.healthy_since = None;
+                state.last_health_check = None;
+                state.health_failures = 0;
+                state.process = Some(child);
+            }
+            Err(e) => {
+                state.fail(format!("Failed to start copilot sidecar: {}. Make sure Node.js is installed and in PATH", e));
+            }
+        }
+}
+
+fn emit_status(app: &tauri::AppHandle, status: &SidecarStatus) {
+    if let Err(e) = app.emit("sidecar-status", status.clone()) {
+        log::error!("Failed to emit sidecar-status event: {}", e);
+    }
+}
+
+/// One supervisor step. Returns false once the app is shutting down.
+fn tick(app: &tauri::AppHandle) -> bool {
+    let state = app.state::<Mutex<SidecarState>>();
+    let mut guard = state.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.shutting_down {
+        return false;
+    }
+    let before = guard.status.clone();
+
+    if let Some(at) = guard.restart_at {
+        if Instant::now() >= at {
+            guard.status.restarts += 1;
+            spawn_process(app, &mut guard);
+        } else {
+            guard.status.next_restart_in_ms = Some(at.saturating_duration_since(Instant::now()).as_millis() as u64);
+        }
+    } else if let Some(process) = guard.process.as_mut() {
+        match process.try_wait() {
+            Ok(Some(exit)) => {
+                guard.process = None;
+                guard.fail(format!("Copilot sidecar exited unexpectedly ({})", exit));
+            }
+            Ok(None) => {
+                let due = guard.last_health_check.map(|t| t.elapsed() >= HEALTH_INTERVAL).unwrap_or(true);
+                if due {
+                    guard.last_health_check = Some(Instant::now());
+                    // Don't hold the lock over the request; output capture needs it
+                    drop(guard);
+                    let result = sidecar_client::get_json("/health", HEALTH_TIMEOUT)
+                        .map(|body| body.get("copilotReady").and_then(|v| v.as_bool()).unwrap_or(false));
+                    guard = state.lock().unwrap_or_else(|e| e.into_inner());
+                    if guard.shutting_down || guard.process.is_none() {
+                        return !guard.shutting_down;
+                    }
+                    guard.record_health(result);
+                    if guard.health_failures >= MAX_HEALTH_FAILURES {
+                        guard.kill_process();
+                        guard.fail(format!("Copilot sidecar failed {} health checks in a row", MAX_HEALTH_FAILURES));
+                    }
+                }
+            }
+            Err(e) => log::warn!("Failed to check sidecar process: {}", e),
+        }
+    }
+
+    let after = guard.status.clone();
+    drop(guard);
+    // The countdown alone is not worth an event
+    let changed = (SidecarStatus { next_restart_in_ms: before.next_restart_in_ms, ..after.clone() }) != before;
+    if changed {
+        emit_status(app, &after);
+    }
+    true
+}
+
+/// Start the sidecar and the thread that watches over it
+pub fn start_supervisor(app: &tauri::AppHandle, launch: Option<SidecarLaunch>) {
+    // Managed before the first start, since the output capture threads look it up
+    app.manage(Mutex::new(SidecarState::new(launch)));
+    {
+        let state = app.state::<Mutex<SidecarState>>();
+        let mut guard = state.lock().unwrap_or_else(|e| e.into_inner());
+        spawn_process(app, &mut guard);
+    }
+
+    let app = app.clone();
+    thread::spawn(move || {
+        while tick(&app) {
+            thread::sleep(TICK_INTERVAL);
+        }
+        log::info!("Sidecar supervisor stopped");
+    });
+}
+
+// Tauri command: Current state of the copilot sidecar
+#[tauri::command]
+pub async fn get_sidecar_status(app: tauri::AppHandle) -> Result<SidecarStatus, String> {
+    let state = app.state::<Mutex<SidecarState>>();
+    let status = state.lock().unwrap_or_else(|e| e.into_inner()).status.clone();
+    Ok(status)
+}
+
+// Tauri command: Restart the copilot sidecar now, resetting the failure backoff
+#[tauri::command]
+pub async fn restart_sidecar(app: tauri::AppHandle) -> Result<SidecarStatus, String> {
+    let state = app.state::<Mutex<SidecarState>>();
+    let mut guard = state.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.launch.is_none() {
+        return Err("Sidecar not found. Build it with: cd src/copilot-sidecar && npm run build".to_string());
+    }
+
+    guard.note("Restart requested".to_string());
+    guard.kill_process();
+    guard.status.consecutive_failures = 0;
+    guard.status.last_error = None;
+    spawn_process(&app, &mut guard);
+
+    let status = guard.status.clone();
+    drop(guard);
+    emit_status(&app, &status);
+    Ok(status)
+}
+
+// Tauri command: Sidecar address and per-launch token for the frontend
+// Every HTTP request needs "Authorization: Bearer <token>"; WebSockets pass ?token=<token>
+#[tauri::command]
+pub async fn get_sidecar_connection() -> Result<SidecarConnection, String> {
+    sidecar_client::connection()
+}
+
+// Tauri command: Recent sidecar output (stdout, stderr and supervisor messages), oldest first
+#[tauri::command]
+pub async fn get_sidecar_logs(app: tauri::AppHandle, limit: Option<usize>) -> Result<Vec<SidecarLogLine>, String> {
+    let state = app.state::<Mutex<SidecarState>>();
+    let guard = state.lock().unwrap_or_else(|e| e.into_inner());
+    let limit = limit.unwrap_or(MAX_LOG_LINES).min(MAX_LOG_LINES);
+    let skip = guard.logs.len().saturating_sub(limit);
+    Ok(guard.logs.iter().skip(skip).cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(1));
+        assert_eq!(backoff_delay(3), Duration::from_secs(4));
+        assert_eq!(backoff_delay(7), MAX_BACKOFF);
+        assert_eq!(backoff_delay(40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_connection_credentials() {
+        let token = sidecar_client::generate_token().unwrap();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, sidecar_client::generate_token().unwrap());
+
+        let port = sidecar_client::free_port().unwrap();
+        assert!(port > 0);
+        let connection = SidecarConnection::new(port, &token);
+        assert_eq!(connection.url, format!("http://127.0.0.1:{}", port));
+        assert_eq!(connection.ws_url, format!("ws://127.0.0.1:{}", port));
+    }
+
+    #[test]
+    fn test_failures_back_off_then_give_up() {
+        let mut state = SidecarState::new(None);
+        state.started = Some(Instant::now() - STARTUP_GRACE);
+
+        // Failed checks after the start-up grace mark the sidecar unhealthy
+        state.record_health(Err("connection refused".to_string()));
+        assert_eq!(state.status.phase, SidecarPhase::Unhealthy);
+        assert_eq!(state.health_failures, 1);
+        state.record_health(Ok(true));
+        assert_eq!(state.status.phase, SidecarPhase::Running);
+        assert!(state.status.copilot_ready);
+        assert_eq!(state.health_failures, 0);
+
+        state.fail("exited".to_string());
+        assert_eq!(state.status.phase, SidecarPhase::Restarting);
+        assert_eq!(state.status.next_restart_in_ms, Some(1000));
+        for _ in 1..MAX_RESTART_ATTEMPTS {
+            state.fail("exited".to_string());
+        }
+        assert_eq!(state.status.next_restart_in_ms, Some(32000));
+        state.fail("exited".to_string());
+        assert_eq!(state.status.phase, SidecarPhase::Failed);
+        assert!(state.restart_at.is_none());
+        assert!(state.logs.iter().any(|l| l.stream == "supervisor" && l.line.starts_with("Giving up")));
+    }
+}
@@ -0,0 +1,100 @@
+use std::net::TcpListener;
+use std::sync::RwLock;
+use std::time::Duration;
+use serde::Serialize;
+use serde_json::Value;
+
+// Environment variables that tell the sidecar where to listen and which token to accept
+pub const PORT_ENV: &str = "NAIDE_SIDECAR_PORT";
+pub const TOKEN_ENV: &str = "NAIDE_SIDECAR_TOKEN";
+
+/// Address and per-launch token of the running sidecar
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SidecarConnection {
+    pub url: String,
+    pub ws_url: String,
+    pub port: u16,
+    pub token: String,
+}
+
+impl SidecarConnection {
+    pub fn new(port: u16, token: &str) -> Self {
+        // 127.0.0.1 rather than localhost: the sidecar only binds IPv4 loopback
+        Self {
+            url: format!("http://127.0.0.1:{}", port),
+            ws_url: format!("ws://127.0.0.1:{}", port),
+            port,
+            token: token.to_string(),
+        }
+    }
+}
+
+// Set by the supervisor each time it starts the sidecar
+static CONNECTION: RwLock<Option<SidecarConnection>> = RwLock::new(None);
+
+pub fn set_connection(connection: Option<SidecarConnection>) {
+    *CONNECTION.write().unwrap_or_else(|e| e.into_inner()) = connection;
+}
+
+pub fn connection() -> Result<SidecarConnection, String> {
+    CONNECTION.read().unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or_else(|| "Copilot sidecar is not running".to_string())
+}
+
+/// Random hex token, 256 bits from the OS generator
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| format!("Failed to generate sidecar token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// A loopback port that is free right now
+pub fn free_port() -> Result<u16, String> {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|address| address.port())
+        .map_err(|e| format!("Failed to find a free port for the sidecar: {}", e))
+}
+
+fn authorization(connection: &SidecarConnection) -> String {
+    format!("Bearer {}", connection.token)
+}
+
+/// Turn a failed request into a readable message, using the sidecar's `error` field when present
+fn request_error(path: &str, error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(code, response) => {
+            let detail = response.into_json::<Value>().ok()
+                .and_then(|body| body.get("error").and_then(|e| e.as_str()).map(str::to_string))
+                .unwrap_or_else(|| "no details".to_string());
+            format!("Sidecar request {} failed with status {}: {}", path, code, detail)
+        }
+        ureq::Error::Transport(e) => format!("Failed to reach the sidecar ({}): {}", path, e),
+    }
+}
+
+/// GET a JSON document from the sidecar
+pub fn get_json(path: &str, timeout: Duration) -> Result<Value, String> {
+    let connection = connection()?;
+    ureq::get(&format!("{}{}", connection.url, path))
+        .timeout(timeout)
+        .set("Authorization", &authorization(&connection))
+        .call()
+        .map_err(|e| request_error(path, e))?
+        .into_json()
+        .map_err(|e| format!("Failed to parse sidecar response ({}): {}", path, e))
+}
+
+/// POST a JSON body to the sidecar and parse the JSON reply
+pub fn post_json(path: &str, body: &Value, timeout: Duration) -> Result<Value, String> {
+    let connection = connection()?;
+    ureq::post(&format!("{}{}", connection.url, path))
+        .timeout(timeout)
+        .set("Authorization", &authorization(&connection))
+        .send_json(body)
+        .map_err(|e| request_error(path, e))?
+        .into_json()
+        .map_err(|e| format!("Failed to parse sidecar response ({}): {}", path, e))
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::markdown_outline::parse_outline;
+
+// Project-level lint configuration, committed alongside the specs
+const LINT_CONFIG_FILE: &str = "spec-lint.json";
+
+pub const RULE_FILENAME_DATE: &str = "filename-date";
+pub const RULE_REQUIRED_SECTIONS: &str = "required-sections";
+pub const RULE_DUPLICATE_TITLE: &str = "duplicate-title";
+pub const RULE_BROKEN_LINK: &str = "broken-link";
+pub const RULE_EMPTY_ACCEPTANCE_CRITERIA: &str = "empty-acceptance-criteria";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+    Off,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintConfig {
+    // Severity overrides per rule id; rules not listed use their default severity
+    #[serde(default)]
+    pub rules: HashMap<String, Severity>,
+    #[serde(default = "default_required_sections")]
+    pub required_sections: Vec<String>,
+    #[serde(default = "default_acceptance_headings")]
+    pub acceptance_headings: Vec<String>,
+    // Files never linted (matched by file name)
+    #[serde(default = "default_exempt_files")]
+    pub exempt_files: Vec<String>,
+    // Folders whose specs use a different structure and skip the required-sections rule
+    #[serde(default = "default_section_exempt_folders")]
+    pub section_exempt_folders: Vec<String>,
+}
+
+fn default_required_sections() -> Vec<String> {
+    vec!["Summary".to_string(), "Acceptance Criteria".to_string()]
+}
+
+fn default_acceptance_headings() -> Vec<String> {
+    vec!["Acceptance Criteria".to_string()]
+}
+
+fn default_exempt_files() -> Vec<String> {
+    vec!["FEATURES_INDEX.md".to_string()]
+}
+
+fn default_section_exempt_folders() -> Vec<String> {
+    vec!["bugs".to_string(), "removed-features".to_string()]
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            rules: HashMap::new(),
+            required_sections: default_required_sections(),
+            acceptance_headings: default_acceptance_headings(),
+            exempt_files: default_exempt_files(),
+            section_exempt_folders: default_section_exempt_folders(),
+        }
+    }
+}
+
+impl LintConfig {
+    fn severity(&self, rule: &str) -> Severity {
+        if let Some(severity) = self.rules.get(rule) {
+            return *severity;
+        }
+        match rule {
+            RULE_FILENAME_DATE | RULE_BROKEN_LINK => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LintDiagnostic {
+    pub file: String, // Relative path from .prompts/features/
+    pub line: usize,  // 1-based
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+// A spec loaded for linting
+pub struct SpecFile {
+    pub path: String, // Relative path from .prompts/features/, forward slashes
+    pub content: String,
+}
+
+/// Load lint configuration from .prompts/spec-lint.json, falling back to defaults
+pub fn load_config(project_path: &Path) -> LintConfig {
+    let config_path = project_path.join(".prompts").join(LINT_CONFIG_FILE);
+    if !config_path.exists() {
+        return LintConfig::default();
+    }
+
+    match fs::read_to_string(&config_path).map(|c| serde_json::from_str::<LintConfig>(&c)) {
+        Ok(Ok(config)) => config,
+        Ok(Err(e)) => {
+            log::warn!("Invalid spec lint config {:?}, using defaults: {}", config_path, e);
+            LintConfig::default()
+        }
+        Err(e) => {
+            log::warn!("Failed to read spec lint config {:?}, using defaults: {}", config_path, e);
+            LintConfig::default()
+        }
+    }
+}
+
+fn push(diagnostics: &mut Vec<LintDiagnostic>, config: &LintConfig, file: &str, line: usize, rule: &str, message: String) {
+    let severity = config.severity(rule);
+    if severity == Severity::Off {
+        return;
+    }
+    diagnostics.push(LintDiagnostic {
+        file: file.to_string(),
+        line,
+        rule: rule.to_string(),
+        severity,
+        message,
+    });
+}
+
+fn normalize_heading(text: &str) -> String {
+    text.trim().trim_end_matches(':').to_lowercase()
+}
+
+/// Title of a spec: its first level-1 heading, without a "Feature:" / "Bug:" prefix
+fn spec_title(content: &str) -> Option<(String, usize)> {
+    let outline = parse_outline(content);
+    let heading = outline.headings.iter().find(|h| h.level == 1)?;
+    let text = heading.text.trim();
+    let text = ["feature:", "bug:"].iter()
+        .find(|prefix| text.to_lowercase().starts_with(*prefix))
+        .map(|prefix| text[prefix.len()..].trim())
+        .unwrap_or(text);
+    Some((text.to_lowercase(), heading.range.start_line))
+}
+
+fn is_external_link(target: &str) -> bool {
+    let lower = target.to_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("mailto:")
+        || lower.starts_with('#') || lower.contains("://")
+}
+
+/// Lint one spec. `features_dir` is used to resolve relative links.
+pub fn lint_spec(spec: &SpecFile, features_dir: &Path, config: &LintConfig) -> Vec<LintDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let file_name = spec.path.rsplit('/').next().unwrap_or(&spec.path);
+    let folder = spec.path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    let outline = parse_outline(&spec.content);
+
+    // Filename must start with a real YYYY-MM-DD- date
+    if crate::parse_date_from_filename(file_name).is_none() {
+        push(&mut diagnostics, config, &spec.path, 1, RULE_FILENAME_DATE,
+            format!("Filename '{}' should start with a valid YYYY-MM-DD- date", file_name));
+    }
+
+    // Required sections
+    let top_folder = folder.split('/').next().unwrap_or("");
+    if !config.section_exempt_folders.iter().any(|f| f == top_folder) {
+        for section in &config.required_sections {
+            let wanted = normalize_heading(section);
+            if !outline.headings.iter().any(|h| normalize_heading(&h.text) == wanted) {
+                push(&mut diagnostics, config, &spec.path, 1, RULE_REQUIRED_SECTIONS,
+                    format!("Missing required section '{}'", section));
+            }
+        }
+    }
+
+    // Acceptance criteria sections need at least one checklist item or some text
+    for heading in &outline.headings {
+        let name = normalize_heading(&heading.text);
+        if !config.acceptance_headings.iter().any(|h| normalize_heading(h) == name) {
+            continue;
+        }
+        let has_tasks = outline.tasks.iter().any(|t| {
+            t.range.start_line > heading.range.end_line && t.range.end_line <= heading.section_range.end_line
+        });
+        let body_start = heading.range.end_byte;
+        let body_end = heading.section_range.end_byte.max(body_start);
+        let body = &spec.content[body_start..body_end];
+        let has_text = body.lines().any(|l| {
+            let l = l.trim();
+            !l.is_empty() && !l.starts_with('#') && l != "- [ ]" && l != "-"
+        });
+        if !has_tasks && !has_text {
+            push(&mut diagnostics, config, &spec.path, heading.range.start_line, RULE_EMPTY_ACCEPTANCE_CRITERIA,
+                format!("Section '{}' has no acceptance criteria", heading.text));
+        }
+    }
+
+    // Relative links must point at existing files
+    let spec_dir = features_dir.join(folder);
+    for link in &outline.links {
+        if link.target.starts_with("data:") || is_external_link(&link.target) {
+            continue;
+        }
+        let target = link.target.split('#').next().unwrap_or("");
+        if target.is_empty() {
+            continue;
+        }
+        let decoded = target.replace("%20", " ");
+        if !spec_dir.join(&decoded).exists() {
+            push(&mut diagnostics, config, &spec.path, link.range.start_line, RULE_BROKEN_LINK,
+                format!("Broken link: {}", link.target));
+        }
+    }
+
+    diagnostics
+}
+
+/// Lint a set of specs, including rules that compare specs with each other
+pub fn lint_specs(specs: &[SpecFile], features_dir: &Path, config: &LintConfig) -> Vec<LintDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut titles: HashMap<String, Vec<(&str, usize)>> = HashMap::new();
+
+    for spec in specs {
+        let file_name = spec.path.rsplit('/').next().unwrap_or(&spec.path);
+        if config.exempt_files.iter().any(|f| f == file_name) {
+            continue;
+        }
+        diagnostics.extend(lint_spec(spec, features_dir, config));
+        if let Some((title, line)) = spec_title(&spec.content) {
+            titles.entry(title).or_default().push((&spec.path, line));
+        }
+    }
+
+    for files in titles.values().filter(|files| files.len() > 1) {
+        for (path, line) in files {
+            let others: Vec<&str> = files.iter().filter(|(p, _)| p != path).map(|(p, _)| *p).collect();
+            push(&mut diagnostics, config, path, *line, RULE_DUPLICATE_TITLE,
+                format!("Title is also used by {}", others.join(", ")));
+        }
+    }
+
+    diagnostics.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    diagnostics
+}
+
+fn collect_specs(base_dir: &Path, current_dir: &Path, specs: &mut Vec<SpecFile>) -> Result<(), String> {
+    let entries = fs::read_dir(current_dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_specs(base_dir, &path, specs)?;
+        } else if file_name.ends_with(".md") {
+            let rel_path = path.strip_prefix(base_dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            match fs::read_to_string(&path) {
+                Ok(content) => specs.push(SpecFile { path: rel_path, content }),
+                Err(e) => log::warn!("Skipping unreadable spec {}: {}", rel_path, e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lint every spec under .prompts/features/ of a project
+pub fn lint_project(project_path: &Path) -> Result<Vec<LintDiagnostic>, String> {
+    let features_dir = project_path.join(".prompts").join("features");
+    if !features_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut specs = Vec::new();
+    collect_specs(&features_dir, &features_dir, &mut specs)?;
+    Ok(lint_specs(&specs, &features_dir, &load_config(project_path)))
+}
+
+// Tauri command: Lint feature specs (all of them, or only diagnostics for file_path)
+#[tauri::command]
+pub async fn lint_feature_specs(project_path: String, file_path: Option<String>) -> Result<Vec<LintDiagnostic>, String> {
+    let mut diagnostics = lint_project(&PathBuf::from(&project_path))?;
+
+    if let Some(file) = file_path {
+        let file = file.replace('\\', "/");
+        diagnostics.retain(|d| d.file == file);
+    }
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn spec(path: &str, content: &str) -> SpecFile {
+        SpecFile { path: path.to_string(), content: content.to_string() }
+    }
+
+    fn rules(diagnostics: &[LintDiagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.rule.as_str()).collect()
+    }
+
+    const VALID: &str = "# Feature: Login\n\n## Summary\nText\n\n## Acceptance Criteria\n- [ ] Works\n";
+
+    #[test]
+    fn test_valid_spec_has_no_diagnostics() {
+        let diagnostics = lint_specs(&[spec("2026-02-01-login.md", VALID)], Path::new("/nonexistent"), &LintConfig::default());
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_filename_date_rule() {
+        let config = LintConfig::default();
+        for name in ["abcd-ef-gh-login.md", "2026-13-01-login.md", "login.md"] {
+            let diagnostics = lint_specs(&[spec(name, VALID)], Path::new("/nonexistent"), &config);
+            assert_eq!(rules(&diagnostics), vec![RULE_FILENAME_DATE], "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_required_sections_and_empty_acceptance_criteria() {
+        let content = "# Feature: Login\n\n## Acceptance Criteria\n\n## Notes\n";
+        let diagnostics = lint_specs(&[spec("2026-02-01-login.md", content)], Path::new("/nonexistent"), &LintConfig::default());
+        assert_eq!(rules(&diagnostics), vec![RULE_REQUIRED_SECTIONS, RULE_EMPTY_ACCEPTANCE_CRITERIA]);
+        assert_eq!(diagnostics[1].line, 3);
+
+        // Bug reports use a different structure
+        let diagnostics = lint_specs(&[spec("bugs/2026-02-01-crash.md", "# Bug: Crash\n")], Path::new("/nonexistent"), &LintConfig::default());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_titles_and_severity_overrides() {
+        let specs = [
+            spec("2026-02-01-login.md", VALID),
+            spec("2026-02-02-login-again.md", &VALID.replace("Feature: Login", "Login")),
+        ];
+        let diagnostics = lint_specs(&specs, Path::new("/nonexistent"), &LintConfig::default());
+        assert_eq!(rules(&diagnostics), vec![RULE_DUPLICATE_TITLE, RULE_DUPLICATE_TITLE]);
+        assert!(diagnostics[0].message.contains("2026-02-02-login-again.md"));
+
+        let mut config = LintConfig::default();
+        config.rules.insert(RULE_DUPLICATE_TITLE.to_string(), Severity::Off);
+        assert!(lint_specs(&specs, Path::new("/nonexistent"), &config).is_empty());
+    }
+
+    #[test]
+    fn test_broken_links() {
+        let dir = TempDir::new("spec-lint");
+        fs::write(dir.join("2026-02-01-other.md"), "").unwrap();
+
+        let content = format!("{}\nSee [other](./2026-02-01-other.md#goals), [missing](./missing.md), [web](https://example.com) and [top](#summary).\n", VALID);
+        let diagnostics = lint_specs(&[spec("2026-02-01-login.md", &content)], &dir, &LintConfig::default());
+        assert_eq!(rules(&diagnostics), vec![RULE_BROKEN_LINK]);
+        assert_eq!(diagnostics[0].line, 9);
+    }
+}
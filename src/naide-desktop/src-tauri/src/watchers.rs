@@ -0,0 +1,660 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{recommended_watcher, Event, EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+// How often forwarding threads check whether their watcher was stopped
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// Quiet period that ends a batch of changes, unless configured otherwise
+pub const DEFAULT_DEBOUNCE_MS: u64 = 300;
+// A batch is flushed after this long even if changes keep arriving
+const MAX_BATCH_WAIT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+/// One coalesced change to a path, relative to the project root
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>, // Previous path of a rename
+}
+
+impl FileChange {
+    /// Re-root a change under `prefix` (e.g. ".prompts/features"), None if it is outside.
+    /// A rename into or out of the prefix becomes a create or remove.
+    pub fn relative_to(&self, prefix: &str) -> Option<FileChange> {
+        let strip = |path: &str| -> Option<String> {
+            if prefix.is_empty() {
+                return Some(path.to_string());
+            }
+            path.strip_prefix(prefix)?.strip_prefix('/').map(|rest| rest.to_string())
+        };
+
+        let path = strip(&self.path);
+        let from = self.from.as_deref().and_then(strip);
+        match (path, from, self.kind) {
+            (Some(path), Some(from), ChangeKind::Renamed) => Some(FileChange { kind: ChangeKind::Renamed, path, from: Some(from) }),
+            (Some(path), None, ChangeKind::Renamed) => Some(FileChange { kind: ChangeKind::Created, path, from: None }),
+            (None, Some(from), ChangeKind::Renamed) => Some(FileChange { kind: ChangeKind::Removed, path: from, from: None }),
+            (Some(path), _, kind) => Some(FileChange { kind, path, from: None }),
+            (None, _, _) => None,
+        }
+    }
+}
+
+/// Payload of the feature, project and prompt change events
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChangesPayload {
+    pub changes: Vec<FileChange>,
+}
+
+/// Receives the debounced, coalesced changes of one subscription
+pub type WatchHandler = Box<dyn FnMut(Vec<FileChange>) + Send>;
+
+// Primitive operation derived from a notify event
+enum RawChange {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+    Renamed(PathBuf, PathBuf),
+}
+
+fn is_likely_move(from: &Path, to: &Path) -> bool {
+    from.parent() == to.parent() || from.file_name() == to.file_name()
+}
+
+fn raw_changes(events: &[Event]) -> Vec<RawChange> {
+    // inotify reports a rename as From, To and then Both; prefer Both and drop the halves
+    let mut paired: Vec<(&PathBuf, &PathBuf)> = Vec::new();
+    for event in events {
+        if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind {
+            if let [from, to] = event.paths.as_slice() {
+                paired.push((from, to));
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    let mut pending_from: Option<PathBuf> = None;
+    let flush_from = |pending: &mut Option<PathBuf>, changes: &mut Vec<RawChange>| {
+        if let Some(from) = pending.take() {
+            changes.push(RawChange::Removed(from));
+        }
+    };
+
+    for event in events {
+        match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                if let [from, to] = event.paths.as_slice() {
+                    changes.push(RawChange::Renamed(from.clone(), to.clone()));
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                for path in &event.paths {
+                    if paired.iter().any(|(from, _)| *from == path) {
+                        continue;
+                    }
+                    flush_from(&mut pending_from, &mut changes);
+                    pending_from = Some(path.clone());
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                for path in &event.paths {
+                    if paired.iter().any(|(_, to)| *to == path) {
+                        continue;
+                    }
+                    // A To right after an unpaired From is the other half of the same rename
+                    match pending_from.take() {
+                        Some(from) => changes.push(RawChange::Renamed(from, path.clone())),
+                        None => changes.push(RawChange::Created(path.clone())),
+                    }
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                // Platforms that do not say which side of a rename a path is on (FSEvents):
+                // a path that is gone followed by one that exists is treated as a move
+                // when they share a parent directory or a file name
+                for path in &event.paths {
+                    if !path.exists() {
+                        flush_from(&mut pending_from, &mut changes);
+                        pending_from = Some(path.clone());
+                        continue;
+                    }
+                    match pending_from.take() {
+                        Some(from) if is_likely_move(&from, path) => changes.push(RawChange::Renamed(from, path.clone())),
+                        Some(from) => {
+                            changes.push(RawChange::Removed(from));
+                            changes.push(RawChange::Created(path.clone()));
+                        }
+                        None => changes.push(RawChange::Created(path.clone())),
+                    }
+                }
+            }
+            EventKind::Modify(ModifyKind::Metadata(_)) | EventKind::Access(_) => {}
+            EventKind::Create(_) => changes.extend(event.paths.iter().cloned().map(RawChange::Created)),
+            EventKind::Modify(_) => changes.extend(event.paths.iter().cloned().map(RawChange::Modified)),
+            EventKind::Remove(_) => changes.extend(event.paths.iter().cloned().map(RawChange::Removed)),
+            EventKind::Any | EventKind::Other => {}
+        }
+    }
+    flush_from(&mut pending_from, &mut changes);
+
+    changes
+}
+
+/// Coalesce a batch of notify events into at most one change per path.
+/// Paths outside `project_root` are dropped.
+pub fn coalesce_events(events: &[Event], project_root: &Path) -> Vec<FileChange> {
+    let canonical_root = normalize_root(project_root);
+    let relative = |path: &Path| -> Option<String> {
+        let rel = path.strip_prefix(project_root)
+            .or_else(|_| path.strip_prefix(&canonical_root))
+            .ok()?;
+        Some(rel.to_string_lossy().replace('\\', "/"))
+    };
+
+    let mut order: Vec<String> = Vec::new();
+    let mut changes: HashMap<String, FileChange> = HashMap::new();
+
+    // A path may be listed twice in `order` if it was dropped and re-added; only the first yields a change
+    let mut set = |changes: &mut HashMap<String, FileChange>, change: FileChange| {
+        if !changes.contains_key(&change.path) {
+            order.push(change.path.clone());
+        }
+        changes.insert(change.path.clone(), change);
+    };
+
+    for raw in raw_changes(events) {
+        match raw {
+            RawChange::Created(path) => {
+                let Some(path) = relative(&path) else { continue };
+                let kind = match changes.get(&path).map(|c| c.kind) {
+                    Some(ChangeKind::Removed) => ChangeKind::Modified,
+                    Some(kind) => kind,
+                    None => ChangeKind::Created,
+                };
+                let from = changes.get(&path).and_then(|c| c.from.clone());
+                set(&mut changes, FileChange { kind, path, from });
+            }
+            RawChange::Modified(path) => {
+                let Some(path) = relative(&path) else { continue };
+                if !changes.contains_key(&path) {
+                    set(&mut changes, FileChange { kind: ChangeKind::Modified, path, from: None });
+                } else if let Some(change) = changes.get_mut(&path).filter(|c| c.kind == ChangeKind::Removed) {
+                    change.kind = ChangeKind::Modified;
+                }
+            }
+            RawChange::Removed(path) => {
+                let Some(path) = relative(&path) else { continue };
+                match changes.get(&path).map(|c| (c.kind, c.from.clone())) {
+                    // Created and removed within one batch: nothing happened
+                    Some((ChangeKind::Created, _)) => {
+                        changes.remove(&path);
+                    }
+                    // Renamed and then removed: the original path is gone
+                    Some((ChangeKind::Renamed, Some(from))) => {
+                        changes.remove(&path);
+                        set(&mut changes, FileChange { kind: ChangeKind::Removed, path: from, from: None });
+                    }
+                    _ => set(&mut changes, FileChange { kind: ChangeKind::Removed, path, from: None }),
+                }
+            }
+            RawChange::Renamed(from, to) => {
+                match (relative(&from), relative(&to)) {
+                    (Some(from), Some(to)) => {
+                        let previous = changes.remove(&from);
+                        let change = match previous {
+                            Some(FileChange { kind: ChangeKind::Created, .. }) => {
+                                FileChange { kind: ChangeKind::Created, path: to, from: None }
+                            }
+                            Some(FileChange { kind: ChangeKind::Renamed, from: Some(original), .. }) if original == to => {
+                                FileChange { kind: ChangeKind::Modified, path: to, from: None }
+                            }
+                            Some(FileChange { kind: ChangeKind::Renamed, from: Some(original), .. }) => {
+                                FileChange { kind: ChangeKind::Renamed, path: to, from: Some(original) }
+                            }
+                            _ => FileChange { kind: ChangeKind::Renamed, path: to, from: Some(from) },
+                        };
+                        set(&mut changes, change);
+                    }
+                    (Some(from), None) => set(&mut changes, FileChange { kind: ChangeKind::Removed, path: from, from: None }),
+                    (None, Some(to)) => set(&mut changes, FileChange { kind: ChangeKind::Created, path: to, from: None }),
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+
+    order.into_iter().filter_map(|path| changes.remove(&path)).collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchSubscription {
+    pub id: String,
+    pub project_path: String,
+    pub roots: Vec<String>, // Relative to the project root, "" is the root itself
+    pub debounce_ms: u64,
+}
+
+struct WatchEntry {
+    info: WatchSubscription,
+    _watcher: Box<dyn Watcher + Send>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for WatchEntry {
+    fn drop(&mut self) {
+        // The watcher itself is dropped with the entry; the flag ends the forwarding thread
+        self.stop.store(true, Ordering::SeqCst);
+        log::info!("Stopped watcher: {}", self.info.id);
+    }
+}
+
+/// All active file watchers, keyed by subscription id.
+/// Watchers belong to one project; switching projects tears them all down.
+pub struct WatcherRegistry {
+    project_root: Option<PathBuf>,
+    entries: HashMap<String, WatchEntry>,
+    debounce_ms: u64, // Used by subscriptions that do not pass their own window
+}
+
+impl Default for WatcherRegistry {
+    fn default() -> Self {
+        Self {
+            project_root: None,
+            entries: HashMap::new(),
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+        }
+    }
+}
+
+fn normalize_root(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl WatcherRegistry {
+    /// Make `project_root` the active project, stopping watchers of any other project.
+    /// Returns the number of watchers stopped.
+    pub fn switch_project(&mut self, project_root: &Path) -> usize {
+        let root = normalize_root(project_root);
+        if self.project_root.as_ref() == Some(&root) {
+            return 0;
+        }
+
+        let stopped = self.entries.len();
+        if stopped > 0 {
+            log::info!("Project switched to {:?}, stopping {} watcher(s)", root, stopped);
+        }
+        self.entries.clear();
+        self.project_root = Some(root);
+        stopped
+    }
+
+    pub fn set_default_debounce(&mut self, debounce_ms: u64) {
+        self.debounce_ms = debounce_ms;
+    }
+
+    /// Add a started watcher, replacing a subscription with the same id.
+    /// Watchers of another project are stopped.
+    fn attach(&mut self, entry: WatchEntry) -> WatchSubscription {
+        self.switch_project(Path::new(&entry.info.project_path));
+        let info = entry.info.clone();
+        // Dropping the replaced entry stops its thread
+        self.entries.insert(info.id.clone(), entry);
+        info
+    }
+
+    pub fn unsubscribe(&mut self, id: &str) -> bool {
+        self.entries.remove(id).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.project_root = None;
+    }
+
+    pub fn list(&self) -> Vec<WatchSubscription> {
+        let mut list: Vec<WatchSubscription> = self.entries.values().map(|e| e.info.clone()).collect();
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        list
+    }
+}
+
+/// Start watching `roots` (relative to the project), passing their changes to `handler`
+/// coalesced until no event arrived for the debounce window
+fn start_watch(
+    id: &str,
+    project_path: &str,
+    roots: &[&str],
+    debounce_ms: u64,
+    handler: WatchHandler,
+) -> Result<WatchEntry, String> {
+    let debounce = Duration::from_millis(debounce_ms);
+    let project_root = PathBuf::from(project_path);
+
+    let (tx, rx) = channel();
+    let mut watcher = recommended_watcher(move |res: Result<Event, notify::Error>| {
+        match res {
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(e) => {
+                log::error!("Watch error: {:?}", e);
+            }
+        }
+    }).map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    let mut watched = Vec::new();
+    for root in roots {
+        let path = project_root.join(root);
+        if !path.is_dir() {
+            log::warn!("Watch root does not exist: {:?}", path);
+            continue;
+        }
+        watcher.watch(&path, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch directory: {}", e))?;
+        watched.push(root.to_string());
+    }
+
+    if watched.is_empty() {
+        return Err(format!("No directory to watch for {}", id));
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let mut handler = handler;
+    let thread_root = project_root.clone();
+
+    std::thread::spawn(move || {
+        loop {
+            let first = match rx.recv_timeout(STOP_POLL_INTERVAL) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => {
+                    if thread_stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            // Collect events until the watched tree has been quiet for the debounce window
+            let started = Instant::now();
+            let mut batch = vec![first];
+            let mut disconnected = false;
+            loop {
+                let remaining = MAX_BATCH_WAIT.saturating_sub(started.elapsed());
+                if remaining.is_zero() {
+                    break;
+                }
+                match rx.recv_timeout(debounce.min(remaining)) {
+                    Ok(event) => batch.push(event),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        disconnected = true;
+                        break;
+                    }
+                }
+            }
+
+            if thread_stop.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let changes = coalesce_events(&batch, &thread_root);
+            if !changes.is_empty() {
+                handler(changes);
+            }
+
+            if disconnected {
+                break;
+            }
+        }
+    });
+
+    let info = WatchSubscription {
+        id: id.to_string(),
+        project_path: project_path.to_string(),
+        roots: watched,
+        debounce_ms,
+    };
+    log::info!("Started watcher {} for {:?}", id, info.roots);
+
+    Ok(WatchEntry {
+        info,
+        _watcher: Box::new(watcher),
+        stop,
+    })
+}
+
+/// Watch `roots` of a project and register the watcher under `id`, replacing a subscription
+/// with the same id. The new watcher is started before anything is replaced, so a failure
+/// leaves the registry as it was, and the registry is not locked while the tree is being watched.
+pub fn subscribe(
+    registry: &Mutex<WatcherRegistry>,
+    id: &str,
+    project_path: &str,
+    roots: &[&str],
+    debounce_ms: Option<u64>,
+    handler: WatchHandler,
+) -> Result<WatchSubscription, String> {
+    let debounce_ms = debounce_ms.unwrap_or_else(|| registry.lock().unwrap().debounce_ms);
+    let entry = start_watch(id, project_path, roots, debounce_ms, handler)?;
+    Ok(registry.lock().unwrap().attach(entry))
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WatchChangedPayload {
+    id: String,
+    changes: Vec<FileChange>, // Paths relative to the project root
+}
+
+/// Normalize a watch root relative to the project, rejecting absolute roots (e.g. "C:/Users"),
+/// ".." segments and symlinks that lead out of the project
+fn resolve_watch_root(project_path: &str, root: &str) -> Result<String, String> {
+    let root = root.replace('\\', "/").trim_matches('/').to_string();
+    crate::file_io::resolve_existing(Path::new(project_path), &root, "project")?;
+    Ok(root)
+}
+
+// Tauri command: Watch a directory of the current project (e.g. ".naide/learnings")
+// Emits "watch-changed" with the subscription id and the debounced changes
+#[tauri::command]
+pub async fn subscribe_watch(
+    window: tauri::Window,
+    project_path: String,
+    root: String,
+    id: Option<String>,
+    debounce_ms: Option<u64>,
+) -> Result<WatchSubscription, String> {
+    let root = resolve_watch_root(&project_path, &root)?;
+
+    let id = id.unwrap_or_else(|| root.clone());
+    let window_clone = window.clone();
+    let handler_id = id.clone();
+
+    let handler: WatchHandler = Box::new(move |changes| {
+        let payload = WatchChangedPayload { id: handler_id.clone(), changes };
+        if let Err(e) = window_clone.emit("watch-changed", payload) {
+            log::error!("Failed to emit event: {}", e);
+        }
+    });
+
+    subscribe(window.state::<Mutex<WatcherRegistry>>().inner(), &id, &project_path, &[root.as_str()], debounce_ms, handler)
+}
+
+// Tauri command: Stop a watcher by id
+#[tauri::command]
+pub async fn unsubscribe_watch(window: tauri::Window, id: String) -> Result<bool, String> {
+    Ok(window.state::<Mutex<WatcherRegistry>>().lock().unwrap().unsubscribe(&id))
+}
+
+// Tauri command: List active watchers
+#[tauri::command]
+pub async fn list_watches(window: tauri::Window) -> Result<Vec<WatchSubscription>, String> {
+    Ok(window.state::<Mutex<WatcherRegistry>>().lock().unwrap().list())
+}
+
+// Tauri command: Set the debounce window used by watchers started afterwards
+#[tauri::command]
+pub async fn set_watch_debounce(window: tauri::Window, debounce_ms: u64) -> Result<(), String> {
+    window.state::<Mutex<WatcherRegistry>>().lock().unwrap().set_default_debounce(debounce_ms);
+    log::info!("Watch debounce set to {} ms", debounce_ms);
+    Ok(())
+}
+
+// Tauri command: Stop every watcher (e.g. when closing a project)
+#[tauri::command]
+pub async fn stop_all_watches(window: tauri::Window) -> Result<(), String> {
+    window.state::<Mutex<WatcherRegistry>>().lock().unwrap().clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_subscribe_replace_and_switch_project() {
+        let base = TempDir::new("watchers");
+        fs::create_dir_all(base.join("a").join(".prompts").join("plan")).unwrap();
+        fs::create_dir_all(base.join("b")).unwrap();
+        let project_a = base.join("a").to_string_lossy().to_string();
+        let project_b = base.join("b").to_string_lossy().to_string();
+
+        let registry = Mutex::new(WatcherRegistry::default());
+        let info = subscribe(&registry, "plan", &project_a, &[".prompts/plan", "missing"], None, Box::new(|_| {})).unwrap();
+        assert_eq!(info.roots, vec![".prompts/plan"]);
+        subscribe(&registry, "project", &project_a, &[""], None, Box::new(|_| {})).unwrap();
+        subscribe(&registry, "plan", &project_a, &[".prompts/plan"], None, Box::new(|_| {})).unwrap();
+        assert_eq!(registry.lock().unwrap().list().len(), 2);
+
+        // A watcher that cannot start leaves the existing ones running, in this project and others
+        assert!(subscribe(&registry, "none", &project_a, &["missing"], None, Box::new(|_| {})).is_err());
+        assert!(subscribe(&registry, "plan", &project_b, &["missing"], None, Box::new(|_| {})).is_err());
+        let list = registry.lock().unwrap().list();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].id, "plan");
+        assert_eq!(list[0].project_path, project_a);
+
+        // Watching another project stops the old project's watchers
+        subscribe(&registry, "project", &project_b, &[""], None, Box::new(|_| {})).unwrap();
+        let list = registry.lock().unwrap().list();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].project_path, project_b);
+
+        let mut registry = registry.lock().unwrap();
+        assert!(registry.unsubscribe("project"));
+        assert!(!registry.unsubscribe("project"));
+    }
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        let mut event = Event::new(kind);
+        for path in paths {
+            event = event.add_path(PathBuf::from("/project").join(path));
+        }
+        event
+    }
+
+    #[test]
+    fn test_coalesce_events() {
+        use notify::event::{CreateKind, DataChange, RemoveKind};
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Any));
+
+        let events = vec![
+            event(EventKind::Create(CreateKind::File), &["a.md"]),
+            event(modify, &["a.md"]),
+            event(modify, &["b.md"]),
+            event(modify, &["b.md"]),
+            event(EventKind::Create(CreateKind::File), &["tmp.swp"]),
+            event(EventKind::Remove(RemoveKind::File), &["tmp.swp"]),
+            event(EventKind::Remove(RemoveKind::File), &["c.md"]),
+            event(EventKind::Create(CreateKind::File), &["/elsewhere/x.md"]),
+        ];
+        let changes = coalesce_events(&events, Path::new("/project"));
+        let summary: Vec<(ChangeKind, &str)> = changes.iter().map(|c| (c.kind, c.path.as_str())).collect();
+        assert_eq!(summary, vec![
+            (ChangeKind::Created, "a.md"),
+            (ChangeKind::Modified, "b.md"),
+            (ChangeKind::Removed, "c.md"),
+        ]);
+    }
+
+    #[test]
+    fn test_coalesce_renames() {
+        let name = |mode| EventKind::Modify(ModifyKind::Name(mode));
+
+        // inotify: From, To, then Both for the same rename
+        let events = vec![
+            event(name(RenameMode::From), &["old.md"]),
+            event(name(RenameMode::To), &["docs/new.md"]),
+            event(name(RenameMode::Both), &["old.md", "docs/new.md"]),
+        ];
+        let changes = coalesce_events(&events, Path::new("/project"));
+        assert_eq!(changes, vec![FileChange {
+            kind: ChangeKind::Renamed,
+            path: "docs/new.md".to_string(),
+            from: Some("old.md".to_string()),
+        }]);
+
+        // Re-rooting a rename that leaves the prefix turns it into a remove
+        let moved = FileChange { kind: ChangeKind::Renamed, path: "b/x.md".to_string(), from: Some("a/x.md".to_string()) };
+        assert_eq!(moved.relative_to("a").unwrap().kind, ChangeKind::Removed);
+        assert_eq!(moved.relative_to("a").unwrap().path, "x.md");
+        assert!(moved.relative_to("c").is_none());
+
+        // Separate events without a side (macOS): a missing path followed by an existing one
+        let dir = TempDir::new("watch-move");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub").join("moved.md"), "").unwrap();
+        let any = EventKind::Modify(ModifyKind::Name(RenameMode::Any));
+        let events = vec![
+            Event::new(any).add_path(dir.join("moved.md")),
+            Event::new(any).add_path(dir.join("sub").join("moved.md")),
+        ];
+        let changes = coalesce_events(&events, &dir);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, ChangeKind::Renamed);
+        assert_eq!(changes[0].from.as_deref(), Some("moved.md"));
+
+        // Unpaired halves (e.g. moved in from outside the watched tree)
+        let events = vec![event(name(RenameMode::To), &["in.md"]), event(name(RenameMode::From), &["out.md"])];
+        let kinds: Vec<ChangeKind> = coalesce_events(&events, Path::new("/project")).iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::Created, ChangeKind::Removed]);
+    }
+
+    #[test]
+    fn test_watch_root_stays_in_project() {
+        let root = TempDir::new("watch-root");
+        let project = root.join("project");
+        fs::create_dir_all(project.join(".naide").join("learnings")).unwrap();
+        fs::create_dir_all(root.join("outside")).unwrap();
+        let project_path = project.to_string_lossy().to_string();
+
+        assert_eq!(resolve_watch_root(&project_path, ".naide\\learnings/").unwrap(), ".naide/learnings");
+        assert!(resolve_watch_root(&project_path, "../outside").is_err());
+        assert!(resolve_watch_root(&project_path, &root.join("outside").to_string_lossy()).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("outside"), project.join("link")).unwrap();
+            assert!(resolve_watch_root(&project_path, "link").is_err());
+        }
+    }
+}
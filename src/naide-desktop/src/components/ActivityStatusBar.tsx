@@ -10,6 +10,7 @@ import {
   XCircle,
   Loader2,
 } from 'lucide-react';
+import { sidecarWebSocketUrl } from '../utils/sidecar';
 
 export type StatusEventType = 'file_read' | 'file_write' | 'analysis' | 'build' | 'test' | 'api_call' | 'session_complete';
 export type StatusEventStatus = 'in_progress' | 'complete' | 'error';
@@ -89,8 +90,17 @@ const ActivityStatusBar: React.FC = () => {
 
   // WebSocket connection management
   useEffect(() => {
-    const connectWebSocket = () => {
-      const ws = new WebSocket('ws://localhost:3001/api/status');
+    const connectWebSocket = async () => {
+      // Port and token change when the sidecar restarts, so look them up on every attempt
+      let url: string;
+      try {
+        url = await sidecarWebSocketUrl('/api/status');
+      } catch (error) {
+        console.warn('[ActivityStatusBar] Sidecar not available, retrying...', error);
+        setTimeout(connectWebSocket, 5000);
+        return;
+      }
+      const ws = new WebSocket(url);
 
       ws.onopen = () => {
         console.log('[ActivityStatusBar] Connected to status WebSocket');
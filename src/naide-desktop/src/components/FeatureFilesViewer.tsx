@@ -6,6 +6,7 @@ import FeatureFilesList from './FeatureFilesList';
 import ViewOptionsMenu from './ViewOptionsMenu';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import type { FileChangesPayload } from '../utils/fileChanges';
 
 // LocalStorage keys for persisting view options
 const STORAGE_KEY_VIEW_OPTIONS = 'naide-feature-viewer-options';
@@ -112,8 +113,8 @@ const FeatureFilesViewer: React.FC<FeatureFilesViewerProps> = ({
     const setupListener = async () => {
       try {
         // Listen for file change events
-        unlisten = await listen('feature-files-changed', () => {
-          console.log('[FeatureFilesViewer] File change event received');
+        unlisten = await listen<FileChangesPayload>('feature-files-changed', (event) => {
+          console.log(`[FeatureFilesViewer] File change event received (${event.payload?.changes?.length ?? 0} changes)`);
           debouncedRefresh();
         });
         
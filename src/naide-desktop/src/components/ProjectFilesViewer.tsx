@@ -4,6 +4,7 @@ import { useAppContext } from '../context/useAppContext';
 import { listProjectFiles, type ProjectFileNode } from '../utils/projectFiles';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { applyMovesToPaths, type FileChangesPayload } from '../utils/fileChanges';
 
 interface ProjectFilesViewerProps {
   onFileSelect?: (file: ProjectFileNode) => void;
@@ -28,6 +29,12 @@ const ProjectFilesViewer: React.FC<ProjectFilesViewerProps> = ({ onFileSelect })
   
   // Debounce timer ref
   const debounceTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  
+  // Latest expanded folders, read by refreshes triggered from the file watcher
+  const expandedFoldersRef = useRef<Set<string>>(expandedFolders);
+  useEffect(() => {
+    expandedFoldersRef.current = expandedFolders;
+  }, [expandedFolders]);
 
   // Handle refresh - re-fetch root files and all expanded folders
   const handleRefresh = useCallback(async () => {
@@ -43,7 +50,7 @@ const ProjectFilesViewer: React.FC<ProjectFilesViewerProps> = ({ onFileSelect })
       const newLoadedFolders = new Map<string, ProjectFileNode[]>();
       const foldersToRemove: string[] = [];
       
-      for (const folderPath of expandedFolders) {
+      for (const folderPath of expandedFoldersRef.current) {
         try {
           const children = await listProjectFiles(state.projectPath, folderPath);
           newLoadedFolders.set(folderPath, children);
@@ -69,7 +76,7 @@ const ProjectFilesViewer: React.FC<ProjectFilesViewerProps> = ({ onFileSelect })
     } finally {
       setLoading(false);
     }
-  }, [state.projectPath, loading, expandedFolders]);
+  }, [state.projectPath, loading]);
 
   // Debounced refresh function
   const debouncedRefresh = useCallback(() => {
@@ -125,8 +132,16 @@ const ProjectFilesViewer: React.FC<ProjectFilesViewerProps> = ({ onFileSelect })
     const setupListener = async () => {
       try {
         // Listen for file change events
-        unlisten = await listen('project-files-changed', () => {
-          console.log('[ProjectFilesViewer] File change event received');
+        unlisten = await listen<FileChangesPayload>('project-files-changed', (event) => {
+          const changes = event.payload?.changes ?? [];
+          console.log(`[ProjectFilesViewer] File change event received (${changes.length} changes)`);
+          
+          // Keep moved folders expanded under their new path
+          const remapped = applyMovesToPaths(expandedFoldersRef.current, changes);
+          if (remapped !== expandedFoldersRef.current) {
+            expandedFoldersRef.current = remapped;
+            setExpandedFolders(remapped);
+          }
           debouncedRefresh();
         });
         
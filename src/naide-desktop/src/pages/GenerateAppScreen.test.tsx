@@ -31,6 +31,12 @@ const createStreamingResponse = (content: string) => {
 // Mock fetch
 global.fetch = vi.fn();
 
+// Sidecar requests go straight to the fetch mock; the real helper first asks the backend for the port and token
+vi.mock('../utils/sidecar', () => ({
+  sidecarFetch: (path: string, init?: RequestInit) => fetch(`http://127.0.0.1:3001${path}`, init),
+  sidecarWebSocketUrl: vi.fn().mockResolvedValue('ws://127.0.0.1:3001/api/status?token=test-token'),
+}));
+
 // Mock file system utilities
 vi.mock('../utils/fileSystem', () => ({
   createAllProjectFiles: vi.fn().mockResolvedValue(undefined),
@@ -26,6 +26,7 @@ import { getRecentProjects, saveLastProject, removeRecentProject, type LastProje
 import { logInfo, logError } from '../utils/logger';
 import { loadOpenTabs, saveOpenTabs, type PersistedTab } from '../utils/tabPersistence';
 import { loadFavoriteSessions, toggleFavoriteSession } from '../utils/favoritePersistence';
+import { sidecarFetch } from '../utils/sidecar';
 import { ProjectLinkProvider } from '../context/ProjectLinkContext';
 import { getTabType } from '../utils/projectLinkUtils';
 import { invoke } from '@tauri-apps/api/core';
@@ -321,7 +322,7 @@ const GenerateAppScreen: React.FC = () => {
     const fetchModels = async () => {
       try {
         logInfo('[GenerateApp] Fetching available models from sidecar');
-        const response = await fetch('http://localhost:3001/api/models');
+        const response = await sidecarFetch('/api/models');
         
         if (!response.ok) {
           throw new Error(`HTTP ${response.status}: ${response.statusText}`);
@@ -654,7 +655,7 @@ const GenerateAppScreen: React.FC = () => {
       abortControllerRef.current = controller;
       
       try {
-        const response = await fetch('http://localhost:3001/api/copilot/stream', {
+        const response = await sidecarFetch('/api/copilot/stream', {
           method: 'POST',
           headers: {
             'Content-Type': 'application/json',
@@ -966,7 +967,7 @@ const GenerateAppScreen: React.FC = () => {
     if (hasUserMessages) {
       try {
         // Archive the current chat session
-        const { archiveChatSession } = await import('../utils/chatPersistence');
+        const { archiveChatSession, summarizeChatSession } = await import('../utils/chatPersistence');
         const archivedId = await archiveChatSession(
           state.projectName,
           messages,
@@ -977,6 +978,13 @@ const GenerateAppScreen: React.FC = () => {
         
         if (archivedId) {
           console.log('[GenerateApp] Archived chat session:', archivedId);
+          
+          // Title, summary and tags are generated in the background
+          if (state.projectPath) {
+            summarizeChatSession(state.projectPath, `${archivedId}.json`).catch((error) => {
+              console.warn('[GenerateApp] Failed to summarize archived chat session:', error);
+            });
+          }
         }
       } catch (error) {
         console.error('[GenerateApp] Error archiving chat session:', error);
@@ -1634,7 +1642,7 @@ const GenerateAppScreen: React.FC = () => {
       if (result.url) {
         try {
           logInfo(`[AppRunner] Starting proxy for URL: ${result.url}`);
-          const proxyResponse = await fetch('http://localhost:3001/api/proxy/start', {
+          const proxyResponse = await sidecarFetch('/api/proxy/start', {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
             body: JSON.stringify({ targetUrl: result.url }),
@@ -1691,7 +1699,7 @@ const GenerateAppScreen: React.FC = () => {
       if (appRunState.proxyUrl) {
         try {
           logInfo('[AppRunner] Stopping proxy');
-          await fetch('http://localhost:3001/api/proxy/stop', {
+          await sidecarFetch('/api/proxy/stop', {
             method: 'POST',
           });
           logInfo('[AppRunner] Proxy stopped');
@@ -1,11 +1,13 @@
-import { exists, readTextFile, writeTextFile, mkdir } from '@tauri-apps/plugin-fs';
+import { exists, readTextFile } from '@tauri-apps/plugin-fs';
 import { join } from '@tauri-apps/api/path';
+import { invoke } from '@tauri-apps/api/core';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { getProjectPath } from './fileSystem';
 import { logInfo, logError } from './logger';
 
 export interface ChatMessage {
   id: string;
-  role: 'user' | 'assistant' | 'command';
+  role: 'user' | 'assistant' | 'command' | 'system' | 'tool';
   content: string;
   timestamp: string;
   // Command-specific fields
@@ -13,14 +15,49 @@ export interface ChatMessage {
   commandStatus?: 'running' | 'success' | 'error';
   isExpanded?: boolean;
   userToggled?: boolean;
+  toolCalls?: ChatToolCall[];
+  attachments?: ChatAttachment[];
+}
+
+export interface ChatToolCall {
+  id: string;
+  name: string;
+  arguments?: unknown;
+  result?: unknown;
+  status?: 'running' | 'success' | 'error';
+}
+
+export interface ChatAttachment {
+  name: string;
+  path?: string; // Relative to the project root
+  mimeType?: string;
+  size?: number;
+}
+
+export interface TokenCounts {
+  input: number; // User and system messages
+  output: number; // Assistant messages and tool calls
+  total: number;
+}
+
+// Generated by summarize_chat_session after a session is archived
+export interface ChatDigest {
+  summary: string;
+  tags: string[];
+  tokenCounts: TokenCounts; // Estimated, see count_tokens
+  model?: string;
+  generatedAt: string;
 }
 
 export interface ChatSession {
+  schemaVersion?: number; // Set by the backend when it migrates or saves a session
   id: string;
   projectName: string;
   mode?: string;
+  title?: string;
   messages: ChatMessage[];
   summary?: unknown;
+  digest?: ChatDigest;
   createdAt: string;
   updatedAt: string;
   savedAt?: string;
@@ -72,28 +109,6 @@ async function loadProjectConfig(projectName: string, actualPath?: string): Prom
   }
 }
 
-// Save project config
-async function saveProjectConfig(projectName: string, config: { lastChatSession: string | null }, actualPath?: string): Promise<void> {
-  try {
-    const naideDir = await getProjectNaideDir(projectName, actualPath);
-    const naideDirExists = await exists(naideDir);
-    
-    if (!naideDirExists) {
-      console.log('[ChatPersistence] Creating .naide directory:', naideDir);
-      await mkdir(naideDir, { recursive: true });
-    }
-    
-    const configPath = await getProjectConfigPath(projectName, actualPath);
-    const content = JSON.stringify(config, null, 2);
-    console.log('[ChatPersistence] Saving project config to:', configPath);
-    await writeTextFile(configPath, content);
-    console.log('[ChatPersistence] Project config saved successfully');
-  } catch (error) {
-    console.error('[ChatPersistence] Error saving project config:', error);
-    throw error;
-  }
-}
-
 // Load chat session
 export async function loadChatSession(projectName: string, sessionFilename?: string, actualPath?: string): Promise<ChatMessage[]> {
   logInfo(`[ChatPersistence] loadChatSession called with: projectName=${projectName}, sessionFilename=${sessionFilename}, actualPath=${actualPath}`);
@@ -151,22 +166,8 @@ export async function loadFullChatSession(projectName: string, sessionFilename:
   }
 }
 
-// Serialize messages for disk storage - strip command output but keep command metadata
-function serializeMessagesForDisk(messages: ChatMessage[]): ChatMessage[] {
-  return messages.map(m => {
-    if (m.role === 'command') {
-      return {
-        ...m,
-        content: '(command executed)',
-        isExpanded: undefined,
-        userToggled: undefined,
-      };
-    }
-    return m;
-  });
-}
-
 // Save chat session
+// The backend strips command output, writes atomically and records the last used session
 export async function saveChatSession(
   projectName: string,
   messages: ChatMessage[],
@@ -176,54 +177,23 @@ export async function saveChatSession(
   logInfo(`[ChatPersistence] saveChatSession called with: projectName=${projectName}, messageCount=${messages.length}, sessionFilename=${sessionFilename}, actualPath=${actualPath}`);
   try {
     const filename = sessionFilename || getDefaultChatSessionFilename();
+    const projectPath = await getProjectPath(projectName, actualPath);
     
-    // Ensure chat sessions directory exists
-    const chatSessionsDir = await getChatSessionsDir(projectName, actualPath);
-    logInfo(`[ChatPersistence] Chat sessions directory: ${chatSessionsDir}`);
-    const chatSessionsDirExists = await exists(chatSessionsDir);
-    
-    if (!chatSessionsDirExists) {
-      logInfo(`[ChatPersistence] Creating chat sessions directory: ${chatSessionsDir}`);
-      await mkdir(chatSessionsDir, { recursive: true });
-    }
-    
-    // Create session object
-    const sessionPath = await join(chatSessionsDir, filename);
-    logInfo(`[ChatPersistence] Attempting to save chat to: ${sessionPath}`);
-    const sessionExists = await exists(sessionPath);
-    
-    // Serialize messages for disk (strip command output)
-    const serializedMessages = serializeMessagesForDisk(messages);
-    
-    let session: ChatSession;
-    if (sessionExists) {
-      // Update existing session
-      logInfo('[ChatPersistence] Updating existing chat session');
-      const existingContent = await readTextFile(sessionPath);
-      const existingSession: ChatSession = JSON.parse(existingContent);
-      session = {
-        ...existingSession,
-        messages: serializedMessages,
-        updatedAt: new Date().toISOString(),
-      };
-    } else {
-      // Create new session
-      logInfo('[ChatPersistence] Creating new chat session');
-      session = {
-        id: filename.replace('.json', ''),
-        projectName,
-        messages: serializedMessages,
-        createdAt: new Date().toISOString(),
-        updatedAt: new Date().toISOString(),
-      };
-    }
-    
-    const content = JSON.stringify(session, null, 2);
-    await writeTextFile(sessionPath, content);
-    logInfo(`[ChatPersistence] Successfully saved chat session to: ${sessionPath} with ${messages.length} messages`);
+    // Keep mode, summary and title of an existing session
+    const existingSession = await loadFullChatSession(projectName, filename, actualPath);
+    const now = new Date().toISOString();
+    const session: ChatSession = existingSession
+      ? { ...existingSession, messages, updatedAt: now }
+      : {
+          id: filename.replace('.json', ''),
+          projectName,
+          messages,
+          createdAt: now,
+          updatedAt: now,
+        };
     
-    // Update project config with last used session
-    await saveProjectConfig(projectName, { lastChatSession: filename }, actualPath);
+    await invoke<ChatSession>('save_chat_session', { projectPath, session, filename });
+    logInfo(`[ChatPersistence] Successfully saved chat session ${filename} with ${messages.length} messages`);
   } catch (error) {
     logError(`[ChatPersistence] Error saving chat session: ${error}`);
     throw error;
@@ -252,20 +222,7 @@ export async function listChatSessions(projectName: string, actualPath?: string)
   }
 }
 
-// Generate a unique chat session ID with date prefix (YYYY-MM-DD-)
-function generateChatId(): string {
-  const now = new Date();
-  const year = now.getFullYear();
-  const month = String(now.getMonth() + 1).padStart(2, '0');
-  const day = String(now.getDate()).padStart(2, '0');
-  const datePrefix = `${year}-${month}-${day}`;
-  
-  const timestamp = Date.now();
-  const randomStr = Math.random().toString(36).substr(2, 9);
-  return `${datePrefix}-chat-${timestamp}-${randomStr}`;
-}
-
-// Archive the current chat session by saving it with a unique ID
+// Archive the current chat session under a generated name
 // Returns the archived session ID, or null if there was nothing to archive
 export async function archiveChatSession(
   projectName: string,
@@ -275,57 +232,215 @@ export async function archiveChatSession(
   actualPath?: string
 ): Promise<string | null> {
   try {
-    // Filter out welcome messages (they have IDs starting with 'welcome-')
-    const userMessages = messages.filter(
-      (m) => m.role === 'user' || !m.id.startsWith('welcome-')
-    );
-    
-    // Only archive if there are user messages
-    const hasUserMessages = messages.some((m) => m.role === 'user');
-    if (!hasUserMessages) {
-      console.log('[ChatPersistence] No user messages to archive, skipping');
-      return null;
-    }
-    
-    // Generate unique ID for the archived session
-    const chatId = generateChatId();
-    const filename = `${chatId}.json`;
-    
-    // Ensure chat sessions directory exists
-    const chatSessionsDir = await getChatSessionsDir(projectName, actualPath);
-    const chatSessionsDirExists = await exists(chatSessionsDir);
-    
-    if (!chatSessionsDirExists) {
-      console.log('[ChatPersistence] Creating chat sessions directory:', chatSessionsDir);
-      await mkdir(chatSessionsDir, { recursive: true });
-    }
-    
-    // Serialize messages for disk (strip command output)
-    const serializedMessages = serializeMessagesForDisk(userMessages);
-    
-    // Create the archived session object
+    const projectPath = await getProjectPath(projectName, actualPath);
     const now = new Date().toISOString();
     const session: ChatSession = {
-      id: chatId,
+      id: '',
       projectName,
       mode,
-      messages: serializedMessages,
+      messages,
       summary,
       createdAt: messages[0]?.timestamp || now,
       updatedAt: now,
-      savedAt: now,
     };
     
-    const sessionPath = await join(chatSessionsDir, filename);
-    const content = JSON.stringify(session, null, 2);
+    const filename = await invoke<string | null>('archive_chat_session', { projectPath, session });
+    if (!filename) {
+      console.log('[ChatPersistence] No user messages to archive, skipping');
+      return null;
+    }
     
-    console.log('[ChatPersistence] Archiving chat session to:', sessionPath);
-    await writeTextFile(sessionPath, content);
+    const chatId = filename.replace('.json', '');
     console.log('[ChatPersistence] Chat session archived successfully with ID:', chatId);
-    
     return chatId;
   } catch (error) {
     console.error('[ChatPersistence] Error archiving chat session:', error);
     throw error;
   }
 }
+
+// Give a chat session a human-readable title
+export async function renameChatSession(projectPath: string, filename: string, title: string): Promise<ChatSession> {
+  return await invoke<ChatSession>('rename_chat_session', { projectPath, filename, title });
+}
+
+// Ask the sidecar for a title, summary and tags for an archived session (uses the selected model)
+export async function summarizeChatSession(projectPath: string, filename: string): Promise<ChatSession> {
+  return await invoke<ChatSession>('summarize_chat_session', { projectPath, filename });
+}
+
+// Copy a chat session up to and including a message into a new session
+// Returns the new session filename
+export async function forkChatSession(projectPath: string, filename: string, messageIndex: number): Promise<string> {
+  return await invoke<string>('fork_chat_session', { projectPath, filename, messageIndex });
+}
+
+export interface TrashedChatSession {
+  filename: string; // Name in the trash folder
+  original_filename: string;
+  deleted_at: string;
+  message_count: number;
+  mode: string;
+  first_user_message: string;
+  title: string | null;
+}
+
+// List trashed chat sessions; sessions older than retentionDays (default 30, 0 keeps all) are purged first
+export async function listTrashedChatSessions(projectPath: string, retentionDays?: number): Promise<TrashedChatSession[]> {
+  return await invoke<TrashedChatSession[]>('list_trashed_chat_sessions', { projectPath, retentionDays: retentionDays ?? null });
+}
+
+// Restore a trashed chat session; returns the restored filename
+export async function restoreChatSession(projectPath: string, filename: string): Promise<string> {
+  return await invoke<string>('restore_chat_session', { projectPath, filename });
+}
+
+// Permanently delete one trashed chat session, or empty the trash when no filename is given
+export async function purgeChatSessions(projectPath: string, filename?: string): Promise<number> {
+  return await invoke<number>('purge_chat_sessions', { projectPath, filename: filename ?? null });
+}
+
+export interface ChatSessionMetadata {
+  filename: string;
+  last_modified: number;
+  message_count: number;
+  mode: string;
+  first_user_message: string;
+  title: string | null;
+  created_at: string;
+  summary: string | null;
+  tags: string[];
+  token_count: number | null;
+  model: string | null;
+}
+
+export interface ChatSessionPage {
+  sessions: ChatSessionMetadata[];
+  total: number;
+  offset: number;
+}
+
+export interface ChatSessionPageOptions {
+  offset?: number;
+  limit?: number;
+  sortBy?: 'date' | 'created' | 'mode';
+  descending?: boolean;
+  mode?: string;
+}
+
+// List one page of archived chat sessions from the backend index
+export async function listChatSessionsPage(projectPath: string, options: ChatSessionPageOptions = {}): Promise<ChatSessionPage> {
+  return await invoke<ChatSessionPage>('list_chat_sessions_page', {
+    projectPath,
+    offset: options.offset ?? null,
+    limit: options.limit ?? null,
+    sortBy: options.sortBy ?? null,
+    descending: options.descending ?? null,
+    mode: options.mode ?? null,
+  });
+}
+
+export interface ChatSearchOptions {
+  roles?: ChatMessage['role'][];
+  mode?: string;
+  from?: string; // YYYY-MM-DD or ISO timestamp, inclusive
+  to?: string;
+  filename?: string; // Search within one session
+  caseSensitive?: boolean;
+  includeActive?: boolean;
+  limit?: number;
+}
+
+export interface ChatSearchResult {
+  filename: string;
+  session_title: string | null;
+  mode: string;
+  message_index: number;
+  message_id: string;
+  role: ChatMessage['role'];
+  timestamp: string;
+  snippet: string;
+  highlights: [number, number][]; // [start, end) string indices into snippet
+  match_count: number;
+}
+
+export interface ChatSearchResponse {
+  results: ChatSearchResult[];
+  total_messages: number;
+  truncated: boolean;
+}
+
+// Search message content across chat sessions
+export async function searchChatSessions(projectPath: string, query: string, options: ChatSearchOptions = {}): Promise<ChatSearchResponse> {
+  return await invoke<ChatSearchResponse>('search_chat_sessions', {
+    projectPath,
+    query,
+    options: {
+      roles: options.roles ?? null,
+      mode: options.mode ?? null,
+      from: options.from ?? null,
+      to: options.to ?? null,
+      filename: options.filename ?? null,
+      case_sensitive: options.caseSensitive ?? null,
+      include_active: options.includeActive ?? null,
+      limit: options.limit ?? null,
+    },
+  });
+}
+
+export type ChatExportFormat = 'markdown' | 'html' | 'jsonl';
+
+export interface ChatExportResult {
+  path: string;
+  format: ChatExportFormat;
+  bytes: number;
+  message_count: number;
+}
+
+const EXPORT_FILTERS: Record<ChatExportFormat, { name: string; extensions: string[] }> = {
+  markdown: { name: 'Markdown', extensions: ['md'] },
+  html: { name: 'HTML', extensions: ['html'] },
+  jsonl: { name: 'JSON Lines', extensions: ['jsonl'] },
+};
+
+// Export a chat session to a file chosen with the save dialog
+// Returns null if the user cancels the dialog
+export async function exportChatSession(
+  projectPath: string,
+  filename: string,
+  format: ChatExportFormat
+): Promise<ChatExportResult | null> {
+  const filter = EXPORT_FILTERS[format];
+  const targetPath = await save({
+    title: 'Export chat',
+    defaultPath: `${filename.replace('.json', '')}.${filter.extensions[0]}`,
+    filters: [filter],
+  });
+  if (!targetPath) {
+    return null;
+  }
+  return await invoke<ChatExportResult>('export_chat_session', { projectPath, filename, format, targetPath });
+}
+
+export type ChatImportSource = 'vscode-copilot' | 'naide-vscode' | 'openai';
+
+export interface ChatImportResult {
+  source: ChatImportSource;
+  imported: { filename: string; title: string | null; message_count: number; created_at: string }[];
+  skipped: string[];
+}
+
+// Import conversations from a file chosen with the open dialog
+// The source format is detected unless given; returns null if the user cancels the dialog
+export async function importChatSessions(projectPath: string, source?: ChatImportSource): Promise<ChatImportResult | null> {
+  const sourcePath = await open({
+    title: 'Import chats',
+    multiple: false,
+    directory: false,
+    filters: [{ name: 'Chat exports', extensions: ['json', 'jsonl'] }],
+  });
+  if (!sourcePath || Array.isArray(sourcePath)) {
+    return null;
+  }
+  return await invoke<ChatImportResult>('import_chat_sessions', { projectPath, sourcePath, source: source ?? null });
+}
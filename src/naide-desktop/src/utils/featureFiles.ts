@@ -7,6 +7,8 @@ export interface FeatureFileNode {
   date: string | null;   // Parsed date (YYYY-MM-DD)
   is_folder: boolean;
   children: FeatureFileNode[] | null;
+  tasks_total?: number;  // Task-list items (summed over children for folders)
+  tasks_done?: number;   // Checked task-list items
 }
 
 export interface ViewOptions {
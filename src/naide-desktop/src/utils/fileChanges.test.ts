@@ -0,0 +1,25 @@
+import { describe, it, expect } from 'vitest';
+import { applyMovesToPaths, type FileChange } from './fileChanges';
+
+describe('applyMovesToPaths', () => {
+  it('should move a renamed folder and its expanded descendants', () => {
+    const changes: FileChange[] = [{ kind: 'renamed', path: 'lib', from: 'src' }];
+    const result = applyMovesToPaths(new Set(['src', 'src/components', 'docs', 'srcs']), changes);
+    expect([...result].sort()).toEqual(['docs', 'lib', 'lib/components', 'srcs']);
+  });
+
+  it('should drop removed folders', () => {
+    const changes: FileChange[] = [{ kind: 'removed', path: 'old' }];
+    expect([...applyMovesToPaths(new Set(['old', 'old/a', 'keep']), changes)]).toEqual(['keep']);
+  });
+
+  it('should keep Windows separators', () => {
+    const changes: FileChange[] = [{ kind: 'renamed', path: 'app/web', from: 'web' }];
+    expect([...applyMovesToPaths(new Set(['web\\src']), changes)]).toEqual(['app\\web\\src']);
+  });
+
+  it('should return the same set when nothing moved', () => {
+    const paths = new Set(['src']);
+    expect(applyMovesToPaths(paths, [{ kind: 'created', path: 'new.ts' }])).toBe(paths);
+  });
+});
@@ -0,0 +1,48 @@
+// Payload of the debounced file change events emitted by the backend watchers
+// (feature-files-changed, project-files-changed, plan-files-changed, system-prompts-changed)
+
+export type FileChangeKind = 'created' | 'modified' | 'removed' | 'renamed';
+
+export interface FileChange {
+  kind: FileChangeKind;
+  path: string;   // Relative to the watched directory (project root for project-files-changed)
+  from?: string;  // Previous path of a rename
+}
+
+export interface FileChangesPayload {
+  changes: FileChange[];
+}
+
+/**
+ * Carry tree paths (e.g. expanded folders) over renames and moves.
+ * A renamed folder takes its expanded descendants with it; removed paths are dropped.
+ * Paths may use either separator; the separator of each path is kept.
+ */
+export function applyMovesToPaths(paths: Set<string>, changes: FileChange[]): Set<string> {
+  const moves = changes.filter((c) => c.kind === 'renamed' && c.from);
+  const removals = changes.filter((c) => c.kind === 'removed');
+  if (moves.length === 0 && removals.length === 0) {
+    return paths;
+  }
+
+  const isSameOrInside = (path: string, prefix: string) =>
+    path === prefix || path.startsWith(`${prefix}/`);
+
+  const result = new Set<string>();
+  for (const original of paths) {
+    const separator = original.includes('\\') ? '\\' : '/';
+    let path = original.replace(/\\/g, '/');
+
+    if (removals.some((c) => isSameOrInside(path, c.path))) {
+      continue;
+    }
+
+    const move = moves.find((c) => isSameOrInside(path, c.from as string));
+    if (move) {
+      path = move.path + path.slice((move.from as string).length);
+    }
+
+    result.add(separator === '/' ? path : path.replace(/\//g, '\\'));
+  }
+  return result;
+}
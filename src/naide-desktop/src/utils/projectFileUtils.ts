@@ -22,6 +22,99 @@ export async function readProjectFile(
   }
 }
 
+export type TextEncoding = 'utf-8' | 'utf-8-bom' | 'utf-16le' | 'utf-16be' | 'latin-1' | 'binary';
+
+export interface ReadRangeOptions {
+  offset?: number;
+  length?: number;
+  startLine?: number; // 1-based
+  lineCount?: number;
+  binaryAsBase64?: boolean;
+}
+
+export interface FileReadResult {
+  path: string;
+  size: number;
+  encoding: TextEncoding;
+  is_binary: boolean;
+  mime_type: string | null;
+  content: string | null;
+  base64: string | null;
+  start_byte: number;
+  end_byte: number; // Pass as the next offset to continue reading
+  start_line: number | null;
+  end_line: number | null;
+  eof: boolean;
+  truncated: boolean; // Last line cut at the read limit; continue from end_byte as a byte range
+}
+
+/**
+ * Read part of a project file with encoding and binary detection
+ * @param projectPath - The root project path
+ * @param filePath - Relative path from project root
+ * @param options - Byte range (offset/length) or line range (startLine/lineCount)
+ * @returns Decoded text of the range, or base64 for images
+ */
+export async function readProjectFileRange(
+  projectPath: string,
+  filePath: string,
+  options: ReadRangeOptions = {}
+): Promise<FileReadResult> {
+  try {
+    return await invoke<FileReadResult>('read_project_file_range', {
+      projectPath,
+      filePath,
+      options: {
+        offset: options.offset ?? null,
+        length: options.length ?? null,
+        start_line: options.startLine ?? null,
+        line_count: options.lineCount ?? null,
+        binary_as_base64: options.binaryAsBase64 ?? null,
+      },
+    });
+  } catch (error) {
+    console.error('[projectFileUtils] Error reading file range:', error);
+    throw error;
+  }
+}
+
+export interface ImagePreview {
+  path: string;
+  format: 'png' | 'jpeg' | 'gif' | 'webp' | 'ico' | 'svg';
+  width: number | null;
+  height: number | null;
+  color_type: string | null;
+  has_alpha: boolean;
+  file_size: number;
+  thumbnail: string | null; // Base64 PNG; null for SVG
+  thumbnail_width: number | null;
+  thumbnail_height: number | null;
+  thumbnail_path: string | null;
+}
+
+/**
+ * Get image metadata and a cached thumbnail
+ * @param projectPath - The root project path
+ * @param filePath - Relative path from project root
+ * @param maxSize - Longest thumbnail edge in pixels (default 256)
+ */
+export async function getImagePreview(
+  projectPath: string,
+  filePath: string,
+  maxSize?: number
+): Promise<ImagePreview> {
+  try {
+    return await invoke<ImagePreview>('get_image_preview', {
+      projectPath,
+      filePath,
+      maxSize: maxSize ?? null,
+    });
+  } catch (error) {
+    console.error('[projectFileUtils] Error getting image preview:', error);
+    throw error;
+  }
+}
+
 /**
  * Write content to a project file
  * @param projectPath - The root project path
@@ -41,6 +41,61 @@ export async function listProjectFiles(
   }
 }
 
+export type GitFileStatus = 'modified' | 'added' | 'deleted' | 'renamed' | 'untracked' | 'conflicted';
+
+export interface ProjectTreeEntry {
+  name: string;
+  path: string;               // Relative to the project root, forward slashes
+  depth: number;              // 1 for the direct children of the listed folder
+  is_folder: boolean;
+  file_extension: string | null;
+  size: number | null;        // Files only
+  modified: number | null;    // Milliseconds since epoch
+  readonly: boolean;
+  mode: number | null;        // Unix permission bits
+  is_binary: boolean | null;
+  is_symlink: boolean;
+  symlink_target: string | null;
+  git_status: GitFileStatus | null;
+  has_children: boolean | null; // Folders at the depth limit
+}
+
+export interface ProjectTreePage {
+  entries: ProjectTreeEntry[];
+  next_cursor: string | null;
+}
+
+export interface ProjectTreeOptions {
+  relativePath?: string;
+  maxDepth?: number;   // Default 1 (direct children only)
+  pageSize?: number;   // Default 500
+  cursor?: string;     // next_cursor of the previous page
+}
+
+/**
+ * List a project folder recursively with metadata and git status, one page at a time
+ * @param projectPath - The root project path
+ * @param options - Folder, depth limit and paging
+ * @returns A page of entries in depth-first order and the cursor for the next page
+ */
+export async function getProjectTree(
+  projectPath: string,
+  options: ProjectTreeOptions = {}
+): Promise<ProjectTreePage> {
+  try {
+    return await invoke<ProjectTreePage>('get_project_tree', {
+      projectPath,
+      relativePath: options.relativePath || null,
+      maxDepth: options.maxDepth ?? null,
+      pageSize: options.pageSize ?? null,
+      cursor: options.cursor || null,
+    });
+  } catch (error) {
+    console.error('[projectFiles] Error reading project tree:', error);
+    throw error;
+  }
+}
+
 /**
  * Get the appropriate icon component for a file extension
  * @param extension - File extension (e.g., 'ts', 'json', 'md')
@@ -157,3 +212,38 @@ export function getFileIconColor(extension?: string): string {
   // Default
   return 'text-gray-400';
 }
+
+export type FileOperationKind = 'create_file' | 'create_folder' | 'rename' | 'move' | 'copy' | 'delete';
+
+export interface FileOperation {
+  id: string;
+  kind: FileOperationKind;
+  source: string | null;      // Relative path before the operation
+  target: string | null;      // Relative path after the operation
+  trash_path: string | null;  // Location of a deleted item in .naide/trash
+  is_folder: boolean;
+  timestamp: string;
+}
+
+/**
+ * File operations in the project tree. Every operation is recorded and can be undone;
+ * delete moves the item to .naide/trash.
+ */
+export const fileOperations = {
+  createFile: (projectPath: string, filePath: string, content?: string) =>
+    invoke<FileOperation>('create_project_file', { projectPath, filePath, content: content ?? null }),
+  createFolder: (projectPath: string, folderPath: string) =>
+    invoke<FileOperation>('create_project_folder', { projectPath, folderPath }),
+  rename: (projectPath: string, path: string, newName: string) =>
+    invoke<FileOperation>('rename_project_path', { projectPath, path, newName }),
+  move: (projectPath: string, path: string, targetFolder: string) =>
+    invoke<FileOperation>('move_project_path', { projectPath, path, targetFolder }),
+  copy: (projectPath: string, path: string, targetPath?: string) =>
+    invoke<FileOperation>('copy_project_path', { projectPath, path, targetPath: targetPath ?? null }),
+  delete: (projectPath: string, path: string) =>
+    invoke<FileOperation>('delete_project_path', { projectPath, path }),
+  list: (projectPath: string) =>
+    invoke<FileOperation[]>('list_file_operations', { projectPath }),
+  undo: (projectPath: string, operationId?: string) =>
+    invoke<FileOperation>('undo_file_operation', { projectPath, operationId: operationId ?? null }),
+};
@@ -0,0 +1,70 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+// State of the Copilot sidecar as tracked by the backend supervisor
+// (returned by get_sidecar_status and emitted as sidecar-status events)
+
+export type SidecarPhase = 'starting' | 'running' | 'unhealthy' | 'restarting' | 'failed' | 'stopped';
+
+export interface SidecarStatus {
+  phase: SidecarPhase;
+  url: string | null; // Changes on every start, since each start gets a free port
+  pid: number | null;
+  copilot_ready: boolean;
+  restarts: number; // Automatic restarts since launch
+  consecutive_failures: number;
+  last_error: string | null;
+  started_at: string | null;
+  last_healthy_at: string | null;
+  next_restart_in_ms: number | null;
+}
+
+export interface SidecarLogLine {
+  timestamp: string;
+  stream: 'stdout' | 'stderr' | 'supervisor';
+  line: string;
+}
+
+export async function getSidecarStatus(): Promise<SidecarStatus> {
+  return await invoke<SidecarStatus>('get_sidecar_status');
+}
+
+// Restart now; also the way out of the 'failed' phase
+export async function restartSidecar(): Promise<SidecarStatus> {
+  return await invoke<SidecarStatus>('restart_sidecar');
+}
+
+// Recent sidecar output, oldest first
+export async function getSidecarLogs(limit?: number): Promise<SidecarLogLine[]> {
+  return await invoke<SidecarLogLine[]>('get_sidecar_logs', { limit: limit ?? null });
+}
+
+// Where the sidecar listens and the per-launch token every request must carry
+export interface SidecarConnection {
+  url: string;
+  ws_url: string;
+  port: number;
+  token: string;
+}
+
+export async function getSidecarConnection(): Promise<SidecarConnection> {
+  return await invoke<SidecarConnection>('get_sidecar_connection');
+}
+
+// fetch() against the sidecar API, with its address and token filled in
+export async function sidecarFetch(path: string, init: RequestInit = {}): Promise<Response> {
+  const connection = await getSidecarConnection();
+  const headers = new Headers(init.headers);
+  headers.set('Authorization', `Bearer ${connection.token}`);
+  return await fetch(`${connection.url}${path}`, { ...init, headers });
+}
+
+// Browsers cannot set headers on WebSocket connections, so the token goes in the query string
+export async function sidecarWebSocketUrl(path: string): Promise<string> {
+  const connection = await getSidecarConnection();
+  return `${connection.ws_url}${path}?token=${encodeURIComponent(connection.token)}`;
+}
+
+export async function onSidecarStatus(handler: (status: SidecarStatus) => void): Promise<UnlistenFn> {
+  return await listen<SidecarStatus>('sidecar-status', (event) => handler(event.payload));
+}